@@ -546,6 +546,7 @@ pub struct MpvTrack {
     pub id: i64,
     pub title: String,
     pub lang: String,
+    pub codec: String,
     pub type_: String,
 }
 
@@ -596,6 +597,11 @@ fn node_to_tracks(value: &str) -> MpvTracks {
             .and_then(Value::as_str)
             .unwrap_or("unknown")
             .to_string();
+        let codec = obj
+            .get("codec")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string();
         let type_ = obj
             .get("type")
             .and_then(Value::as_str)
@@ -635,6 +641,7 @@ fn node_to_tracks(value: &str) -> MpvTracks {
             id,
             title,
             lang,
+            codec,
             type_,
         };
 
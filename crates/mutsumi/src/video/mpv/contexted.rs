@@ -37,6 +37,19 @@ use crate::{
     video::{MpvActor, MpvValue, MpvValueType},
 };
 
+/// Playback statistics, `None` when mpv does not know the value yet.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MpvStats {
+    /// Bits per second
+    pub video_bitrate: Option<f64>,
+    /// Bits per second
+    pub audio_bitrate: Option<f64>,
+    pub frame_drop_count: Option<i64>,
+    pub decoder_frame_drop_count: Option<i64>,
+    /// Seconds
+    pub demuxer_cache_duration: Option<f64>,
+}
+
 impl ContextedMPV {
     pub fn shutdown(&self) {
         self.mpv.command("quit", &[]);
@@ -96,6 +109,30 @@ impl ContextedMPV {
         speed
     }
 
+    pub async fn stats(&self) -> MpvStats {
+        MpvStats {
+            video_bitrate: self.f64_property("video-bitrate").await,
+            audio_bitrate: self.f64_property("audio-bitrate").await,
+            frame_drop_count: self.i64_property("frame-drop-count").await,
+            decoder_frame_drop_count: self.i64_property("decoder-frame-drop-count").await,
+            demuxer_cache_duration: self.f64_property("demuxer-cache-duration").await,
+        }
+    }
+
+    async fn f64_property(&self, name: &str) -> Option<f64> {
+        match self.mpv.get_property(name, MpvValueType::F64).await {
+            Ok(MpvValue::F64(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    async fn i64_property(&self, name: &str) -> Option<i64> {
+        match self.mpv.get_property(name, MpvValueType::I64).await {
+            Ok(MpvValue::I64(value)) => Some(value),
+            _ => None,
+        }
+    }
+
     pub fn pause(&self, pause: bool) {
         self.mpv.set_property("pause", pause);
     }
@@ -21,9 +21,9 @@ mod imp {
     use url::Url;
 
     use crate::{
-        client::jellyfin_client::{
-            DEVICE_ID,
-            JELLYFIN_CLIENT,
+        client::{
+            jellyfin_client::DEVICE_ID,
+            registry::CLIENTS,
         },
         ui::{
             SETTINGS,
@@ -97,10 +97,10 @@ mod imp {
         fn startup(&self) {
             self.parent_startup();
 
-            // Eagerly initialize `DEVICE_ID` and `JELLYFIN_CLIENT` because they depend on `SETTINGS`, which can only be accessed from the main thread.
+            // Eagerly initialize `DEVICE_ID` and `CLIENTS` because they depend on `SETTINGS`, which can only be accessed from the main thread.
             // If either is first accessed inside `spawn_tokio`, the application will panic.
             Lazy::force(&DEVICE_ID);
-            Lazy::force(&JELLYFIN_CLIENT);
+            Lazy::force(&CLIENTS);
 
             let window = crate::Window::new(&self.obj());
             window.load_window_state();
@@ -156,6 +156,9 @@ mod imp {
 
     fn configure_mpv() {
         mutsumi::set_mpv_initializer(|init| {
+            // The player applies the overrides of each video's server once it plays
+            let client = CLIENTS.active();
+
             init.set_option("input-vo-keyboard", true)?;
             init.set_option("input-default-bindings", true)?;
 
@@ -164,7 +167,7 @@ mod imp {
                 init.set_option("config-dir", SETTINGS.mpv_config_dir().as_str())?;
             }
 
-            init.set_option("hwdec", match_hwdec_interop(SETTINGS.mpv_hwdec()))?;
+            init.set_option("hwdec", match_hwdec_interop(SETTINGS.mpv_hwdec(&client)))?;
 
             match SETTINGS.mpv_renderer() {
                 1 => {
@@ -186,9 +189,9 @@ mod imp {
             init.set_option("osc", false)?;
             init.set_option("osd-level", 0_i64)?;
 
-            let demuxer_max_bytes = format!("{}MiB", SETTINGS.mpv_cache_size());
+            let demuxer_max_bytes = format!("{}MiB", SETTINGS.mpv_cache_size(&client));
             init.set_option("demuxer-max-bytes", demuxer_max_bytes.as_str())?;
-            init.set_option("cache-secs", SETTINGS.mpv_cache_time() as f64)?;
+            init.set_option("cache-secs", SETTINGS.mpv_cache_time(&client) as f64)?;
             init.set_option("volume-max", MAX_VOLUME)?;
             init.set_option("volume", SETTINGS.mpv_default_volume() as i64)?;
 
@@ -260,19 +263,7 @@ mod imp {
 
             init.set_option(
                 "alang",
-                match SETTINGS.mpv_audio_preferred_lang() {
-                    0 => "",
-                    1 => "eng",
-                    2 => "chs",
-                    3 => "jpn",
-                    4 => "chi",
-                    5 => "ara",
-                    6 => "nob",
-                    7 => "por",
-                    8 => "fre",
-                    9 => "rus",
-                    _ => "",
-                },
+                SETTINGS.mpv_audio_preferred_lang_str(&client).as_str(),
             )?;
 
             Ok(())
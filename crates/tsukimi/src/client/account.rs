@@ -47,6 +47,12 @@ impl Account {
     pub fn url(&self) -> Result<Url> {
         build_url(&self.server, &self.port)
     }
+
+    /// Identifies the account by server and user, stays the same when the
+    /// credentials change.
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.servername, self.user_id)
+    }
 }
 
 pub(super) fn build_url(url_str: &str, port: &str) -> Result<Url> {
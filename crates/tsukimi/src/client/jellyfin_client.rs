@@ -63,25 +63,39 @@ use super::{
     ReqClient,
     error::UserFacingError,
     picture_source::PictureSource,
+    registry::CLIENTS,
     structs::{
         ActivityLogs,
         Back,
+        ChapterItem,
+        Culture,
         DeleteInfo,
         ExternalIdInfo,
         FilterList,
         ImageItem,
         ImageSearchResult,
+        ItemEditInfo,
+        LibraryOptions,
         List,
+        LogFile,
         LoginResponse,
         Media,
+        MediaSegment,
         MediaSegmentList,
+        MediaSegmentType,
         MissingEpisodesList,
+        PackageInfo,
+        ParentalRating,
+        PluginInfo,
+        Policy,
         PublicServerInfo,
         RemoteSearchInfo,
         ScheduledTask,
         ServerInfo,
+        SessionInfo,
         SimpleListItem,
         User,
+        VirtualFolder,
     },
 };
 use crate::{
@@ -89,16 +103,14 @@ use crate::{
     config::version,
     ui::{
         SETTINGS,
-        jellyfin_cache_path,
+        server_cache_path,
         widgets::{
             filter_panel::FiltersList,
             single_grid::imp::ListType,
         },
     },
-    utils::spawn_tokio_without_await,
 };
 
-pub static JELLYFIN_CLIENT: Lazy<JellyfinClient> = Lazy::new(JellyfinClient::default);
 pub static DEVICE_ID: Lazy<String> = Lazy::new(|| {
     let uuid = SETTINGS.device_uuid();
     if uuid.is_empty() {
@@ -111,6 +123,7 @@ pub static DEVICE_ID: Lazy<String> = Lazy::new(|| {
 });
 
 const PROFILE: &str = include_str!("stream_profile.json");
+const MEDIA_SEGMENTS_PLUGIN_NAME: &str = "MediaSegmentsApi";
 
 static DEVICE_NAME: Lazy<String> = Lazy::new(|| {
     hostname::get()
@@ -141,6 +154,26 @@ impl Session {
             server_name_hash: String::new(),
         }
     }
+
+    fn for_account(account: &Account) -> Result<Self> {
+        let server_type = account.server_type.unwrap_or_default();
+        let headers = build_headers(
+            Some(&account.user_id),
+            Some(&account.access_token),
+            server_type,
+        )?;
+        let url = build_base_url(account.url()?, server_type)?;
+        Ok(Self {
+            account: account.clone(),
+            url_headers: Some((url, headers)),
+            server_name_hash: generate_hash(&account.servername),
+        })
+    }
+
+    /// Key of the signed in account, for anything stored per account.
+    pub fn account_key(&self) -> String {
+        self.account.key()
+    }
 }
 
 pub struct JellyfinClient {
@@ -206,11 +239,12 @@ fn generate_hash(s: &str) -> String {
     format!("{:x}", hasher.finish())
 }
 
-impl Default for JellyfinClient {
-    fn default() -> Self {
+impl JellyfinClient {
+    /// A client without a session, only good for logging in.
+    pub fn new(threads: usize) -> Self {
         Self {
             session: ArcSwap::from_pointee(Session::empty()),
-            semaphore: tokio::sync::Semaphore::new(SETTINGS.threads() as usize),
+            semaphore: tokio::sync::Semaphore::new(threads),
             client: ReqClient::build(),
             next_up_date_cache: Cache::builder()
                 .max_capacity(256)
@@ -234,33 +268,14 @@ impl JellyfinClient {
         matches!(self.server_type(), ServerType::Jellyfin)
     }
 
-    pub async fn init(&self, account: &Account) -> Result<(), Box<dyn std::error::Error>> {
-        let server_type = account.server_type.unwrap_or_default();
-        let headers = build_headers(
-            Some(&account.user_id),
-            Some(&account.access_token),
-            server_type,
-        )?;
-        let url = build_base_url(account.url()?, server_type)?;
-        self.session.store(Arc::new(Session {
-            account: account.clone(),
-            url_headers: Some((url, headers)),
-            server_name_hash: generate_hash(&account.servername),
-        }));
-        self.next_up_date_cache.invalidate_all();
-
-        crate::ui::provider::set_admin(false);
-        spawn_tokio_without_await(async move {
-            match JELLYFIN_CLIENT.get_current_user().await {
-                Ok(r) => {
-                    if r.policy.is_administrator {
-                        crate::ui::provider::set_admin(true);
-                    }
-                }
-                Err(e) => warn!("Failed to authenticate as admin: {}", e),
-            }
-        });
-        Ok(())
+    /// A client bound to `account`. Use [`CLIENTS`](super::registry::CLIENTS)
+    /// to share it instead of creating one per request.
+    pub fn for_account(account: &Account, threads: usize) -> Result<Self> {
+        let client = Self::new(threads);
+        client
+            .session
+            .store(Arc::new(Session::for_account(account)?));
+        Ok(client)
     }
 
     pub async fn request<T>(&self, path: &str, params: &[(&str, &str)]) -> Result<T>
@@ -467,6 +482,35 @@ impl JellyfinClient {
         self.request(&path, &params).await
     }
 
+    /// Searches one of the name endpoints: `Persons`, `Artists`, `Studios` or `Genres`.
+    pub async fn search_names(&self, endpoint: &str, query: &str) -> Result<List> {
+        let s = self.session();
+        let params = [
+            ("SearchTerm", query),
+            ("UserId", &s.account.user_id),
+            ("Fields", "PrimaryImageAspectRatio"),
+            ("EnableImageTypes", "Primary,Thumb"),
+            ("ImageTypeLimit", "1"),
+            ("Recursive", "true"),
+            ("Limit", "30"),
+        ];
+        self.request(endpoint, &params).await
+    }
+
+    pub async fn search_hints(&self, query: &str) -> Result<SearchHintResult> {
+        let s = self.session();
+        let params = [
+            ("SearchTerm", query),
+            ("UserId", &s.account.user_id),
+            (
+                "IncludeItemTypes",
+                "Movie,Series,BoxSet,Episode,MusicAlbum,MusicArtist,Audio,Person",
+            ),
+            ("Limit", "8"),
+        ];
+        self.request("Search/Hints", &params).await
+    }
+
     pub async fn get_episodes(&self, id: &str, season_id: &str, start_index: u32) -> Result<List> {
         let s = self.session();
         let path = format!("Shows/{id}/Episodes");
@@ -506,16 +550,17 @@ impl JellyfinClient {
         self.request(&path, &params).await
     }
 
-    pub async fn get_edit_info(&self, id: &str) -> Result<Value> {
+    pub async fn get_edit_info(&self, id: &str) -> Result<ItemEditInfo> {
         let s = self.session();
         let path = format!("Users/{}/Items/{}", s.account.user_id, id);
         let params = [("Fields", "ChannelMappingInfo")];
         self.request(&path, &params).await
     }
 
-    pub async fn post_item(&self, id: &str, body: Value) -> Result<Response> {
+    pub async fn post_item(&self, id: &str, info: &ItemEditInfo) -> Result<()> {
         let path = format!("Items/{id}");
-        self.post(&path, &[], body).await
+        self.post(&path, &[], info).await?.error_for_status()?;
+        Ok(())
     }
 
     pub async fn get_resume(&self, limit: u32) -> Result<List> {
@@ -569,10 +614,32 @@ impl JellyfinClient {
         if !self.is_jellyfin() {
             bail!("Next up is not supported on Emby");
         }
-        let (resume, next_up) = tokio::try_join!(
-            self.get_resume(limit),
-            self.get_next_up(limit, next_up_date_cutoff)
-        )?;
+        let items = self
+            .get_continue_watching_dated(limit, next_up_date_cutoff)
+            .await?
+            .into_iter()
+            .map(|(_, item)| item)
+            .collect::<Vec<_>>();
+
+        Ok(List {
+            total_record_count: items.len() as u32,
+            items,
+        })
+    }
+
+    /// Resume items, plus next up on Jellyfin, newest first and paired with
+    /// the date they were last played.
+    async fn get_continue_watching_dated(
+        &self, limit: u32, next_up_date_cutoff: &str,
+    ) -> Result<Vec<(DateTime<Utc>, SimpleListItem)>> {
+        let (resume, next_up) = if self.is_jellyfin() {
+            tokio::try_join!(
+                self.get_resume(limit),
+                self.get_next_up(limit, next_up_date_cutoff)
+            )?
+        } else {
+            (self.get_resume(limit).await?, List::default())
+        };
 
         let date_futures = next_up
             .items
@@ -585,11 +652,12 @@ impl JellyfinClient {
             .collect::<HashMap<_, _>>()
             .await;
 
-        let mut items = resume.items;
-        items.extend(next_up.items);
-        items.sort_by_key(|item| {
-            Reverse(
-                next_up_dates
+        let mut items = resume
+            .items
+            .into_iter()
+            .chain(next_up.items)
+            .map(|item| {
+                let date = next_up_dates
                     .get(&item.id)
                     .copied()
                     .or_else(|| {
@@ -597,15 +665,56 @@ impl JellyfinClient {
                             .as_ref()
                             .and_then(|user_data| user_data.last_played_date)
                     })
-                    .unwrap_or(DateTime::<Utc>::MIN_UTC),
-            )
-        });
+                    .unwrap_or(DateTime::<Utc>::MIN_UTC);
+                (date, item)
+            })
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(date, _)| Reverse(*date));
         items.truncate(limit as usize);
 
-        Ok(List {
-            total_record_count: items.len() as u32,
-            items,
-        })
+        Ok(items)
+    }
+
+    /// Continue watching on every configured account, merged by last played
+    /// date and paired with the client of their account. Servers that fail are
+    /// skipped and returned by name.
+    pub async fn get_all_servers_continue_watching(
+        clients: Vec<Arc<JellyfinClient>>, limit: u32,
+    ) -> (Vec<(SimpleListItem, Arc<JellyfinClient>)>, Vec<String>) {
+        let next_up_date_cutoff = CLIENTS.active().next_up_date_cutoff();
+        let results = futures_util::future::join_all(clients.into_iter().map(|client| {
+            let next_up_date_cutoff = next_up_date_cutoff.to_owned();
+            let servername = client.session().account.servername.to_owned();
+            async move {
+                let result = async {
+                    let items = client
+                        .get_continue_watching_dated(limit, &next_up_date_cutoff)
+                        .await?
+                        .into_iter()
+                        .map(|(date, item)| (date, (item, client.clone())))
+                        .collect::<Vec<_>>();
+                    Ok::<_, anyhow::Error>(items)
+                }
+                .await;
+                (servername, result)
+            }
+        }))
+        .await;
+
+        let mut items = Vec::new();
+        let mut failed = Vec::new();
+        for (servername, result) in results {
+            match result {
+                Ok(dated) => items.extend(dated),
+                Err(e) => {
+                    warn!("Failed to get continue watching of {}: {}", servername, e);
+                    failed.push(servername);
+                }
+            }
+        }
+        items.sort_by_key(|(date, _)| Reverse(*date));
+        items.truncate(limit as usize);
+        (items.into_iter().map(|(_, item)| item).collect(), failed)
     }
 
     async fn get_next_up_date(&self, item: &SimpleListItem) -> Option<DateTime<Utc>> {
@@ -653,7 +762,8 @@ impl JellyfinClient {
     }
 
     pub async fn get_image(&self, source: PictureSource) -> Result<PathBuf> {
-        let mut path = jellyfin_cache_path().await;
+        let server_name_hash = self.session().server_name_hash.to_owned();
+        let mut path = server_cache_path(&server_name_hash).await;
         path.push(source.cache_key());
 
         if tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_file()) {
@@ -666,6 +776,7 @@ impl JellyfinClient {
                 tag,
                 image_type,
                 image_index,
+                ..
             } => {
                 let mut request_path = format!("Items/{id}/Images/{image_type}");
                 if let Some(image_index) = image_index {
@@ -799,16 +910,71 @@ impl JellyfinClient {
 
     pub async fn get_skippable_segments(&self, id: &str) -> Result<MediaSegmentList> {
         if !self.is_jellyfin() {
-            bail!("Skippable segments are not supported on Emby");
+            return self.get_chapter_marker_segments(id).await;
         }
         let path = format!("MediaSegments/{id}");
-        let params = [
-            ("includeSegmentTypes", "Intro"),
-            ("includeSegmentTypes", "Outro"),
-        ];
+        let params = MediaSegmentType::SKIPPABLE
+            .iter()
+            .map(|segment_type| ("includeSegmentTypes", segment_type.as_str()))
+            .collect::<Vec<_>>();
         self.request(&path, &params).await
     }
 
+    /// Provider id to write segments with, fails when the MediaSegments API
+    /// plugin isn't installed and active.
+    ///
+    /// Jellyfin core only exposes reading segments, writing goes through the
+    /// plugin.
+    pub async fn media_segments_provider_id(&self) -> Result<String> {
+        self.get_plugins()
+            .await?
+            .into_iter()
+            .find(|plugin| {
+                plugin.status == "Active"
+                    && plugin
+                        .name
+                        .replace(' ', "")
+                        .eq_ignore_ascii_case(MEDIA_SEGMENTS_PLUGIN_NAME)
+            })
+            .map(|plugin| plugin.id)
+            .ok_or_else(|| anyhow!("The MediaSegments API plugin is not installed"))
+    }
+
+    /// Replace the segments of the same type on `item_id` with `segment`,
+    /// see [`Self::media_segments_provider_id`].
+    ///
+    /// The new segment is saved first, so a failure leaves the old ones.
+    pub async fn replace_media_segment(
+        &self, item_id: &str, segment: &MediaSegment, provider_id: &str,
+    ) -> Result<()> {
+        let path = format!("MediaSegments/{item_id}");
+        let params = [("includeSegmentTypes", segment.segment_type.as_str())];
+        let existing: MediaSegmentList = self.request(&path, &params).await?;
+
+        let mut segment = segment.to_owned();
+        segment.id = None;
+        segment.item_id = Some(item_id.to_owned());
+        let path = format!("MediaSegmentsApi/{item_id}");
+        let params = [("providerId", provider_id)];
+        self.post(&path, &params, &segment)
+            .await?
+            .error_for_status()?;
+
+        for id in existing.items.iter().filter_map(|s| s.id.as_deref()) {
+            let path = format!("MediaSegmentsApi/{id}");
+            self.delete(&path, &[]).await?.error_for_status()?;
+        }
+        Ok(())
+    }
+
+    async fn get_chapter_marker_segments(&self, id: &str) -> Result<MediaSegmentList> {
+        let s = self.session();
+        let path = format!("Users/{}/Items/{}", s.account.user_id, id);
+        let params = [("Fields", "Chapters")];
+        let item: ChapterItem = self.request(&path, &params).await?;
+        Ok(item.marker_segments())
+    }
+
     pub async fn scan(&self, id: &str) -> Result<Response> {
         let path = format!("Items/{id}/Refresh");
         let params = [
@@ -884,6 +1050,39 @@ impl JellyfinClient {
         self.request(&path, &params).await
     }
 
+    /// Items of a pinned home row, `params` come from `PinnedSource::params`.
+    pub async fn get_pinned_items(
+        &self, params: &[(String, String)],
+    ) -> Result<Vec<SimpleListItem>> {
+        let s = self.session();
+        let path = format!("Users/{}/Items", s.account.user_id);
+        let mut query = vec![
+            ("Limit", "16"),
+            (
+                "Fields",
+                "BasicSyncInfo,CanDelete,PrimaryImageAspectRatio,ProductionYear,CommunityRating",
+            ),
+            ("ImageTypeLimit", "1"),
+            ("EnableImageTypes", "Primary,Backdrop,Thumb,Banner"),
+        ];
+        params.iter().for_each(|(k, v)| {
+            query.push((k.as_str(), v.as_str()));
+        });
+        let list: List = self.request(&path, &query).await?;
+        Ok(list.items)
+    }
+
+    pub async fn get_genres(&self) -> Result<List> {
+        let s = self.session();
+        let params = [
+            ("UserId", s.account.user_id.as_str()),
+            ("Recursive", "true"),
+            ("SortBy", "SortName"),
+            ("SortOrder", "Ascending"),
+        ];
+        self.request("Genres", &params).await
+    }
+
     pub async fn get_streaming_url(&self, path: &str) -> String {
         let s = self.session();
         let (url, _) = s.url_headers.as_ref().expect("Client not initialized");
@@ -993,7 +1192,7 @@ impl JellyfinClient {
             ("EnableImageTypes", "Primary,Backdrop,Thumb,Banner"),
             if listtype == "Genres" || listtype == "Genre" {
                 ("GenreIds", parentid)
-            } else if listtype == "Studios" {
+            } else if listtype == "Studios" || listtype == "Studio" {
                 ("StudioIds", parentid)
             } else {
                 ("TagIds", parentid)
@@ -1324,6 +1523,196 @@ impl JellyfinClient {
         Ok(())
     }
 
+    pub async fn get_users(&self) -> Result<Vec<User>> {
+        self.request("Users", &[]).await
+    }
+
+    pub async fn create_user(&self, name: &str, password: &str) -> Result<User> {
+        let body = json!({
+            "Name": name,
+            "Password": password
+        });
+        let user: User = self.post_json("Users/New", &[], body).await?;
+        // Emby ignores the password on creation
+        if !password.is_empty() && !self.is_jellyfin() {
+            self.reset_user_password(&user.id, password).await?;
+        }
+        Ok(user)
+    }
+
+    pub async fn delete_user(&self, id: &str) -> Result<()> {
+        let path = format!("Users/{id}");
+        self.delete(&path, &[]).await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn reset_user_password(&self, id: &str, new_password: &str) -> Result<()> {
+        let path = format!("Users/{id}/Password");
+        self.post(&path, &[], json!({ "ResetPassword": true }))
+            .await?
+            .error_for_status()?;
+        if new_password.is_empty() {
+            return Ok(());
+        }
+        let body = json!({
+            "CurrentPw": "",
+            "NewPw": new_password
+        });
+        self.post(&path, &[], body).await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn update_user_policy(&self, id: &str, policy: &Policy) -> Result<()> {
+        let path = format!("Users/{id}/Policy");
+        self.post(&path, &[], policy).await?.error_for_status()?;
+        Ok(())
+    }
+
+    // Only support base64 encoded images
+    pub async fn post_user_image<B>(&self, id: &str, bytes: B, content_type: &str) -> Result<()>
+    where
+        reqwest::Body: From<B>,
+    {
+        let path = format!("Users/{id}/Images/Primary");
+        self.post_raw(&path, bytes, content_type)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn get_media_folders(&self) -> Result<List> {
+        self.request("Library/MediaFolders", &[("IsHidden", "false")])
+            .await
+    }
+
+    pub async fn get_parental_ratings(&self) -> Result<Vec<ParentalRating>> {
+        self.request("Localization/ParentalRatings", &[]).await
+    }
+
+    pub async fn get_virtual_folders(&self) -> Result<Vec<VirtualFolder>> {
+        self.request("Library/VirtualFolders", &[]).await
+    }
+
+    pub async fn add_virtual_folder(
+        &self, name: &str, collection_type: Option<&str>, path: &str,
+    ) -> Result<()> {
+        let mut params = vec![("name", name), ("refreshLibrary", "true")];
+        if let Some(collection_type) = collection_type {
+            params.push(("collectionType", collection_type));
+        }
+        if !path.is_empty() {
+            params.push(("paths", path));
+        }
+        // Let the server fill in its default library options
+        self.post("Library/VirtualFolders", &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn remove_virtual_folder(&self, name: &str) -> Result<()> {
+        let params = [("name", name), ("refreshLibrary", "true")];
+        self.delete("Library/VirtualFolders", &params)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn rename_virtual_folder(&self, name: &str, new_name: &str) -> Result<()> {
+        let params = [
+            ("name", name),
+            ("newName", new_name),
+            ("refreshLibrary", "true"),
+        ];
+        self.post("Library/VirtualFolders/Name", &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn add_media_path(&self, name: &str, path: &str) -> Result<()> {
+        let body = json!({
+            "Name": name,
+            "PathInfo": { "Path": path }
+        });
+        self.post(
+            "Library/VirtualFolders/Paths",
+            &[("refreshLibrary", "true")],
+            body,
+        )
+        .await?
+        .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn remove_media_path(&self, name: &str, path: &str) -> Result<()> {
+        let params = [("name", name), ("path", path), ("refreshLibrary", "true")];
+        self.delete("Library/VirtualFolders/Paths", &params)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn update_library_options(&self, id: &str, options: &LibraryOptions) -> Result<()> {
+        let body = json!({
+            "Id": id,
+            "LibraryOptions": options
+        });
+        self.post("Library/VirtualFolders/LibraryOptions", &[], body)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn refresh_libraries(&self) -> Result<()> {
+        self.post("Library/Refresh", &[], json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn get_cultures(&self) -> Result<Vec<Culture>> {
+        self.request("Localization/Cultures", &[]).await
+    }
+
+    pub async fn get_plugins(&self) -> Result<Vec<PluginInfo>> {
+        self.request("Plugins", &[]).await
+    }
+
+    pub async fn set_plugin_enabled(&self, id: &str, version: &str, enabled: bool) -> Result<()> {
+        let action = if enabled { "Enable" } else { "Disable" };
+        let path = format!("Plugins/{id}/{version}/{action}");
+        self.post(&path, &[], json!({})).await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn uninstall_plugin(&self, id: &str, version: &str) -> Result<()> {
+        let path = format!("Plugins/{id}/{version}");
+        self.delete(&path, &[]).await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn get_packages(&self) -> Result<Vec<PackageInfo>> {
+        self.request("Packages", &[]).await
+    }
+
+    // The server picks the newest version compatible with itself
+    pub async fn install_package(&self, package: &PackageInfo) -> Result<()> {
+        let path = format!("Packages/Installed/{}", package.name);
+        let mut params = vec![("assemblyGuid", package.guid.as_str())];
+        if let Some(repository_url) = package
+            .versions
+            .first()
+            .and_then(|version| version.repository_url.as_deref())
+        {
+            params.push(("repositoryUrl", repository_url));
+        }
+        self.post(&path, &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
     pub async fn hide_from_resume<T: Into<String>>(
         &self, id: &str, series_id: Option<T>,
     ) -> Result<()> {
@@ -1352,6 +1741,25 @@ impl JellyfinClient {
         self.request(&path, &params).await
     }
 
+    pub async fn get_artist_songs(&self, artist_id: &str) -> Result<List> {
+        let s = self.session();
+        let path = format!("Users/{}/Items", s.account.user_id);
+        let params = [
+            (
+                "Fields",
+                "BasicSyncInfo,CanDelete,PrimaryImageAspectRatio,SyncStatus",
+            ),
+            ("IncludeItemTypes", "Audio"),
+            ("Recursive", "true"),
+            ("ImageTypeLimit", "1"),
+            ("ArtistIds", artist_id),
+            ("SortBy", "Album,ParentIndexNumber,IndexNumber,SortName"),
+            ("SortOrder", "Ascending"),
+            ("EnableTotalRecordCount", "false"),
+        ];
+        self.request(&path, &params).await
+    }
+
     pub async fn get_song_streaming_uri(&self, id: &str) -> String {
         let s = self.session();
         let (url, _) = s.url_headers.as_ref().expect("Client not initialized");
@@ -1457,6 +1865,130 @@ impl JellyfinClient {
         self.request("System/ActivityLog/Entries", &params).await
     }
 
+    pub async fn get_sessions(&self) -> Result<Vec<SessionInfo>> {
+        self.request("Sessions", &[("ActiveWithinSeconds", "960")])
+            .await
+    }
+
+    pub async fn send_session_message(&self, id: &str, header: &str, text: &str) -> Result<()> {
+        let path = format!("Sessions/{id}/Message");
+        let body = json!({
+            "Header": header,
+            "Text": text,
+            "TimeoutMs": 10000
+        });
+        self.post(&path, &[], body).await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn stop_session_playback(&self, id: &str) -> Result<()> {
+        let path = format!("Sessions/{id}/Playing/Stop");
+        self.post(&path, &[], json!({})).await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn kill_transcode(&self, device_id: &str, play_session_id: &str) -> Result<()> {
+        let params = [("deviceId", device_id), ("playSessionId", play_session_id)];
+        self.delete("Videos/ActiveEncodings", &params)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn get_controllable_sessions(&self) -> Result<Vec<SessionInfo>> {
+        let user_id = self.session().account.user_id.to_owned();
+        let sessions: Vec<SessionInfo> = self
+            .request(
+                "Sessions",
+                &[
+                    ("ControllableByUserId", &user_id),
+                    ("ActiveWithinSeconds", "960"),
+                ],
+            )
+            .await?;
+        Ok(sessions
+            .into_iter()
+            .filter(|session| session.device_id.as_deref() != Some(DEVICE_ID.as_str()))
+            .collect())
+    }
+
+    pub async fn play_on_session(
+        &self, session_id: &str, item_ids: &[String], start_index: usize, start_ticks: i64,
+        media_source_id: Option<&str>, subtitle_stream_index: Option<i64>,
+    ) -> Result<()> {
+        let path = format!("Sessions/{session_id}/Playing");
+        let item_ids = item_ids.join(",");
+        let start_index = start_index.to_string();
+        let start_ticks = start_ticks.to_string();
+        let subtitle_stream_index = subtitle_stream_index.map(|index| index.to_string());
+
+        let mut params = vec![
+            ("PlayCommand", "PlayNow"),
+            ("ItemIds", item_ids.as_str()),
+            ("StartIndex", start_index.as_str()),
+            ("StartPositionTicks", start_ticks.as_str()),
+        ];
+        if let Some(media_source_id) = media_source_id {
+            params.push(("MediaSourceId", media_source_id));
+        }
+        if let Some(subtitle_stream_index) = &subtitle_stream_index {
+            params.push(("SubtitleStreamIndex", subtitle_stream_index));
+        }
+
+        self.post(&path, &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn send_playstate_command(
+        &self, session_id: &str, command: &str, seek_ticks: Option<i64>,
+    ) -> Result<()> {
+        let path = format!("Sessions/{session_id}/Playing/{command}");
+        let seek_ticks = seek_ticks.map(|ticks| ticks.to_string());
+        let mut params = Vec::new();
+        if let Some(seek_ticks) = &seek_ticks {
+            params.push(("SeekPositionTicks", seek_ticks.as_str()));
+        }
+        self.post(&path, &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn send_general_command(
+        &self, session_id: &str, name: &str, arguments: &[(&str, String)],
+    ) -> Result<()> {
+        let path = format!("Sessions/{session_id}/Command");
+        let arguments: HashMap<&str, &str> = arguments
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+        let body = json!({
+            "Name": name,
+            "Arguments": arguments,
+        });
+        self.post(&path, &[], body).await?.error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn get_log_files(&self) -> Result<Vec<LogFile>> {
+        let mut files: Vec<LogFile> = self.request("System/Logs", &[]).await?;
+        files.sort_by_key(|file| Reverse(file.date_modified));
+        Ok(files)
+    }
+
+    pub async fn get_log_file(&self, name: &str) -> Result<String> {
+        let request = self.prepare_request(Method::GET, "System/Logs/Log", &[("name", name)])?;
+        let text = self
+            .send_request(request)
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(text)
+    }
+
     pub async fn get_scheduled_tasks(&self) -> Result<Vec<ScheduledTask>> {
         self.request("ScheduledTasks", &[]).await
     }
@@ -1538,6 +2070,68 @@ impl JellyfinClient {
             .await
     }
 
+    /// Playlists or collections (`BoxSet`) owned by the current user
+    pub async fn get_user_containers(&self, item_type: &str) -> Result<List> {
+        let s = self.session();
+        let path = format!("Users/{}/Items", s.account.user_id);
+        let params = [
+            ("IncludeItemTypes", item_type),
+            ("Recursive", "true"),
+            ("SortBy", "SortName"),
+            ("SortOrder", "Ascending"),
+            ("ImageTypeLimit", "1"),
+        ];
+        self.request(&path, &params).await
+    }
+
+    pub async fn add_to_playlist(&self, playlist_id: &str, ids: &[String]) -> Result<()> {
+        let s = self.session();
+        let path = format!("Playlists/{playlist_id}/Items");
+        let ids = ids.join(",");
+        let params = [("Ids", ids.as_str()), ("UserId", &s.account.user_id)];
+        self.post(&path, &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// `media_type` is `Video` or `Audio`
+    pub async fn create_playlist(
+        &self, name: &str, ids: &[String], media_type: &str,
+    ) -> Result<()> {
+        let s = self.session();
+        let ids = ids.join(",");
+        let params = [
+            ("Name", name),
+            ("Ids", ids.as_str()),
+            ("UserId", &s.account.user_id),
+            ("MediaType", media_type),
+        ];
+        self.post("Playlists", &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn add_to_collection(&self, collection_id: &str, ids: &[String]) -> Result<()> {
+        let path = format!("Collections/{collection_id}/Items");
+        let ids = ids.join(",");
+        let params = [("Ids", ids.as_str())];
+        self.post(&path, &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn create_collection(&self, name: &str, ids: &[String]) -> Result<()> {
+        let ids = ids.join(",");
+        let params = [("Name", name), ("Ids", ids.as_str())];
+        self.post("Collections", &params, json!({}))
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
     pub async fn filters(&self, type_: &str) -> Result<FilterList> {
         let s = self.session();
         let params = [
@@ -1566,7 +2160,8 @@ mod tests {
 
     #[tokio::test]
     async fn search() {
-        let result = JELLYFIN_CLIENT
+        let mut client = JellyfinClient::new(1);
+        let result = client
             .login(
                 "https://example.com",
                 "443",
@@ -1588,14 +2183,14 @@ mod tests {
                     access_token: response.access_token,
                     server_type: Some(ServerType::Jellyfin),
                 };
-                let _ = JELLYFIN_CLIENT.init(&account).await;
+                client = JellyfinClient::for_account(&account, 1).unwrap();
             }
             Err(e) => {
                 eprintln!("{}", e.to_user_facing());
             }
         }
         let filters_list = FiltersList::default();
-        let result = JELLYFIN_CLIENT.search("你的名字", &["Movie"], "0", &filters_list);
+        let result = client.search("你的名字", &["Movie"], "0", &filters_list);
         match result.await {
             Ok(items) => {
                 for item in items.items {
@@ -1622,7 +2217,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_upload_image() {
-        let result = JELLYFIN_CLIENT
+        let mut client = JellyfinClient::new(1);
+        let result = client
             .login(
                 "http://127.0.0.1",
                 "8096",
@@ -1644,7 +2240,7 @@ mod tests {
                     access_token: response.access_token,
                     server_type: Some(ServerType::Jellyfin),
                 };
-                let _ = JELLYFIN_CLIENT.init(&account).await;
+                client = JellyfinClient::for_account(&account, 1).unwrap();
             }
             Err(e) => {
                 eprintln!("{}", e.to_user_facing());
@@ -1657,10 +2253,7 @@ mod tests {
             engine::general_purpose::STANDARD,
         };
         let image = STANDARD.encode(&image);
-        match JELLYFIN_CLIENT
-            .post_image("293", "Thumb", image, "image/jpeg")
-            .await
-        {
+        match client.post_image("293", "Thumb", image, "image/jpeg").await {
             Ok(_) => {
                 println!("success");
             }
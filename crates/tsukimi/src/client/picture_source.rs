@@ -8,6 +8,9 @@ pub enum PictureSource {
         tag: String,
         image_type: &'static str,
         image_index: Option<u8>,
+        /// Key of the account the image is fetched with, see
+        /// [`Account::key`](super::Account::key). `None` for the active one.
+        account: Option<String>,
     },
     User {
         id: String,
@@ -26,9 +29,25 @@ impl PictureSource {
                 tag,
                 image_type,
                 image_index,
+                ..
             } => format!("{}-{}-{}-{}", id, image_type, image_index.unwrap_or(0), tag),
             Self::User { id, tag } => format!("{id}-Primary-0-{tag}"),
             Self::Url { .. } => unreachable!(),
         }
     }
+
+    pub fn account(&self) -> Option<&str> {
+        match self {
+            Self::Item { account, .. } => account.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Fetches the image with the account of `key` rather than the active one.
+    pub fn for_account(mut self, key: Option<String>) -> Self {
+        if let Self::Item { account, .. } = &mut self {
+            *account = key;
+        }
+        self
+    }
 }
@@ -0,0 +1,100 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use once_cell::sync::Lazy;
+use tracing::warn;
+
+use super::{
+    Account,
+    jellyfin_client::JellyfinClient,
+};
+use crate::{
+    ui::SETTINGS,
+    utils::spawn_tokio_without_await,
+};
+
+pub static CLIENTS: Lazy<ClientRegistry> = Lazy::new(ClientRegistry::default);
+
+/// Owns one [`JellyfinClient`] per signed in account.
+///
+/// Switching accounts only changes which client is active. Requests that are
+/// already running keep talking to the server they were started against, and
+/// several servers can be used at the same time.
+pub struct ClientRegistry {
+    clients: RwLock<HashMap<String, Arc<JellyfinClient>>>,
+    active: ArcSwap<JellyfinClient>,
+    // `SETTINGS` can only be read on the main thread, clients may be created elsewhere
+    threads: usize,
+}
+
+impl Default for ClientRegistry {
+    fn default() -> Self {
+        let threads = SETTINGS.threads() as usize;
+        Self {
+            clients: RwLock::new(HashMap::new()),
+            active: ArcSwap::from_pointee(JellyfinClient::new(threads)),
+            threads,
+        }
+    }
+}
+
+impl ClientRegistry {
+    /// Client of the account that is currently shown.
+    pub fn active(&self) -> Arc<JellyfinClient> {
+        self.active.load_full()
+    }
+
+    /// Client of `account`, created on first use.
+    pub fn get(&self, account: &Account) -> Result<Arc<JellyfinClient>> {
+        let key = account.key();
+        if let Some(client) = self.clients.read().unwrap().get(&key)
+            && client.session().account == *account
+        {
+            return Ok(client.clone());
+        }
+
+        // Account is new or its credentials changed
+        let client = Arc::new(JellyfinClient::for_account(account, self.threads)?);
+        self.clients.write().unwrap().insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Client of the account with `key`, see [`Account::key`]. `None` once the
+    /// account was removed.
+    pub fn get_by_key(&self, key: &str) -> Option<Arc<JellyfinClient>> {
+        self.clients.read().unwrap().get(key).cloned()
+    }
+
+    /// Makes the client of `account` the active one.
+    pub fn activate(&self, account: &Account) -> Result<Arc<JellyfinClient>> {
+        let client = self.get(account)?;
+        self.active.store(client.clone());
+
+        crate::ui::provider::set_admin(false);
+        let admin_client = client.clone();
+        spawn_tokio_without_await(async move {
+            match admin_client.get_current_user().await {
+                Ok(r) => {
+                    // Another account may have been activated meanwhile
+                    if r.policy.is_administrator && Arc::ptr_eq(&CLIENTS.active(), &admin_client) {
+                        crate::ui::provider::set_admin(true);
+                    }
+                }
+                Err(e) => warn!("Failed to authenticate as admin: {}", e),
+            }
+        });
+        Ok(client)
+    }
+
+    /// Drops the client of a removed account.
+    pub fn remove(&self, account: &Account) {
+        self.clients.write().unwrap().remove(&account.key());
+    }
+}
@@ -8,7 +8,10 @@ use serde::{
     Serialize,
 };
 use std::{
-    collections::HashMap,
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
     fmt,
 };
 
@@ -16,6 +19,26 @@ use std::{
 pub struct Policy {
     #[serde(rename = "IsAdministrator")]
     pub is_administrator: bool,
+    #[serde(rename = "IsDisabled", default)]
+    pub is_disabled: bool,
+    #[serde(rename = "EnableRemoteAccess", default)]
+    pub enable_remote_access: bool,
+    #[serde(rename = "EnableAllFolders", default)]
+    pub enable_all_folders: bool,
+    #[serde(rename = "EnabledFolders", default)]
+    pub enabled_folders: Vec<String>,
+    #[serde(rename = "RemoteClientBitrateLimit", default)]
+    pub remote_client_bitrate_limit: i64,
+    #[serde(
+        rename = "MaxParentalRating",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_parental_rating: Option<i32>,
+    // Payloads the server replaces as a whole on update keep the fields we
+    // don't edit in `other`, like `LibraryOptions` and `ItemEditInfo` do
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
 }
 
 // media info
@@ -51,6 +74,8 @@ pub struct MediaStream {
     pub pixel_format: Option<String>,
     #[serde(rename = "ColorSpace")]
     pub color_space: Option<String>,
+    #[serde(rename = "VideoRangeType")]
+    pub video_range_type: Option<String>,
     #[serde(rename = "SampleRate")]
     pub sample_rate: Option<u64>,
     #[serde(rename = "Channels")]
@@ -81,6 +106,9 @@ pub struct MediaSource {
     pub direct_stream_url: Option<String>,
     #[serde(rename = "TranscodingUrl")]
     pub transcoding_url: Option<String>,
+    /// A list on Jellyfin 10.9+, a comma separated string before
+    #[serde(rename = "TranscodeReasons")]
+    pub transcode_reasons: Option<serde_json::Value>,
     #[serde(rename = "LiveStreamId")]
     pub live_stream_id: Option<String>,
     #[serde(rename = "MediaStreams")]
@@ -109,6 +137,10 @@ pub struct MediaSegmentList {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MediaSegment {
+    #[serde(rename = "Id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "ItemId", default, skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<String>,
     #[serde(rename = "Type")]
     pub segment_type: MediaSegmentType,
     #[serde(rename = "StartTicks")]
@@ -129,7 +161,41 @@ pub enum MediaSegmentType {
     Other,
 }
 
+impl MediaSegmentType {
+    pub const SKIPPABLE: [Self; 5] = [
+        Self::Intro,
+        Self::Outro,
+        Self::Recap,
+        Self::Preview,
+        Self::Commercial,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unknown => "Unknown",
+            Self::Commercial => "Commercial",
+            Self::Preview => "Preview",
+            Self::Recap => "Recap",
+            Self::Outro => "Outro",
+            Self::Intro => "Intro",
+            Self::Other => "Other",
+        }
+    }
+}
+
 impl MediaSegment {
+    pub fn from_seconds(
+        item_id: &str, segment_type: MediaSegmentType, start: f64, end: f64,
+    ) -> Self {
+        Self {
+            id: None,
+            item_id: Some(item_id.to_owned()),
+            segment_type,
+            start_ticks: (start * 10_000_000.0) as i64,
+            end_ticks: (end * 10_000_000.0) as i64,
+        }
+    }
+
     pub fn start_seconds(&self) -> f64 {
         self.start_ticks as f64 / 10_000_000.0
     }
@@ -139,6 +205,58 @@ impl MediaSegment {
     }
 }
 
+// Emby has no media segments, intro and credits are stored as chapter markers.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChapterInfo {
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "StartPositionTicks")]
+    pub start_position_ticks: i64,
+    #[serde(rename = "MarkerType")]
+    pub marker_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChapterItem {
+    #[serde(rename = "RunTimeTicks")]
+    pub run_time_ticks: Option<i64>,
+    #[serde(rename = "Chapters", default)]
+    pub chapters: Vec<ChapterInfo>,
+}
+
+impl ChapterItem {
+    pub fn marker_segments(&self) -> MediaSegmentList {
+        let marker = |marker_type: &str| {
+            self.chapters
+                .iter()
+                .find(|chapter| chapter.marker_type.as_deref() == Some(marker_type))
+                .map(|chapter| chapter.start_position_ticks)
+        };
+
+        let mut items = Vec::new();
+        if let (Some(start_ticks), Some(end_ticks)) = (marker("IntroStart"), marker("IntroEnd")) {
+            items.push(MediaSegment {
+                id: None,
+                item_id: None,
+                segment_type: MediaSegmentType::Intro,
+                start_ticks,
+                end_ticks,
+            });
+        }
+        if let (Some(start_ticks), Some(end_ticks)) = (marker("CreditsStart"), self.run_time_ticks)
+        {
+            items.push(MediaSegment {
+                id: None,
+                item_id: None,
+                segment_type: MediaSegmentType::Outro,
+                start_ticks,
+                end_ticks,
+            });
+        }
+        MediaSegmentList { items }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LiveMedia {
     #[serde(rename = "MediaSources")]
@@ -411,6 +529,8 @@ pub struct SimpleListItem {
     pub lock_data: Option<bool>,
     #[serde(rename = "PartCount")]
     pub part_count: Option<u32>,
+    #[serde(rename = "MediaStreams")]
+    pub media_streams: Option<Vec<MediaStream>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -443,6 +563,35 @@ pub struct List {
     pub items: Vec<SimpleListItem>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SearchHintResult {
+    #[serde(rename = "SearchHints", default)]
+    pub search_hints: Vec<SearchHint>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SearchHint {
+    #[serde(rename = "ItemId")]
+    pub item_id: Option<String>,
+    #[serde(rename = "Id")]
+    pub id: Option<String>,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub item_type: String,
+    #[serde(rename = "ProductionYear")]
+    pub production_year: Option<u32>,
+    #[serde(rename = "Series")]
+    pub series: Option<String>,
+}
+
+impl SearchHint {
+    /// Emby only fills `ItemId`, Jellyfin fills both.
+    pub fn item_id(&self) -> Option<&str> {
+        self.item_id.as_deref().or(self.id.as_deref())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ExternalIdInfo {
     #[serde(rename = "Name")]
@@ -531,6 +680,18 @@ pub struct ActivityLog {
     pub name: String,
     #[serde(rename = "Date")]
     pub date: DateTime<Utc>,
+    #[serde(rename = "Severity")]
+    pub severity: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct LogFile {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Size", default)]
+    pub size: i64,
+    #[serde(rename = "DateModified")]
+    pub date_modified: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -583,16 +744,299 @@ pub struct LoginResponse {
     pub access_token: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct User {
+    #[serde(rename = "Name", default)]
+    pub name: String,
     #[serde(rename = "Id")]
     pub id: String,
     #[serde(rename = "PrimaryImageTag")]
     pub primary_image_tag: Option<String>,
+    #[serde(rename = "HasPassword", default)]
+    pub has_password: bool,
+    #[serde(rename = "LastActivityDate")]
+    pub last_activity_date: Option<DateTime<Utc>>,
     #[serde(rename = "Policy")]
     pub policy: Policy,
 }
 
+#[derive(Deserialize, Clone)]
+pub struct VirtualFolder {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "ItemId")]
+    pub item_id: String,
+    #[serde(rename = "Locations", default)]
+    pub locations: Vec<String>,
+    #[serde(rename = "CollectionType")]
+    pub collection_type: Option<String>,
+    #[serde(rename = "LibraryOptions", default)]
+    pub library_options: LibraryOptions,
+    #[serde(rename = "RefreshProgress")]
+    pub refresh_progress: Option<f64>,
+    #[serde(rename = "RefreshStatus")]
+    pub refresh_status: Option<String>,
+}
+
+impl VirtualFolder {
+    pub fn is_refreshing(&self) -> bool {
+        self.refresh_status
+            .as_deref()
+            .is_some_and(|status| status != "Idle")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LibraryOptions {
+    #[serde(rename = "EnableRealtimeMonitor", default)]
+    pub enable_realtime_monitor: bool,
+    #[serde(
+        rename = "PreferredMetadataLanguage",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub preferred_metadata_language: Option<String>,
+    #[serde(rename = "EnableTrickplayImageExtraction", default)]
+    pub enable_trickplay_image_extraction: bool,
+    #[serde(rename = "EnableChapterImageExtraction", default)]
+    pub enable_chapter_image_extraction: bool,
+    #[serde(rename = "ExtractChapterImagesDuringLibraryScan", default)]
+    pub extract_chapter_images_during_library_scan: bool,
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Culture {
+    #[serde(rename = "DisplayName")]
+    pub display_name: String,
+    #[serde(rename = "TwoLetterISOLanguageName")]
+    pub two_letter_iso_language_name: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SessionInfo {
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "UserId")]
+    pub user_id: Option<String>,
+    #[serde(rename = "UserName")]
+    pub user_name: Option<String>,
+    #[serde(rename = "Client")]
+    pub client: Option<String>,
+    #[serde(rename = "ApplicationVersion")]
+    pub application_version: Option<String>,
+    #[serde(rename = "DeviceName")]
+    pub device_name: Option<String>,
+    #[serde(rename = "DeviceId")]
+    pub device_id: Option<String>,
+    #[serde(rename = "RemoteEndPoint")]
+    pub remote_end_point: Option<String>,
+    #[serde(rename = "LastActivityDate")]
+    pub last_activity_date: Option<DateTime<Utc>>,
+    #[serde(rename = "SupportsRemoteControl", default)]
+    pub supports_remote_control: bool,
+    #[serde(rename = "NowPlayingItem")]
+    pub now_playing_item: Option<SimpleListItem>,
+    #[serde(rename = "PlayState", default)]
+    pub play_state: SessionPlayState,
+    #[serde(rename = "TranscodingInfo")]
+    pub transcoding_info: Option<TranscodingInfo>,
+    #[serde(rename = "SupportedCommands", default)]
+    pub supported_commands: Vec<String>,
+    #[serde(rename = "NowPlayingQueue", default)]
+    pub now_playing_queue: Vec<QueueItem>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct QueueItem {
+    #[serde(rename = "Id")]
+    pub id: String,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct SessionPlayState {
+    #[serde(rename = "PositionTicks")]
+    pub position_ticks: Option<i64>,
+    #[serde(rename = "IsPaused", default)]
+    pub is_paused: bool,
+    #[serde(rename = "IsMuted", default)]
+    pub is_muted: bool,
+    #[serde(rename = "VolumeLevel")]
+    pub volume_level: Option<i32>,
+    #[serde(rename = "AudioStreamIndex")]
+    pub audio_stream_index: Option<i32>,
+    #[serde(rename = "SubtitleStreamIndex")]
+    pub subtitle_stream_index: Option<i32>,
+    #[serde(rename = "MediaSourceId")]
+    pub media_source_id: Option<String>,
+    #[serde(rename = "PlayMethod")]
+    pub play_method: Option<String>,
+    #[serde(rename = "PlaySessionId")]
+    pub play_session_id: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct TranscodingInfo {
+    #[serde(rename = "AudioCodec")]
+    pub audio_codec: Option<String>,
+    #[serde(rename = "VideoCodec")]
+    pub video_codec: Option<String>,
+    #[serde(rename = "Container")]
+    pub container: Option<String>,
+    #[serde(rename = "IsVideoDirect", default)]
+    pub is_video_direct: bool,
+    #[serde(rename = "IsAudioDirect", default)]
+    pub is_audio_direct: bool,
+    #[serde(rename = "Bitrate")]
+    pub bitrate: Option<i64>,
+    #[serde(rename = "Framerate")]
+    pub framerate: Option<f64>,
+    #[serde(rename = "CompletionPercentage")]
+    pub completion_percentage: Option<f64>,
+    #[serde(rename = "Width")]
+    pub width: Option<i32>,
+    #[serde(rename = "Height")]
+    pub height: Option<i32>,
+    #[serde(rename = "HardwareAccelerationType")]
+    pub hardware_acceleration_type: Option<String>,
+    #[serde(rename = "TranscodeReasons", default)]
+    pub transcode_reasons: Vec<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct PluginInfo {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Version")]
+    pub version: String,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "CanUninstall", default)]
+    pub can_uninstall: bool,
+    #[serde(rename = "Status", default)]
+    pub status: String,
+}
+
+// Packages come from plugin repositories, which use camelCase
+#[derive(Deserialize, Clone)]
+pub struct PackageInfo {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "guid")]
+    pub guid: String,
+    #[serde(rename = "overview")]
+    pub overview: Option<String>,
+    #[serde(rename = "owner")]
+    pub owner: Option<String>,
+    #[serde(rename = "category")]
+    pub category: Option<String>,
+    #[serde(rename = "versions", default)]
+    pub versions: Vec<PackageVersionInfo>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct PackageVersionInfo {
+    #[serde(rename = "version")]
+    pub version: String,
+    #[serde(rename = "repositoryUrl")]
+    pub repository_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ParentalRating {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Value")]
+    pub value: Option<i32>,
+}
+
+// Payload of `Items/{id}` for the metadata editor
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ItemEditInfo {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Type", default)]
+    pub item_type: String,
+    #[serde(rename = "Path", skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(rename = "OriginalTitle", skip_serializing_if = "Option::is_none")]
+    pub original_title: Option<String>,
+    #[serde(rename = "SortName", skip_serializing_if = "Option::is_none")]
+    pub sort_name: Option<String>,
+    #[serde(rename = "ForcedSortName", skip_serializing_if = "Option::is_none")]
+    pub forced_sort_name: Option<String>,
+    #[serde(rename = "DateCreated", skip_serializing_if = "Option::is_none")]
+    pub date_created: Option<DateTime<Utc>>,
+    #[serde(rename = "PremiereDate", skip_serializing_if = "Option::is_none")]
+    pub premiere_date: Option<DateTime<Utc>>,
+    #[serde(rename = "EndDate", skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<DateTime<Utc>>,
+    #[serde(rename = "ProductionYear", skip_serializing_if = "Option::is_none")]
+    pub production_year: Option<i32>,
+    #[serde(rename = "Overview", skip_serializing_if = "Option::is_none")]
+    pub overview: Option<String>,
+    #[serde(rename = "Genres", default)]
+    pub genres: Vec<String>,
+    #[serde(rename = "Tags", default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "Studios", default)]
+    pub studios: Vec<NameIdPair>,
+    #[serde(rename = "People", default)]
+    pub people: Vec<PersonInfo>,
+    #[serde(rename = "ProviderIds", default)]
+    pub provider_ids: BTreeMap<String, String>,
+    #[serde(rename = "OfficialRating", skip_serializing_if = "Option::is_none")]
+    pub official_rating: Option<String>,
+    #[serde(rename = "CommunityRating", skip_serializing_if = "Option::is_none")]
+    pub community_rating: Option<f32>,
+    #[serde(rename = "CriticRating", skip_serializing_if = "Option::is_none")]
+    pub critic_rating: Option<f32>,
+    #[serde(rename = "LockData", default)]
+    pub lock_data: bool,
+    #[serde(rename = "LockedFields", default)]
+    pub locked_fields: Vec<String>,
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+impl ItemEditInfo {
+    pub fn is_locked(&self, field: &str) -> bool {
+        self.locked_fields.iter().any(|f| f == field)
+    }
+
+    pub fn set_locked(&mut self, field: &str, locked: bool) {
+        self.locked_fields.retain(|f| f != field);
+        if locked {
+            self.locked_fields.push(field.to_string());
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NameIdPair {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<IdType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct PersonInfo {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<IdType>,
+    #[serde(rename = "Role", skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(rename = "Type", skip_serializing_if = "Option::is_none")]
+    pub person_type: Option<String>,
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ImageSearchResult {
     #[serde(rename = "Images")]
@@ -671,28 +1115,30 @@ pub struct FilterItem {
     pub id: Option<String>,
 }
 
+use std::sync::Arc;
+
 use adw::prelude::*;
 use gtk::glib;
 
-use super::jellyfin_client::JELLYFIN_CLIENT;
+use super::jellyfin_client::JellyfinClient;
 use crate::ui::widgets::{
     single_grid::SingleGrid,
     window::Window,
 };
 
 impl SGTitem {
-    pub fn activate<T>(&self, widget: &T, list_type: String)
+    pub fn activate<T>(&self, widget: &T, list_type: String, client: Arc<JellyfinClient>)
     where
         T: gtk::prelude::WidgetExt + glib::clone::Downgrade,
     {
-        let page = SingleGrid::new();
+        let page = SingleGrid::new(client);
         let id = self.id.to_string();
         let list_type_clone = list_type.to_owned();
-        page.connect_sort_changed_tokio(move |sort_by, sort_order, filters_list| {
+        page.connect_sort_changed_tokio(move |client, sort_by, sort_order, filters_list| {
             let id = id.to_owned();
             let list_type_clone = list_type_clone.to_owned();
             async move {
-                JELLYFIN_CLIENT
+                client
                     .get_inlist(
                         None,
                         0,
@@ -707,23 +1153,25 @@ impl SGTitem {
         });
         let id = self.id.to_string();
         let list_type = list_type.to_owned();
-        page.connect_end_edge_overshot_tokio(move |sort_by, sort_order, n_items, filters_list| {
-            let id = id.to_owned();
-            let list_type = list_type.to_owned();
-            async move {
-                JELLYFIN_CLIENT
-                    .get_inlist(
-                        None,
-                        n_items,
-                        &list_type,
-                        &id,
-                        &sort_order,
-                        &sort_by,
-                        &filters_list,
-                    )
-                    .await
-            }
-        });
+        page.connect_end_edge_overshot_tokio(
+            move |client, sort_by, sort_order, n_items, filters_list| {
+                let id = id.to_owned();
+                let list_type = list_type.to_owned();
+                async move {
+                    client
+                        .get_inlist(
+                            None,
+                            n_items,
+                            &list_type,
+                            &id,
+                            &sort_order,
+                            &sort_by,
+                            &filters_list,
+                        )
+                        .await
+                }
+            },
+        );
         push_page_with_tag(widget, page, &self.id.to_string(), &self.name.to_owned());
     }
 }
@@ -737,3 +1185,31 @@ where
     let window = binding.and_downcast_ref::<Window>().unwrap();
     window.push_page(&page, tag, name);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_edit_info_round_trip() {
+        let value = serde_json::json!({
+            "Name": "Movie",
+            "Type": "Movie",
+            "Genres": ["Drama"],
+            "Tags": [],
+            "Studios": [{ "Name": "Studio", "Id": "1" }],
+            "People": [{ "Name": "Actor", "Id": "2", "Role": "Lead", "Type": "Actor", "PrimaryImageTag": "tag" }],
+            "ProviderIds": { "Imdb": "tt0000001" },
+            "LockData": false,
+            "LockedFields": ["Overview"],
+            "CommunityRating": 7.5,
+            "ProductionYear": 2001,
+            "Taglines": ["Tagline"],
+            "AirDays": []
+        });
+
+        let info: ItemEditInfo = serde_json::from_value(value.to_owned()).unwrap();
+        assert!(info.is_locked("Overview"));
+        assert_eq!(serde_json::to_value(&info).unwrap(), value);
+    }
+}
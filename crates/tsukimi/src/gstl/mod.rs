@@ -1,2 +1,5 @@
 pub mod mpris;
 pub mod player;
+mod queue;
+mod shuffle;
+mod sleep_timer;
@@ -8,6 +8,7 @@ use gtk::{
     self,
     gio::prelude::FileExt,
     glib,
+    prelude::ObjectExt,
 };
 use mpris_server::{
     LocalPlayerInterface,
@@ -139,6 +140,10 @@ impl MusicPlayer {
         self.mpris_properties_changed([Property::LoopStatus(status.into())]);
     }
 
+    pub fn notify_mpris_shuffle(&self, shuffle: bool) {
+        self.mpris_properties_changed([Property::Shuffle(shuffle)]);
+    }
+
     pub fn notify_mpris_art_changed(&self) {
         let mut metadata = self.metadata().clone();
         spawn(glib::clone!(
@@ -163,12 +168,17 @@ impl MusicPlayer {
             .active_core_song()
             .as_ref()
             .map_or_else(Metadata::new, |song| {
-                Metadata::builder()
+                let mut metadata = Metadata::builder()
                     .album(song.album_id())
                     .title(song.name())
                     .length(Time::from_secs(song.duration() as i64))
                     .artist([song.artist()])
-                    .build()
+                    .build();
+                // Lets MPRIS clients show when the sleep timer stops playback
+                if let Some(description) = self.sleep_timer_description() {
+                    metadata.set_comment(Some([description]));
+                }
+                metadata
             })
     }
 }
@@ -299,13 +309,12 @@ impl LocalPlayerInterface for MusicPlayer {
     }
 
     async fn shuffle(&self) -> fdo::Result<bool> {
-        Ok(false)
+        Ok(self.imp().shuffle.get())
     }
 
-    async fn set_shuffle(&self, _shuffle: bool) -> zbus::Result<()> {
-        Err(zbus::Error::from(fdo::Error::NotSupported(
-            "SetShuffle is not supported".into(),
-        )))
+    async fn set_shuffle(&self, shuffle: bool) -> zbus::Result<()> {
+        self.set_property("shuffle", shuffle);
+        Ok(())
     }
 
     async fn metadata(&self) -> fdo::Result<Metadata> {
@@ -1,10 +1,7 @@
 use gst::prelude::*;
 use gtk::glib;
 
-use crate::{
-    client::jellyfin_client::JELLYFIN_CLIENT,
-    ui::provider::core_song::CoreSong,
-};
+use crate::ui::provider::core_song::CoreSong;
 
 pub mod imp {
     use std::{
@@ -41,7 +38,13 @@ pub mod imp {
     };
 
     use super::*;
-    use crate::ui::widgets::song_widget::State;
+    use crate::ui::{
+        models::{
+            shuffle_order::ShuffleOrder,
+            sleep_timer::SleepTimer,
+        },
+        widgets::song_widget::State,
+    };
 
     #[derive(Default, Hash, Eq, PartialEq, Clone, Copy, glib::Enum, Debug)]
     #[repr(u32)]
@@ -101,8 +104,23 @@ pub mod imp {
         pub repeat_mode: Cell<ListRepeatMode>,
         #[property(get, set, default_value = false)]
         pub gapless: Cell<bool>,
+        #[property(get, set = Self::set_shuffle, explicit_notify, default_value = false)]
+        pub shuffle: Cell<bool>,
+        pub shuffle_order: RefCell<ShuffleOrder<CoreSong>>,
 
         pub mpris_server: OnceCell<LocalServer<super::MusicPlayer>>,
+
+        pub sleep_timer: RefCell<SleepTimer>,
+        pub sleep_timer_source: RefCell<Option<glib::SourceId>>,
+        /// Volume before the sleep timer started fading it out
+        pub sleep_timer_volume: Cell<Option<f64>>,
+        pub sleep_timer_label: RefCell<Option<String>>,
+        /// The sleep timer stops playback once the current track ended
+        pub stop_at_eos: Cell<bool>,
+        /// Track handed to the pipeline for gapless playback
+        pub queued_song: RefCell<Option<CoreSong>>,
+        /// Order of the next repeat-all round while shuffled
+        pub next_round: RefCell<Option<Vec<CoreSong>>>,
     }
 
     #[glib::derived_properties]
@@ -174,14 +192,33 @@ pub mod imp {
                     while let Ok(value) = GSTREAMER_EVENT_CHANNEL.rx.recv_async().await {
                         match value {
                             GstreamerEvent::AboutToFinish => {
+                                if imp.obj().sleep_timer_item_finished() {
+                                    imp.stop_at_eos.set(true);
+                                    continue;
+                                }
                                 if let Some(core_song) = imp.next_song() {
                                     imp.add_song(&core_song).await;
+                                    imp.queued_song.replace(Some(core_song));
                                     imp.obj().set_gapless(true);
                                 }
                             }
                             GstreamerEvent::StreamStart => {
                                 let obj = imp.obj();
                                 if obj.gapless() {
+                                    let queued = imp.queued_song.take();
+                                    if queued.is_some() && queued != imp.next_song() {
+                                        // The queue was edited before the replacement
+                                        // reached the pipeline, or nothing comes next
+                                        // anymore
+                                        obj.set_gapless(false);
+                                        if imp.next_song().is_some() {
+                                            imp.next().await;
+                                        } else {
+                                            imp.stop();
+                                            imp.notify_stopped();
+                                        }
+                                        continue;
+                                    }
                                     let _ = imp.playlist_next();
                                 }
                                 obj.set_gapless(false);
@@ -194,6 +231,13 @@ pub mod imp {
                             }
                             GstreamerEvent::Eos => {
                                 let obj = imp.obj();
+                                if imp.stop_at_eos.take() {
+                                    let duration = obj
+                                        .active_core_song()
+                                        .map_or(0.0, |song| song.duration() as f64);
+                                    obj.finish_sleep_timer(duration);
+                                    continue;
+                                }
                                 if imp.playlist_next().is_err() {
                                     return;
                                 };
@@ -218,6 +262,7 @@ pub mod imp {
                     Signal::builder("stream-start")
                         .param_types([ClockTime::static_type()])
                         .build(),
+                    Signal::builder("sleep-timer-finished").build(),
                 ]
             })
         }
@@ -230,6 +275,20 @@ pub mod imp {
     }
 
     impl MusicPlayer {
+        fn set_shuffle(&self, shuffle: bool) {
+            if self.shuffle.replace(shuffle) == shuffle {
+                return;
+            }
+            let obj = self.obj();
+            if shuffle {
+                obj.shuffle_queue();
+            } else {
+                obj.unshuffle_queue();
+            }
+            obj.notify_shuffle();
+            obj.notify_mpris_shuffle(shuffle);
+        }
+
         fn pipeline(&self) -> &gst::Element {
             self.pipeline.get().unwrap()
         }
@@ -267,7 +326,12 @@ pub mod imp {
             }
 
             self.stop();
-            let uri = JELLYFIN_CLIENT
+            // Picking another track overrides a sleep timer that was due
+            self.stop_at_eos.set(false);
+            self.queued_song.take();
+            self.next_round.take();
+            let uri = core_song
+                .client()
                 .get_song_streaming_uri(&core_song.id())
                 .await;
 
@@ -277,7 +341,8 @@ pub mod imp {
         }
 
         pub async fn add_song(&self, core_song: &CoreSong) {
-            let uri = JELLYFIN_CLIENT
+            let uri = core_song
+                .client()
                 .get_song_streaming_uri(&core_song.id())
                 .await;
             gst::prelude::ObjectExt::set_property(self.pipeline(), "uri", uri);
@@ -288,6 +353,7 @@ pub mod imp {
                 core_song.set_state(State::Played);
             };
             if let Some(core_song) = self.next_song() {
+                self.obj().start_next_round(&core_song);
                 core_song.set_state(State::Playing);
                 debug!("Next Song: {}", core_song.name());
                 self.obj().set_active_core_song(Some(core_song));
@@ -315,6 +381,9 @@ pub mod imp {
             let next_position = core_song_position + 1;
             if next_position >= model.n_items() {
                 if obj.repeat_mode() == ListRepeatMode::Repeat {
+                    if let Some(round) = obj.next_round() {
+                        return round.into_iter().next();
+                    }
                     return model.item(0)?.downcast::<CoreSong>().ok();
                 }
                 return None;
@@ -410,8 +479,16 @@ pub mod imp {
             if let Some(core_song) = self.active_core_song.borrow().as_ref() {
                 core_song.set_state(State::Played);
             };
-            self.active_model.replace(Some(active_model));
-            self.active_core_song.replace(Some(active_core_song));
+            // The queue always holds the playing track
+            if active_model.find(&active_core_song).is_none() {
+                active_model.insert(0, &active_core_song);
+            }
+            let obj = self.obj();
+            obj.set_active_model(Some(active_model));
+            obj.set_active_core_song(Some(active_core_song));
+            if obj.shuffle() {
+                obj.shuffle_queue();
+            }
             self.prepre_play().await;
         }
 
@@ -443,6 +520,10 @@ pub mod imp {
             }
         }
 
+        pub fn volume(&self) -> f64 {
+            gst::prelude::ObjectExt::property(self.pipeline(), "volume")
+        }
+
         pub fn set_volume(&self, volume: f64) {
             gst::prelude::ObjectExt::set_property(self.pipeline(), "volume", volume);
         }
@@ -0,0 +1,159 @@
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use gtk::{
+    glib,
+    prelude::*,
+};
+use mpris_server::Property;
+
+use super::player::MusicPlayer;
+use crate::{
+    ui::{
+        provider::core_song::CoreSong,
+        widgets::song_widget::State,
+    },
+    utils::spawn,
+};
+
+impl MusicPlayer {
+    /// Puts `songs` right after the playing track. Returns `false` when
+    /// nothing is playing, there is no queue to add to then.
+    pub fn play_next(&self, songs: &[CoreSong]) -> bool {
+        self.enqueue(songs, true)
+    }
+
+    /// Appends `songs` to the end of the queue. Returns `false` when nothing
+    /// is playing, there is no queue to add to then.
+    pub fn add_to_queue(&self, songs: &[CoreSong]) -> bool {
+        self.enqueue(songs, false)
+    }
+
+    fn enqueue(&self, songs: &[CoreSong], next: bool) -> bool {
+        if self.imp().state() == gst::State::Null {
+            return false;
+        }
+        let (Some(model), Some(active)) = (self.active_model(), self.active_core_song()) else {
+            return false;
+        };
+
+        // A song is in the queue once, queueing it again moves the instance
+        // that is already there, which keeps its state
+        let mut queued: Vec<CoreSong> = Vec::new();
+        for song in songs {
+            if song.id() == active.id() || queued.iter().any(|queued| queued.id() == song.id()) {
+                continue;
+            }
+            let existing = (0..model.n_items()).find_map(|position| {
+                model
+                    .item(position)
+                    .and_downcast::<CoreSong>()
+                    .filter(|existing| existing.id() == song.id())
+                    .map(|existing| (position, existing))
+            });
+            match existing {
+                Some((position, existing)) => {
+                    model.remove(position);
+                    queued.push(existing);
+                }
+                None => queued.push(song.to_owned()),
+            }
+        }
+
+        let position = if next {
+            model.find(&active).map_or(0, |position| position + 1)
+        } else {
+            model.n_items()
+        };
+        model.splice(position, 0, &queued);
+        self.queue_changed();
+        true
+    }
+
+    pub fn move_song(&self, from: u32, to: u32) {
+        let Some(model) = self.active_model() else {
+            return;
+        };
+        if from == to || from >= model.n_items() || to >= model.n_items() {
+            return;
+        }
+        let Some(song) = model.item(from) else {
+            return;
+        };
+        model.remove(from);
+        model.insert(to, &song);
+        self.queue_changed();
+    }
+
+    /// The playing track stays, skip it to get rid of it.
+    pub fn remove_song(&self, position: u32) {
+        let Some(model) = self.active_model() else {
+            return;
+        };
+        let Some(song) = model.item(position).and_downcast::<CoreSong>() else {
+            return;
+        };
+        if self.active_core_song().as_ref() == Some(&song) {
+            return;
+        }
+        model.remove(position);
+        self.queue_changed();
+    }
+
+    /// Removes everything but the playing track.
+    pub fn clear_queue(&self) {
+        let Some(model) = self.active_model() else {
+            return;
+        };
+        let active = self.active_core_song();
+        model.retain(|song| {
+            active
+                .as_ref()
+                .is_some_and(|active| song == active.upcast_ref::<glib::Object>())
+        });
+        self.queue_changed();
+    }
+
+    pub async fn play_queue_position(&self, position: u32) {
+        let Some(song) = self
+            .active_model()
+            .and_then(|model| model.item(position))
+            .and_downcast::<CoreSong>()
+        else {
+            return;
+        };
+        if let Some(active) = self.active_core_song() {
+            active.set_state(State::Played);
+        }
+        self.set_active_core_song(Some(song));
+        self.imp().prepre_play().await;
+    }
+
+    /// A track that was already handed to the pipeline for gapless playback
+    /// gets replaced by the one the queue now says comes next.
+    pub(super) fn queue_changed(&self) {
+        let imp = self.imp();
+        imp.next_round.take();
+        let queued = imp.queued_song.borrow().to_owned();
+        if self.gapless()
+            && queued.is_some()
+            && let Some(next) = imp.next_song()
+            && queued.as_ref() != Some(&next)
+        {
+            spawn(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                async move {
+                    let imp = obj.imp();
+                    imp.add_song(&next).await;
+                    // Until here the track starting is still the stale one
+                    if obj.gapless() {
+                        imp.queued_song.replace(Some(next));
+                    }
+                }
+            ));
+        }
+        self.mpris_properties_changed([
+            Property::CanGoPrevious(imp.prev_song().is_some()),
+            Property::CanGoNext(imp.next_song().is_some()),
+        ]);
+    }
+}
@@ -0,0 +1,83 @@
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use gtk::prelude::*;
+
+use super::player::{
+    MusicPlayer,
+    imp::ListRepeatMode,
+};
+use crate::ui::{
+    models::shuffle_order::ShuffleOrder,
+    provider::core_song::CoreSong,
+};
+
+impl MusicPlayer {
+    /// Shuffles the queue in place, the playing track goes first. Gapless
+    /// playback keeps working as it pre-queues from the queue order.
+    pub(super) fn shuffle_queue(&self) {
+        let Some(model) = self.active_model() else {
+            return;
+        };
+        let songs = model.iter::<CoreSong>().flatten().collect::<Vec<_>>();
+        let shuffled = self
+            .imp()
+            .shuffle_order
+            .borrow_mut()
+            .shuffle(songs, self.active_core_song().as_ref());
+        model.splice(0, model.n_items(), &shuffled);
+        self.queue_changed();
+    }
+
+    pub(super) fn unshuffle_queue(&self) {
+        let Some(model) = self.active_model() else {
+            return;
+        };
+        let songs = model.iter::<CoreSong>().flatten().collect::<Vec<_>>();
+        let ordered = self.imp().shuffle_order.borrow_mut().unshuffle(songs);
+        model.splice(0, model.n_items(), &ordered);
+        self.queue_changed();
+    }
+
+    /// With repeat-all, the queue gets a new order every time it wraps, so
+    /// every round plays differently. The order is made once the wrap is
+    /// first looked at, so gapless playback pre-queues the track that
+    /// really comes next.
+    pub(super) fn next_round(&self) -> Option<Vec<CoreSong>> {
+        if !self.shuffle() || self.repeat_mode() != ListRepeatMode::Repeat {
+            return None;
+        }
+        let imp = self.imp();
+        if let Some(round) = imp.next_round.borrow().as_ref() {
+            return Some(round.to_owned());
+        }
+        let (Some(model), Some(last)) = (self.active_model(), self.active_core_song()) else {
+            return None;
+        };
+        if model.n_items() < 3 {
+            return None;
+        }
+        let songs = model.iter::<CoreSong>().flatten().collect::<Vec<_>>();
+        let round = ShuffleOrder::next_round(&songs, &last);
+        imp.next_round.replace(Some(round.to_owned()));
+        Some(round)
+    }
+
+    /// Puts the order of the next round in the queue when `next`, the track
+    /// about to play, starts it.
+    pub(super) fn start_next_round(&self, next: &CoreSong) {
+        let imp = self.imp();
+        let Some(round) = imp.next_round.take() else {
+            return;
+        };
+        let Some(model) = self.active_model() else {
+            return;
+        };
+        let n_items = model.n_items();
+        if imp.core_song_position() != Some(n_items.saturating_sub(1))
+            || round.len() != n_items as usize
+            || round.first() != Some(next)
+        {
+            return;
+        }
+        model.splice(0, n_items, &round);
+    }
+}
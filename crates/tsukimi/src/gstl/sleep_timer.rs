@@ -0,0 +1,138 @@
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use gtk::{
+    glib,
+    prelude::ObjectExt,
+};
+use mpris_server::Property;
+
+use super::player::MusicPlayer;
+use crate::{
+    client::{
+        jellyfin_client::BackType,
+        structs::Back,
+    },
+    ui::models::sleep_timer::SleepTimerMode,
+    utils::spawn_tokio_without_await,
+};
+
+impl MusicPlayer {
+    pub fn set_sleep_timer(&self, mode: SleepTimerMode) {
+        self.stop_sleep_timer();
+        if mode != SleepTimerMode::Off {
+            let imp = self.imp();
+            imp.sleep_timer
+                .borrow_mut()
+                .set(mode, glib::monotonic_time());
+            let source = glib::timeout_add_seconds_local(
+                1,
+                glib::clone!(
+                    #[weak(rename_to = obj)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || obj.sleep_timer_tick()
+                ),
+            );
+            imp.sleep_timer_source.replace(Some(source));
+        }
+        self.mpris_properties_changed([Property::Metadata(self.metadata())]);
+    }
+
+    pub fn sleep_timer_description(&self) -> Option<String> {
+        self.imp()
+            .sleep_timer
+            .borrow()
+            .describe(glib::monotonic_time())
+    }
+
+    /// Counts the track that is about to finish, returns whether playback
+    /// should stop at its end instead of moving on.
+    pub(super) fn sleep_timer_item_finished(&self) -> bool {
+        let finished = self.imp().sleep_timer.borrow_mut().item_finished();
+        if !finished && self.imp().sleep_timer.borrow().is_active() {
+            self.mpris_properties_changed([Property::Metadata(self.metadata())]);
+        }
+        finished
+    }
+
+    fn sleep_timer_tick(&self) -> glib::ControlFlow {
+        let imp = self.imp();
+        let now = glib::monotonic_time();
+        let duration = self
+            .active_core_song()
+            .map_or(0.0, |song| song.duration() as f64);
+        let position = imp.position();
+        let (expired, factor, description) = {
+            let timer = imp.sleep_timer.borrow();
+            (
+                timer.expired(now),
+                timer.volume_factor(now, duration - position),
+                timer.describe(now),
+            )
+        };
+
+        if expired {
+            // Returning `Break` removes the source already
+            imp.sleep_timer_source.take();
+            self.finish_sleep_timer(position);
+            return glib::ControlFlow::Break;
+        }
+
+        if factor < 1.0 {
+            let volume = match imp.sleep_timer_volume.get() {
+                Some(volume) => volume,
+                None => {
+                    let volume = imp.volume();
+                    imp.sleep_timer_volume.set(Some(volume));
+                    volume
+                }
+            };
+            imp.set_volume(volume * factor);
+        }
+
+        if imp.sleep_timer_label.replace(description.clone()) != description {
+            self.mpris_properties_changed([Property::Metadata(self.metadata())]);
+        }
+        glib::ControlFlow::Continue
+    }
+
+    /// Reports how far the current track got, then stops playback.
+    pub(super) fn finish_sleep_timer(&self, position: f64) {
+        let imp = self.imp();
+        if let Some(song) = self.active_core_song() {
+            let back = Back {
+                id: song.id(),
+                series_id: None,
+                playsessionid: None,
+                mediasourceid: song.id(),
+                livestreamid: None,
+                playmethod: "DirectPlay",
+                tick: (position * 10_000_000.0) as u64,
+                start_tick: 0,
+            };
+            let client = song.client();
+            spawn_tokio_without_await(async move {
+                let _ = client.position_back(&back, BackType::Stop).await;
+            });
+        }
+
+        imp.stop();
+        imp.notify_stopped();
+        self.stop_sleep_timer();
+        self.emit_by_name::<()>("sleep-timer-finished", &[]);
+    }
+
+    /// Cancels the sleep timer and brings the volume back.
+    pub fn stop_sleep_timer(&self) {
+        let imp = self.imp();
+        imp.sleep_timer.borrow_mut().cancel();
+        imp.sleep_timer_label.take();
+        imp.stop_at_eos.set(false);
+        if let Some(source) = imp.sleep_timer_source.take() {
+            source.remove();
+        }
+        if let Some(volume) = imp.sleep_timer_volume.take() {
+            imp.set_volume(volume);
+        }
+    }
+}
@@ -8,6 +8,7 @@ use gtk::glib::prelude::StaticTypeExt;
 pub use models::{
     SETTINGS,
     jellyfin_cache_path,
+    server_cache_path,
 };
 pub(crate) use mpv::options_matcher::{
     match_audio_channels,
@@ -46,8 +47,10 @@ pub fn init() {
     mpv::video_scale::VideoScale::ensure_type();
     mpv::volume_bar::VolumeBar::ensure_type();
     mpv::danmaku_popover::DanmakuPopover::ensure_type();
+    mpv::up_next_card::UpNextCard::ensure_type();
     mutsumi::Danmakw::ensure_type();
 
+    widgets::music_queue::MusicQueue::ensure_type();
     widgets::player_toolbar::PlayerToolbarBox::ensure_type();
     widgets::content_viewer::MediaContentViewer::ensure_type();
     widgets::media_viewer::MediaViewer::ensure_type();
@@ -58,4 +61,5 @@ pub fn init() {
     mpv::page::MPVPage::ensure_type();
     mpv::control_sidebar::MPVControlSidebar::ensure_type();
     widgets::theme_switcher::ThemeSwitcher::ensure_type();
+    widgets::server_settings_page::ServerSettingsPage::ensure_type();
 }
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use gettextrs::gettext;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::SETTINGS;
+use crate::client::jellyfin_client::JellyfinClient;
+
+/// Where the items of a pinned home row come from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PinnedSource {
+    Collection {
+        id: String,
+    },
+    Playlist {
+        id: String,
+    },
+    Genre {
+        id: String,
+    },
+    /// A library view narrowed down by the filter panel, stored as query parameters
+    Filter {
+        parent_id: Option<String>,
+        params: Vec<(String, String)>,
+    },
+}
+
+impl PinnedSource {
+    /// Query parameters appended to `Users/{id}/Items`.
+    pub fn params(&self) -> Vec<(String, String)> {
+        let owned = |k: &str, v: &str| (k.to_owned(), v.to_owned());
+        match self {
+            PinnedSource::Collection { id } | PinnedSource::Playlist { id } => {
+                vec![owned("ParentId", id)]
+            }
+            PinnedSource::Genre { id } => vec![
+                owned("GenreIds", id),
+                owned("Recursive", "true"),
+                owned("IncludeItemTypes", "Movie,Series,MusicAlbum"),
+                owned("SortBy", "DateCreated,SortName"),
+                owned("SortOrder", "Descending"),
+            ],
+            PinnedSource::Filter { parent_id, params } => {
+                let mut kv = vec![
+                    owned("Recursive", "true"),
+                    owned("IncludeItemTypes", "Movie,Series,MusicAlbum,Video"),
+                    owned("SortBy", "DateCreated,SortName"),
+                    owned("SortOrder", "Descending"),
+                ];
+                if let Some(parent_id) = parent_id {
+                    kv.push(owned("ParentId", parent_id));
+                }
+                kv.extend(params.iter().cloned());
+                kv
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinnedRow {
+    /// Stable id, used to match rows on the home page across reloads
+    pub id: String,
+    pub title: String,
+    pub source: PinnedSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum HomeSectionKind {
+    Libraries,
+    Resume,
+    NextUp,
+    Latest,
+    Pinned(PinnedRow),
+}
+
+impl HomeSectionKind {
+    pub fn title(&self) -> String {
+        match self {
+            HomeSectionKind::Libraries => gettext("Library"),
+            HomeSectionKind::Resume => gettext("Continue Watching"),
+            HomeSectionKind::NextUp => gettext("Next Up"),
+            HomeSectionKind::Latest => gettext("Latest in Libraries"),
+            HomeSectionKind::Pinned(row) => row.title.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HomeSection {
+    pub kind: HomeSectionKind,
+    pub visible: bool,
+}
+
+/// Home page layout of one server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerHomeLayout {
+    pub sections: Vec<HomeSection>,
+    /// Libraries whose "Latest" row is hidden
+    #[serde(default)]
+    pub hidden_libraries: Vec<String>,
+}
+
+impl Default for ServerHomeLayout {
+    fn default() -> Self {
+        Self {
+            sections: Self::BUILTIN
+                .iter()
+                .map(|kind| HomeSection {
+                    kind: kind.clone(),
+                    visible: true,
+                })
+                .collect(),
+            hidden_libraries: Vec::new(),
+        }
+    }
+}
+
+impl ServerHomeLayout {
+    const BUILTIN: [HomeSectionKind; 4] = [
+        HomeSectionKind::Libraries,
+        HomeSectionKind::Resume,
+        HomeSectionKind::NextUp,
+        HomeSectionKind::Latest,
+    ];
+
+    /// Makes sure every built-in section appears exactly once, so older or
+    /// hand-edited layouts can't lose a section for good.
+    fn normalize(&mut self) {
+        let mut seen = Vec::new();
+        self.sections.retain(|section| {
+            if matches!(section.kind, HomeSectionKind::Pinned(_)) {
+                return true;
+            }
+            if seen.contains(&section.kind) {
+                return false;
+            }
+            seen.push(section.kind.clone());
+            true
+        });
+        for kind in Self::BUILTIN {
+            if !seen.contains(&kind) {
+                self.sections.push(HomeSection {
+                    kind,
+                    visible: true,
+                });
+            }
+        }
+    }
+
+    pub fn is_visible(&self, kind: &HomeSectionKind) -> bool {
+        self.sections
+            .iter()
+            .find(|section| &section.kind == kind)
+            .is_none_or(|section| section.visible)
+    }
+
+    pub fn is_library_hidden(&self, id: &str) -> bool {
+        self.hidden_libraries.iter().any(|hidden| hidden == id)
+    }
+
+    pub fn set_library_hidden(&mut self, id: &str, hidden: bool) {
+        self.hidden_libraries.retain(|h| h != id);
+        if hidden {
+            self.hidden_libraries.push(id.to_owned());
+        }
+    }
+
+    pub fn move_section(&mut self, index: usize, up: bool) {
+        let target = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1).filter(|i| *i < self.sections.len())
+        };
+        if let Some(target) = target {
+            self.sections.swap(index, target);
+        }
+    }
+
+    pub fn add_pinned(&mut self, title: String, source: PinnedSource) {
+        self.sections.push(HomeSection {
+            kind: HomeSectionKind::Pinned(PinnedRow {
+                id: gtk::glib::uuid_string_random().to_string(),
+                title,
+                source,
+            }),
+            visible: true,
+        });
+    }
+
+    pub fn remove_section(&mut self, index: usize) {
+        if matches!(
+            self.sections.get(index).map(|s| &s.kind),
+            Some(HomeSectionKind::Pinned(_))
+        ) {
+            self.sections.remove(index);
+        }
+    }
+}
+
+/// Home page layouts, keyed by `Session::account_key`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HomeLayouts {
+    servers: HashMap<String, ServerHomeLayout>,
+}
+
+impl HomeLayouts {
+    fn load() -> Self {
+        serde_json::from_str(&SETTINGS.home_layouts()).unwrap_or_default()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        SETTINGS.set_home_layouts(&serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    // Users of the same server lay out their home on their own
+    fn key(client: &JellyfinClient) -> String {
+        client.session().account_key()
+    }
+
+    /// Layout of the account `client` is signed in to
+    pub fn of(client: &JellyfinClient) -> ServerHomeLayout {
+        let mut layout = Self::load()
+            .servers
+            .remove(&Self::key(client))
+            .unwrap_or_default();
+        layout.normalize();
+        layout
+    }
+
+    pub fn save_for(client: &JellyfinClient, layout: &ServerHomeLayout) -> anyhow::Result<()> {
+        let mut layouts = Self::load();
+        layouts.servers.insert(Self::key(client), layout.to_owned());
+        layouts.save()
+    }
+
+    pub fn reset(client: &JellyfinClient) -> anyhow::Result<()> {
+        let mut layouts = Self::load();
+        layouts.servers.remove(&Self::key(client));
+        layouts.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_restores_missing_builtin_sections() {
+        let mut layout = ServerHomeLayout {
+            sections: vec![
+                HomeSection {
+                    kind: HomeSectionKind::Latest,
+                    visible: false,
+                },
+                HomeSection {
+                    kind: HomeSectionKind::Latest,
+                    visible: true,
+                },
+            ],
+            hidden_libraries: Vec::new(),
+        };
+        layout.normalize();
+
+        assert_eq!(layout.sections.len(), 4);
+        assert_eq!(layout.sections[0].kind, HomeSectionKind::Latest);
+        assert!(!layout.is_visible(&HomeSectionKind::Latest));
+        assert!(layout.is_visible(&HomeSectionKind::Resume));
+    }
+
+    #[test]
+    fn move_section_stays_in_bounds() {
+        let mut layout = ServerHomeLayout::default();
+        layout.move_section(0, true);
+        assert_eq!(layout.sections[0].kind, HomeSectionKind::Libraries);
+        layout.move_section(3, false);
+        assert_eq!(layout.sections[3].kind, HomeSectionKind::Latest);
+        layout.move_section(0, false);
+        assert_eq!(layout.sections[1].kind, HomeSectionKind::Libraries);
+    }
+}
@@ -1,8 +1,15 @@
 use once_cell::sync::Lazy;
+pub mod home_layout;
+pub mod search_history;
+pub mod server_overrides;
 pub mod settings;
+pub mod settings_bundle;
+pub mod shuffle_order;
+pub mod sleep_timer;
+pub mod watch_history;
 pub use self::settings::Settings;
 use crate::{
-    client::jellyfin_client::JELLYFIN_CLIENT,
+    client::registry::CLIENTS,
     utils::spawn_tokio_blocking,
 };
 pub static SETTINGS: Lazy<Settings> = Lazy::new(Settings::default);
@@ -11,7 +18,12 @@ static CACHE_PATH: Lazy<std::path::PathBuf> =
     Lazy::new(|| gtk::glib::user_cache_dir().join("tsukimi"));
 
 pub async fn jellyfin_cache_path() -> std::path::PathBuf {
-    let path = CACHE_PATH.join(&JELLYFIN_CLIENT.session().server_name_hash);
+    server_cache_path(&CLIENTS.active().session().server_name_hash).await
+}
+
+/// Cache directory of the server with `server_name_hash`.
+pub async fn server_cache_path(server_name_hash: &str) -> std::path::PathBuf {
+    let path = CACHE_PATH.join(server_name_hash);
     spawn_tokio_blocking(move || {
         std::fs::create_dir_all(&path).expect("Failed to create directory");
         path
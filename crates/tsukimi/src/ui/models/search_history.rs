@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::SETTINGS;
+use crate::client::jellyfin_client::JellyfinClient;
+
+const MAX_ENTRIES: usize = 20;
+
+/// Recent search queries, kept separately for every signed in account.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SearchHistory {
+    entries: HashMap<String, Vec<String>>,
+    /// Account the queries are read and written for
+    #[serde(skip)]
+    account: String,
+}
+
+impl SearchHistory {
+    /// History of the account `client` is signed in to
+    pub fn load(client: &JellyfinClient) -> Self {
+        let mut history: Self =
+            serde_json::from_str(&SETTINGS.search_history()).unwrap_or_default();
+        history.account = client.session().account_key();
+        history
+    }
+
+    /// Queries of the account, most recent first
+    pub fn queries(&self) -> Vec<String> {
+        self.entries.get(&self.account).cloned().unwrap_or_default()
+    }
+
+    pub fn push(&mut self, query: &str) -> anyhow::Result<()> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(());
+        }
+        let queries = self.entries.entry(self.account.to_owned()).or_default();
+        queries.retain(|q| !q.eq_ignore_ascii_case(query));
+        queries.insert(0, query.to_string());
+        queries.truncate(MAX_ENTRIES);
+        self.save()
+    }
+
+    pub fn remove(&mut self, query: &str) -> anyhow::Result<()> {
+        if let Some(queries) = self.entries.get_mut(&self.account) {
+            queries.retain(|q| q != query);
+        }
+        self.save()
+    }
+
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.entries.remove(&self.account);
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let value = serde_json::to_string(self)?;
+        SETTINGS.set_search_history(&value)?;
+        Ok(())
+    }
+}
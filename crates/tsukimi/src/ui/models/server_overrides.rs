@@ -0,0 +1,112 @@
+use std::{
+    cell::RefCell,
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    sync::Once,
+};
+
+use gtk::{
+    gio::prelude::*,
+    glib,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::SETTINGS;
+use crate::client::jellyfin_client::JellyfinClient;
+
+/// Settings keys a server can override.
+pub const OVERRIDABLE_KEYS: &[&str] = &[
+    "mpv-audio-preferred-lang",
+    "mpv-subtitle-preferred-lang",
+    "is-danmaku-enabled",
+    "mpv-hwdec",
+    "mpv-cache-size",
+    "mpv-cache-time",
+    "music-repeat-mode",
+];
+
+thread_local! {
+    // Parsed once instead of on every settings read, dropped when the setting changes
+    static CACHE: RefCell<Option<ServerOverrides>> = const { RefCell::new(None) };
+}
+static INVALIDATE_CACHE: Once = Once::new();
+
+/// Per-server values layered over the global settings, keyed by
+/// `Session::account_key`. Values are stored in GVariant text format.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ServerOverrides {
+    servers: HashMap<String, BTreeMap<String, String>>,
+}
+
+impl ServerOverrides {
+    fn with<R>(f: impl FnOnce(&Self) -> R) -> R {
+        INVALIDATE_CACHE.call_once(|| {
+            SETTINGS.connect_changed(Some("server-overrides"), |_, _| {
+                CACHE.with_borrow_mut(|cache| cache.take());
+            });
+        });
+        CACHE.with_borrow_mut(|cache| {
+            f(cache.get_or_insert_with(|| {
+                serde_json::from_str(&SETTINGS.server_overrides()).unwrap_or_default()
+            }))
+        })
+    }
+
+    fn load() -> Self {
+        Self::with(Self::clone)
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        SETTINGS.set_server_overrides(&serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Nothing can be overridden before signing in
+    fn server(client: &JellyfinClient) -> Option<String> {
+        let session = client.session();
+        (!session.server_name_hash.is_empty()).then(|| session.account_key())
+    }
+
+    /// Override of `key` for the account `client` is signed in to
+    pub fn get(client: &JellyfinClient, key: &str) -> Option<glib::Variant> {
+        let server = Self::server(client)?;
+        let text = Self::with(|overrides| overrides.servers.get(&server)?.get(key).cloned())?;
+        let schema_key = SETTINGS.settings_schema()?.key(key);
+        glib::Variant::parse(Some(&schema_key.value_type()), &text)
+            .ok()
+            .filter(|value| schema_key.range_check(value))
+    }
+
+    pub fn has(client: &JellyfinClient, key: &str) -> bool {
+        Self::get(client, key).is_some()
+    }
+
+    /// Sets the override of `key` for the account `client` is signed in to,
+    /// `None` goes back to the global value.
+    pub fn set(
+        client: &JellyfinClient, key: &str, value: Option<&glib::Variant>,
+    ) -> anyhow::Result<()> {
+        let Some(server) = Self::server(client) else {
+            anyhow::bail!("Not signed in to a server");
+        };
+        let mut overrides = Self::load();
+        let values = overrides.servers.entry(server.to_owned()).or_default();
+        match value {
+            Some(value) => {
+                values.insert(key.to_owned(), value.print(false).to_string());
+            }
+            None => {
+                values.remove(key);
+                if values.is_empty() {
+                    overrides.servers.remove(&server);
+                }
+            }
+        }
+        overrides.save()
+    }
+}
@@ -11,13 +11,37 @@ use gtk::{
 
 use crate::{
     APP_ID,
-    client::Account,
-    ui::provider::descriptor::{
-        Descriptor,
-        VecSerialize,
+    client::{
+        Account,
+        jellyfin_client::JellyfinClient,
+        structs::MediaSegmentType,
+    },
+    ui::{
+        models::server_overrides::ServerOverrides,
+        provider::descriptor::{
+            Descriptor,
+            VecSerialize,
+        },
     },
 };
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentSkipPolicy {
+    ShowButton = 0,
+    AutoSkip = 1,
+    Ignore = 2,
+}
+
+impl From<i32> for SegmentSkipPolicy {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::AutoSkip,
+            2 => Self::Ignore,
+            _ => Self::ShowButton,
+        }
+    }
+}
+
 pub struct Settings(ThreadGuard<gio::Settings>);
 
 impl Settings {
@@ -61,6 +85,10 @@ impl Settings {
     const KEY_MPV_DEFAULT_VOLUME: &'static str = "mpv-default-volume"; // i32
     const KEY_MPV_SHOW_BUFFER_SPEED: &'static str = "mpv-show-buffer-speed"; // bool
     const KEY_MPV_ACTION_AFTER_VIDEO_END: &'static str = "mpv-action-after-video-end"; // i32
+    const KEY_MPV_UP_NEXT_LEAD_TIME: &'static str = "mpv-up-next-lead-time";
+    const KEY_MPV_IDLE_GUARD_EPISODES: &'static str = "mpv-idle-guard-episodes";
+    const KEY_MPV_IDLE_GUARD_HOURS: &'static str = "mpv-idle-guard-hours";
+    const KEY_MPV_IDLE_GUARD_TIMEOUT: &'static str = "mpv-idle-guard-timeout";
     const KEY_MPV_HWDEC: &'static str = "mpv-hwdec"; // i32
     const KEY_MPV_RENDERER: &'static str = "mpv-renderer"; // i32
 
@@ -71,7 +99,13 @@ impl Settings {
     const KEY_MPV_CONFIG_DIR: &'static str = "mpv-config-path"; // String
     const KEY_IS_REFRESH: &'static str = "is-refresh"; // bool
     const KEY_MERGE_RESUME_AND_NEXT_UP: &'static str = "merge-resume-and-next-up"; // bool
-    const KEY_AUTO_SKIP_INTRO_OUTRO: &'static str = "auto-skip-intro-outro"; // bool
+    const KEY_ALL_SERVERS_RESUME: &'static str = "all-servers-resume"; // bool
+    const KEY_AUTO_SKIP_INTRO_OUTRO: &'static str = "auto-skip-intro-outro"; // bool, deprecated
+    const KEY_SEGMENT_POLICY_INTRO: &'static str = "segment-policy-intro"; // i32
+    const KEY_SEGMENT_POLICY_OUTRO: &'static str = "segment-policy-outro"; // i32
+    const KEY_SEGMENT_POLICY_RECAP: &'static str = "segment-policy-recap"; // i32
+    const KEY_SEGMENT_POLICY_PREVIEW: &'static str = "segment-policy-preview"; // i32
+    const KEY_SEGMENT_POLICY_COMMERCIAL: &'static str = "segment-policy-commercial"; // i32
     const KEY_DEVICE_UUID: &'static str = "device-uuid"; // String
     const KEY_MAIN_THEME: &'static str = "main-theme"; // i32
     const KEY_WINDOW_WIDTH: &'static str = "window-width"; // i32
@@ -88,13 +122,49 @@ impl Settings {
     const KEY_MPV_DANMAKU_OUTLINE_SIZE: &'static str = "mpv-danmaku-outline-size";
     const KEY_MPV_DANMAKU_SHADOW_OFFSET: &'static str = "mpv-danmaku-shadow-offset";
     const KEY_DANMAKU_CACHE_MAP: &'static str = "danmaku-cache-map";
+    const KEY_SEARCH_HISTORY: &'static str = "search-history"; // String
+    const KEY_HOME_LAYOUTS: &'static str = "home-layouts"; // String
+    const KEY_SERVER_OVERRIDES: &'static str = "server-overrides"; // String
+    const KEY_TRACK_MEMORY: &'static str = "track-memory"; // String
 
-    fn bind_setting(&self, key: &str, object: &impl IsA<glib::Object>, property: &str) {
-        self.0.get_ref().bind(key, object, property).build();
+    /// Value of `key` on the server `client` is signed in to, falling back
+    /// to the global one
+    fn effective(&self, client: &JellyfinClient, key: &str) -> glib::Variant {
+        ServerOverrides::get(client, key).unwrap_or_else(|| self.value(key))
+    }
+
+    fn effective_int(&self, client: &JellyfinClient, key: &str) -> i32 {
+        self.effective(client, key)
+            .get()
+            .unwrap_or_else(|| self.int(key))
+    }
+
+    fn effective_boolean(&self, client: &JellyfinClient, key: &str) -> bool {
+        self.effective(client, key)
+            .get()
+            .unwrap_or_else(|| self.boolean(key))
+    }
+
+    fn effective_string(&self, client: &JellyfinClient, key: &str) -> String {
+        self.effective(client, key)
+            .get()
+            .unwrap_or_else(|| self.string(key).to_string())
+    }
+
+    /// Changes made from the player go to the server of `client` if it
+    /// overrides `key`, otherwise to the global value.
+    fn set_effective(
+        &self, client: &JellyfinClient, key: &str, value: glib::Variant,
+    ) -> Result<(), glib::BoolError> {
+        if ServerOverrides::has(client, key) {
+            return ServerOverrides::set(client, key, Some(&value))
+                .map_err(|e| glib::bool_error!("{}", e));
+        }
+        self.set_value(key, &value)
     }
 
-    pub fn bind_mpv_danmaku_enabled(&self, object: &impl IsA<glib::Object>, property: &str) {
-        self.bind_setting(Self::KEY_MPV_DANMAKU_ENABLED, object, property);
+    fn bind_setting(&self, key: &str, object: &impl IsA<glib::Object>, property: &str) {
+        self.0.get_ref().bind(key, object, property).build();
     }
 
     pub fn bind_mpv_danmaku_opacity(&self, object: &impl IsA<glib::Object>, property: &str) {
@@ -129,8 +199,14 @@ impl Settings {
         self.bind_setting(Self::KEY_MPV_DANMAKU_SHADOW_OFFSET, object, property);
     }
 
-    pub fn mpv_danmaku_enabled(&self) -> bool {
-        self.boolean(Self::KEY_MPV_DANMAKU_ENABLED)
+    pub fn mpv_danmaku_enabled(&self, client: &JellyfinClient) -> bool {
+        self.effective_boolean(client, Self::KEY_MPV_DANMAKU_ENABLED)
+    }
+
+    pub fn set_mpv_danmaku_enabled(
+        &self, client: &JellyfinClient, enabled: bool,
+    ) -> Result<(), glib::BoolError> {
+        self.set_effective(client, Self::KEY_MPV_DANMAKU_ENABLED, enabled.to_variant())
     }
 
     pub fn danmaku_cache_map(&self) -> String {
@@ -141,6 +217,38 @@ impl Settings {
         self.set_string(Self::KEY_DANMAKU_CACHE_MAP, value)
     }
 
+    pub fn search_history(&self) -> String {
+        self.string(Self::KEY_SEARCH_HISTORY).to_string()
+    }
+
+    pub fn set_search_history(&self, value: &str) -> Result<(), glib::BoolError> {
+        self.set_string(Self::KEY_SEARCH_HISTORY, value)
+    }
+
+    pub fn home_layouts(&self) -> String {
+        self.string(Self::KEY_HOME_LAYOUTS).to_string()
+    }
+
+    pub fn set_home_layouts(&self, value: &str) -> Result<(), glib::BoolError> {
+        self.set_string(Self::KEY_HOME_LAYOUTS, value)
+    }
+
+    pub fn server_overrides(&self) -> String {
+        self.string(Self::KEY_SERVER_OVERRIDES).to_string()
+    }
+
+    pub fn set_server_overrides(&self, value: &str) -> Result<(), glib::BoolError> {
+        self.set_string(Self::KEY_SERVER_OVERRIDES, value)
+    }
+
+    pub fn track_memory(&self) -> String {
+        self.string(Self::KEY_TRACK_MEMORY).to_string()
+    }
+
+    pub fn set_track_memory(&self, value: &str) -> Result<(), glib::BoolError> {
+        self.set_string(Self::KEY_TRACK_MEMORY, value)
+    }
+
     pub fn is_overlay(&self) -> bool {
         self.boolean(Self::KEY_IS_OVERLAY)
     }
@@ -197,8 +305,31 @@ impl Settings {
         self.boolean(Self::KEY_MERGE_RESUME_AND_NEXT_UP)
     }
 
-    pub fn auto_skip_intro_outro(&self) -> bool {
-        self.boolean(Self::KEY_AUTO_SKIP_INTRO_OUTRO)
+    pub fn all_servers_resume(&self) -> bool {
+        self.boolean(Self::KEY_ALL_SERVERS_RESUME)
+    }
+
+    pub fn segment_skip_policy(&self, segment_type: MediaSegmentType) -> SegmentSkipPolicy {
+        let key = match segment_type {
+            MediaSegmentType::Intro => Self::KEY_SEGMENT_POLICY_INTRO,
+            MediaSegmentType::Outro => Self::KEY_SEGMENT_POLICY_OUTRO,
+            MediaSegmentType::Recap => Self::KEY_SEGMENT_POLICY_RECAP,
+            MediaSegmentType::Preview => Self::KEY_SEGMENT_POLICY_PREVIEW,
+            MediaSegmentType::Commercial => Self::KEY_SEGMENT_POLICY_COMMERCIAL,
+            _ => return SegmentSkipPolicy::Ignore,
+        };
+
+        // Respect the old global switch until the user picks a policy for this type
+        if matches!(
+            segment_type,
+            MediaSegmentType::Intro | MediaSegmentType::Outro
+        ) && self.user_value(key).is_none()
+            && self.boolean(Self::KEY_AUTO_SKIP_INTRO_OUTRO)
+        {
+            return SegmentSkipPolicy::AutoSkip;
+        }
+
+        SegmentSkipPolicy::from(self.int(key))
     }
 
     pub fn item_text_display(&self) -> String {
@@ -405,12 +536,14 @@ impl Settings {
         )
     }
 
-    pub fn set_mpv_hwdec(&self, mpv_hwdec: i32) -> Result<(), glib::BoolError> {
-        self.set_int(Self::KEY_MPV_HWDEC, mpv_hwdec)
+    pub fn set_mpv_hwdec(
+        &self, client: &JellyfinClient, mpv_hwdec: i32,
+    ) -> Result<(), glib::BoolError> {
+        self.set_effective(client, Self::KEY_MPV_HWDEC, mpv_hwdec.to_variant())
     }
 
-    pub fn mpv_hwdec(&self) -> i32 {
-        self.int(Self::KEY_MPV_HWDEC)
+    pub fn mpv_hwdec(&self, client: &JellyfinClient) -> i32 {
+        self.effective_int(client, Self::KEY_MPV_HWDEC)
     }
 
     pub fn mpv_renderer(&self) -> i32 {
@@ -429,28 +562,20 @@ impl Settings {
         self.int(Self::KEY_LIST_SORT_ORDER)
     }
 
-    pub fn mpv_audio_preferred_lang(&self) -> i32 {
-        self.int(Self::KEY_MPV_AUDIO_PREFERRED_LANG)
+    pub fn mpv_audio_preferred_lang(&self, client: &JellyfinClient) -> i32 {
+        self.effective_int(client, Self::KEY_MPV_AUDIO_PREFERRED_LANG)
     }
 
-    pub fn mpv_subtitle_preferred_lang(&self) -> i32 {
-        self.int(Self::KEY_MPV_SUBTITLE_PREFERRED_LANG)
+    pub fn mpv_audio_preferred_lang_str(&self, client: &JellyfinClient) -> String {
+        lang_code(self.mpv_audio_preferred_lang(client))
     }
 
-    pub fn mpv_subtitle_preferred_lang_str(&self) -> String {
-        match self.mpv_subtitle_preferred_lang() {
-            1 => "eng",
-            2 => "chs",
-            3 => "jpn",
-            4 => "chi",
-            5 => "ara",
-            6 => "nob",
-            7 => "por",
-            8 => "fre",
-            9 => "rus",
-            _ => "",
-        }
-        .to_string()
+    pub fn mpv_subtitle_preferred_lang(&self, client: &JellyfinClient) -> i32 {
+        self.effective_int(client, Self::KEY_MPV_SUBTITLE_PREFERRED_LANG)
+    }
+
+    pub fn mpv_subtitle_preferred_lang_str(&self, client: &JellyfinClient) -> String {
+        lang_code(self.mpv_subtitle_preferred_lang(client))
     }
 
     pub fn mpv_default_volume(&self) -> i32 {
@@ -480,12 +605,48 @@ impl Settings {
         self.int(Self::KEY_MPV_ACTION_AFTER_VIDEO_END)
     }
 
-    pub fn mpv_cache_time(&self) -> i32 {
-        self.int(Self::KEY_MPV_CACHE_TIME)
+    pub fn mpv_up_next_lead_time(&self) -> i32 {
+        self.int(Self::KEY_MPV_UP_NEXT_LEAD_TIME)
+    }
+
+    pub fn mpv_idle_guard_episodes(&self) -> i32 {
+        self.int(Self::KEY_MPV_IDLE_GUARD_EPISODES)
+    }
+
+    pub fn mpv_idle_guard_hours(&self) -> i32 {
+        self.int(Self::KEY_MPV_IDLE_GUARD_HOURS)
     }
 
-    pub fn mpv_cache_size(&self) -> i32 {
-        self.int(Self::KEY_MPV_CACHE_SIZE)
+    pub fn mpv_idle_guard_timeout(&self) -> i32 {
+        self.int(Self::KEY_MPV_IDLE_GUARD_TIMEOUT)
+    }
+
+    pub fn mpv_cache_time(&self, client: &JellyfinClient) -> i32 {
+        self.effective_int(client, Self::KEY_MPV_CACHE_TIME)
+    }
+
+    pub fn mpv_cache_size(&self, client: &JellyfinClient) -> i32 {
+        self.effective_int(client, Self::KEY_MPV_CACHE_SIZE)
+    }
+
+    pub fn set_mpv_cache_time(
+        &self, client: &JellyfinClient, mpv_cache_time: i32,
+    ) -> Result<(), glib::BoolError> {
+        self.set_effective(
+            client,
+            Self::KEY_MPV_CACHE_TIME,
+            mpv_cache_time.to_variant(),
+        )
+    }
+
+    pub fn set_mpv_cache_size(
+        &self, client: &JellyfinClient, mpv_cache_size: i32,
+    ) -> Result<(), glib::BoolError> {
+        self.set_effective(
+            client,
+            Self::KEY_MPV_CACHE_SIZE,
+            mpv_cache_size.to_variant(),
+        )
     }
 
     pub fn mpv_config(&self) -> bool {
@@ -500,12 +661,18 @@ impl Settings {
         self.int(Self::KEY_MPV_SEEK_BACKWARD_STEP)
     }
 
-    pub fn set_music_repeat_mode(&self, music_repeat_mode: &str) -> Result<(), glib::BoolError> {
-        self.set_string(Self::KEY_MUSIC_REPEAT_MODE, music_repeat_mode)
+    pub fn set_music_repeat_mode(
+        &self, client: &JellyfinClient, music_repeat_mode: &str,
+    ) -> Result<(), glib::BoolError> {
+        self.set_effective(
+            client,
+            Self::KEY_MUSIC_REPEAT_MODE,
+            music_repeat_mode.to_variant(),
+        )
     }
 
-    pub fn music_repeat_mode(&self) -> String {
-        self.string(Self::KEY_MUSIC_REPEAT_MODE).to_string()
+    pub fn music_repeat_mode(&self, client: &JellyfinClient) -> String {
+        self.effective_string(client, Self::KEY_MUSIC_REPEAT_MODE)
     }
 
     pub fn set_accent_color_code(&self, accent_color_code: &str) -> Result<(), glib::BoolError> {
@@ -580,6 +747,23 @@ impl Settings {
     }
 }
 
+/// Language code for the index of the preferred language combo rows
+fn lang_code(index: i32) -> String {
+    match index {
+        1 => "eng",
+        2 => "chs",
+        3 => "jpn",
+        4 => "chi",
+        5 => "ara",
+        6 => "nob",
+        7 => "por",
+        8 => "fre",
+        9 => "rus",
+        _ => "",
+    }
+    .to_string()
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self(ThreadGuard::new(gio::Settings::new(APP_ID)))
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+
+use anyhow::{
+    Result,
+    anyhow,
+    bail,
+};
+use gettextrs::gettext;
+use gtk::{
+    gio::prelude::*,
+    glib,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+
+use super::SETTINGS;
+use crate::{
+    client::Account,
+    ui::{
+        mpv::danmaku_cache_map::DanmakuCacheMap,
+        provider::descriptor::Descriptor,
+    },
+};
+
+pub const BUNDLE_VERSION: u64 = 1;
+
+/// Keys with their own field in the bundle, or that only make sense on this
+/// machine.
+const SKIPPED_KEYS: &[&str] = &[
+    "accounts",
+    "video-version-descriptors",
+    "danmaku-cache-map",
+    "device-uuid",
+    "window-width",
+    "window-height",
+    "is-maximized",
+    "is-fullscreen",
+];
+
+/// Accounts and settings, as written by "Export Settings".
+#[derive(Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub version: u64,
+    /// Whether passwords and access tokens were exported
+    pub includes_secrets: bool,
+    pub accounts: Vec<Account>,
+    /// Every other settings key, values in GVariant text format
+    pub settings: BTreeMap<String, String>,
+    pub preferred_version_descriptors: Vec<Descriptor>,
+    pub danmaku_cache_map: Value,
+}
+
+/// One line of the import preview.
+pub struct BundleChange {
+    pub title: String,
+    pub detail: String,
+}
+
+impl SettingsBundle {
+    pub fn export(include_secrets: bool) -> Result<Self> {
+        let schema = SETTINGS
+            .settings_schema()
+            .ok_or_else(|| anyhow!("Settings schema not found"))?;
+        let settings = schema
+            .list_keys()
+            .into_iter()
+            .filter(|key| !SKIPPED_KEYS.contains(&key.as_str()))
+            .map(|key| {
+                let value = SETTINGS.value(&key).print(false).to_string();
+                (key.to_string(), value)
+            })
+            .collect();
+
+        let mut accounts = SETTINGS.accounts();
+        if !include_secrets {
+            for account in &mut accounts {
+                account.password.clear();
+                account.access_token.clear();
+            }
+        }
+
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            includes_secrets: include_secrets,
+            accounts,
+            settings,
+            preferred_version_descriptors: SETTINGS.preferred_version_descriptors(),
+            danmaku_cache_map: serde_json::from_str(&SETTINGS.danmaku_cache_map())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reads a bundle and checks every value against the settings schema.
+    pub fn parse(text: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(text)?;
+        check_version(&value)?;
+        let bundle: Self = serde_json::from_value(value)?;
+        serde_json::from_value::<DanmakuCacheMap>(bundle.danmaku_cache_map.to_owned())?;
+        bundle.values()?;
+        Ok(bundle)
+    }
+
+    /// Settings values of the bundle that this version knows about, keys
+    /// removed from the schema since the export are left out.
+    fn values(&self) -> Result<Vec<(String, glib::Variant)>> {
+        let schema = SETTINGS
+            .settings_schema()
+            .ok_or_else(|| anyhow!("Settings schema not found"))?;
+        let mut values = Vec::new();
+        for (key, text) in &self.settings {
+            if SKIPPED_KEYS.contains(&key.as_str()) || !schema.has_key(key) {
+                continue;
+            }
+            let schema_key = schema.key(key);
+            let value = glib::Variant::parse(Some(&schema_key.value_type()), text)
+                .ok()
+                .filter(|value| schema_key.range_check(value))
+                .ok_or_else(|| anyhow!(gettext("Invalid value for {key}").replace("{key}", key)))?;
+            values.push((key.to_owned(), value));
+        }
+        Ok(values)
+    }
+
+    /// Accounts after merging the bundle into the configured ones, matched by
+    /// server and user. Accounts exported without secrets keep the ones
+    /// already stored here.
+    fn merged_accounts(&self) -> Vec<Account> {
+        let mut accounts = SETTINGS.accounts();
+        for account in &self.accounts {
+            let mut account = account.to_owned();
+            if let Some(pos) = accounts.iter().position(|a| a.key() == account.key()) {
+                let existing = accounts.remove(pos);
+                if !self.includes_secrets {
+                    account.password = existing.password;
+                    account.access_token = existing.access_token;
+                }
+            }
+            accounts.push(account);
+        }
+        accounts
+    }
+
+    /// What importing would change, for the preview.
+    pub fn changes(&self) -> Result<Vec<BundleChange>> {
+        let schema = SETTINGS
+            .settings_schema()
+            .ok_or_else(|| anyhow!("Settings schema not found"))?;
+        let mut changes = Vec::new();
+
+        let current = SETTINGS.accounts();
+        for account in self.merged_accounts() {
+            let existing = current.iter().find(|a| a.key() == account.key());
+            if existing == Some(&account) {
+                continue;
+            }
+            let title = if existing.is_some() {
+                gettext("Update server {name}")
+            } else {
+                gettext("Add server {name}")
+            }
+            .replace("{name}", &account.servername);
+            let detail = if account.access_token.is_empty() {
+                gettext("Sign in required")
+            } else {
+                format!("{}@{}", account.username, account.server)
+            };
+            changes.push(BundleChange { title, detail });
+        }
+
+        for (key, value) in self.values()? {
+            let old = SETTINGS.value(&key);
+            if old == value {
+                continue;
+            }
+            let summary = schema
+                .key(&key)
+                .summary()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| key.to_owned());
+            changes.push(BundleChange {
+                title: summary,
+                detail: format!("{} → {}", old.print(false), value.print(false)),
+            });
+        }
+
+        if self.preferred_version_descriptors != SETTINGS.preferred_version_descriptors() {
+            changes.push(BundleChange {
+                title: gettext("Preferred Video Version"),
+                detail: gettext("{count} rules").replace(
+                    "{count}",
+                    &self.preferred_version_descriptors.len().to_string(),
+                ),
+            });
+        }
+
+        if serde_json::from_str::<Value>(&SETTINGS.danmaku_cache_map()).ok()
+            != Some(self.danmaku_cache_map.to_owned())
+        {
+            changes.push(BundleChange {
+                title: gettext("Danmaku Matches"),
+                detail: gettext("Remembered danmaku selections"),
+            });
+        }
+
+        Ok(changes)
+    }
+
+    pub fn apply(&self) -> Result<()> {
+        let values = self.values()?;
+        let accounts = self.merged_accounts();
+
+        // Write everything at once, or nothing if a key is rejected
+        SETTINGS.delay();
+        let result = (|| -> Result<()> {
+            for (key, value) in values {
+                SETTINGS.set_value(&key, &value)?;
+            }
+            SETTINGS.set_accounts(accounts)?;
+            SETTINGS
+                .set_preferred_version_descriptors(self.preferred_version_descriptors.to_owned())?;
+            SETTINGS.set_danmaku_cache_map(&self.danmaku_cache_map.to_string())?;
+            Ok(())
+        })();
+        if result.is_ok() {
+            SETTINGS.apply();
+        } else {
+            SETTINGS.revert();
+        }
+        result
+    }
+}
+
+fn check_version(value: &Value) -> Result<()> {
+    let Some(version) = value.get("version").and_then(Value::as_u64) else {
+        bail!(gettext("Not a Tsukimi settings file"));
+    };
+    if version > BUNDLE_VERSION {
+        bail!(gettext(
+            "This file was exported by a newer version of Tsukimi"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_version_rejects_newer_bundle() {
+        assert!(check_version(&serde_json::json!({ "version": BUNDLE_VERSION })).is_ok());
+        assert!(check_version(&serde_json::json!({ "version": BUNDLE_VERSION + 1 })).is_err());
+        assert!(check_version(&serde_json::json!({})).is_err());
+    }
+}
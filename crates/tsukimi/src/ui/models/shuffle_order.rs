@@ -0,0 +1,91 @@
+use rand::seq::SliceRandom;
+
+/// Remembers the order of a queue while it plays shuffled, so that turning
+/// shuffle off puts it back.
+#[derive(Debug)]
+pub struct ShuffleOrder<T> {
+    original: Vec<T>,
+}
+
+impl<T> Default for ShuffleOrder<T> {
+    fn default() -> Self {
+        Self {
+            original: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> ShuffleOrder<T> {
+    /// Remembers `items` as the original order and returns them shuffled,
+    /// with `first`, the playing item, in front.
+    pub fn shuffle(&mut self, items: Vec<T>, first: Option<&T>) -> Vec<T> {
+        self.original = items.clone();
+        let mut shuffled = items;
+        shuffled.shuffle(&mut rand::rng());
+        if let Some(position) = first.and_then(|first| shuffled.iter().position(|i| i == first)) {
+            let first = shuffled.remove(position);
+            shuffled.insert(0, first);
+        }
+        shuffled
+    }
+
+    /// Shuffles the whole queue for the next round of repeat-all, `last`
+    /// being the track that ends this round. The new round never starts
+    /// with `last`.
+    pub fn next_round(items: &[T], last: &T) -> Vec<T> {
+        let mut shuffled = items.to_vec();
+        shuffled.shuffle(&mut rand::rng());
+        if shuffled.len() > 1 && shuffled.first() == Some(last) {
+            let other = rand::random_range(1..shuffled.len());
+            shuffled.swap(0, other);
+        }
+        shuffled
+    }
+
+    /// `items` in their original order. Items that were queued while
+    /// shuffled go last, in the order they are queued in now.
+    pub fn unshuffle(&mut self, items: Vec<T>) -> Vec<T> {
+        let original = std::mem::take(&mut self.original);
+        let mut ordered: Vec<T> = original
+            .into_iter()
+            .filter(|item| items.contains(item))
+            .collect();
+        let added: Vec<T> = items
+            .into_iter()
+            .filter(|item| !ordered.contains(item))
+            .collect();
+        ordered.extend(added);
+        ordered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shuffles_around_the_playing_item_and_restores_the_order() {
+        let mut order = ShuffleOrder::default();
+        let shuffled = order.shuffle((1..=10).collect(), Some(&4));
+
+        assert_eq!(shuffled[0], 4);
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        assert_eq!(sorted, (1..=10).collect::<Vec<_>>());
+
+        let mut last_moved = false;
+        for _ in 0..50 {
+            let round = ShuffleOrder::next_round(&[1, 2, 3], &3);
+            assert_ne!(round[0], 3);
+            let mut sorted = round.clone();
+            sorted.sort();
+            assert_eq!(sorted, vec![1, 2, 3]);
+            last_moved |= round[2] != 3;
+        }
+        assert!(last_moved);
+
+        let mut queue: Vec<_> = shuffled.into_iter().filter(|i| *i != 7).collect();
+        queue.insert(1, 11);
+        assert_eq!(order.unshuffle(queue), vec![1, 2, 3, 4, 5, 6, 8, 9, 10, 11]);
+    }
+}
@@ -0,0 +1,164 @@
+use gettextrs::{
+    gettext,
+    ngettext,
+};
+
+const MICROS_PER_MINUTE: i64 = 60 * 1_000_000;
+
+/// The volume fades out over this many seconds before playback stops.
+pub const FADE_OUT_SECONDS: f64 = 60.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SleepTimerMode {
+    #[default]
+    Off,
+    /// Stop after this many minutes
+    Minutes(u32),
+    /// Stop at the end of the current episode or track
+    EndOfItem,
+    /// Stop once this many items finished, the current one included
+    Items(u32),
+}
+
+impl SleepTimerMode {
+    /// Parses the target of the sleep timer menu actions, e.g. `minutes-30`.
+    pub fn from_target(target: &str) -> Self {
+        match target.split_once('-') {
+            Some(("minutes", minutes)) => minutes.parse().map_or(Self::Off, Self::Minutes),
+            Some(("items", items)) => items.parse().map_or(Self::Off, Self::Items),
+            _ if target == "end" => Self::EndOfItem,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// Stops playback after a while, shared by the video and the music player.
+///
+/// Times are `glib::monotonic_time` microseconds, the players tick it once a
+/// second and tell it whenever an item finished.
+#[derive(Debug, Default)]
+pub struct SleepTimer {
+    mode: SleepTimerMode,
+    deadline: i64,
+    items_left: u32,
+}
+
+impl SleepTimer {
+    pub fn set(&mut self, mode: SleepTimerMode, now: i64) {
+        self.mode = mode;
+        match mode {
+            SleepTimerMode::Minutes(minutes) => {
+                self.deadline = now + minutes as i64 * MICROS_PER_MINUTE;
+            }
+            SleepTimerMode::EndOfItem => self.items_left = 1,
+            SleepTimerMode::Items(items) => self.items_left = items.max(1),
+            SleepTimerMode::Off => {}
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.mode = SleepTimerMode::Off;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.mode != SleepTimerMode::Off
+    }
+
+    /// Whether playback stops at the end of the current item.
+    pub fn on_last_item(&self) -> bool {
+        matches!(
+            self.mode,
+            SleepTimerMode::EndOfItem | SleepTimerMode::Items(_)
+        ) && self.items_left <= 1
+    }
+
+    /// Counts a finished item, returns whether playback should stop now.
+    pub fn item_finished(&mut self) -> bool {
+        if !matches!(
+            self.mode,
+            SleepTimerMode::EndOfItem | SleepTimerMode::Items(_)
+        ) {
+            return false;
+        }
+
+        self.items_left = self.items_left.saturating_sub(1);
+        if self.items_left > 0 {
+            return false;
+        }
+        self.cancel();
+        true
+    }
+
+    /// Whether a timer counting minutes ran out.
+    pub fn expired(&self, now: i64) -> bool {
+        matches!(self.mode, SleepTimerMode::Minutes(_)) && now >= self.deadline
+    }
+
+    /// Seconds until playback stops, if it stops before the next item.
+    pub fn remaining(&self, now: i64, item_remaining: f64) -> Option<f64> {
+        match self.mode {
+            SleepTimerMode::Minutes(_) => Some(((self.deadline - now) as f64 / 1e6).max(0.0)),
+            _ if self.on_last_item() => Some(item_remaining.max(0.0)),
+            _ => None,
+        }
+    }
+
+    /// Factor for the player volume, going down to 0 over the last minute.
+    pub fn volume_factor(&self, now: i64, item_remaining: f64) -> f64 {
+        self.remaining(now, item_remaining)
+            .map_or(1.0, |remaining| {
+                (remaining / FADE_OUT_SECONDS).clamp(0.0, 1.0)
+            })
+    }
+
+    /// Shown in the MPRIS metadata of the player.
+    pub fn describe(&self, now: i64) -> Option<String> {
+        match self.mode {
+            SleepTimerMode::Off => None,
+            SleepTimerMode::Minutes(_) => {
+                let minutes =
+                    ((self.deadline - now).max(0) as f64 / MICROS_PER_MINUTE as f64).ceil() as u32;
+                Some(
+                    ngettext(
+                        "Sleep timer: {minutes} minute left",
+                        "Sleep timer: {minutes} minutes left",
+                        minutes,
+                    )
+                    .replace("{minutes}", &minutes.to_string()),
+                )
+            }
+            _ if self.on_last_item() => Some(gettext("Sleep timer: stops after this one")),
+            _ => Some(
+                ngettext(
+                    "Sleep timer: stops after {items} item",
+                    "Sleep timer: stops after {items} items",
+                    self.items_left,
+                )
+                .replace("{items}", &self.items_left.to_string()),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_items_and_fades_the_last_one() {
+        let mut timer = SleepTimer::default();
+        timer.set(SleepTimerMode::from_target("items-2"), 0);
+
+        assert_eq!(timer.volume_factor(0, 30.0), 1.0);
+        assert!(!timer.item_finished());
+        assert!(timer.on_last_item());
+        assert_eq!(timer.volume_factor(0, 30.0), 0.5);
+        assert!(timer.item_finished());
+        assert!(!timer.is_active());
+
+        timer.set(SleepTimerMode::from_target("minutes-10"), 0);
+        assert!(!timer.expired(9 * MICROS_PER_MINUTE));
+        assert_eq!(timer.volume_factor(9 * MICROS_PER_MINUTE, 0.0), 1.0);
+        assert!(timer.expired(10 * MICROS_PER_MINUTE));
+    }
+}
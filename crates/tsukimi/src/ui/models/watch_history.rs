@@ -0,0 +1,476 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    path::PathBuf,
+};
+
+use chrono::{
+    DateTime,
+    Datelike,
+    Days,
+    Local,
+    Months,
+    NaiveDate,
+    TimeZone,
+    Utc,
+};
+use rusqlite::{
+    Connection,
+    params,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::utils::spawn_tokio_blocking;
+
+/// Part of the runtime that has to be reached for an item to count as watched.
+const COMPLETED_RATIO: f64 = 0.9;
+const TOP_ENTRIES: usize = 5;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        id INTEGER PRIMARY KEY,
+        account TEXT NOT NULL,
+        item_id TEXT NOT NULL,
+        item_name TEXT NOT NULL,
+        series_id TEXT,
+        series_name TEXT,
+        season INTEGER,
+        episode INTEGER,
+        season_episodes INTEGER,
+        play_method TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        start_position REAL NOT NULL,
+        end_position REAL NOT NULL,
+        runtime REAL NOT NULL,
+        watched REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS sessions_account ON sessions (account, started_at);
+    CREATE TABLE IF NOT EXISTS session_genres (
+        session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+        genre TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS session_genres_session ON session_genres (session_id);
+";
+
+/// One stretch of playback, from the start report to the stop report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchSession {
+    /// Key of the account the item was played with, see
+    /// [`Account::key`](crate::client::Account::key)
+    #[serde(skip)]
+    pub account: String,
+    pub item_id: String,
+    pub item_name: String,
+    pub series_id: Option<String>,
+    pub series_name: Option<String>,
+    pub season: Option<u32>,
+    pub episode: Option<u32>,
+    /// Episodes of the season in the playlist the item was played from
+    pub season_episodes: Option<u32>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    pub play_method: String,
+    pub started_at: DateTime<Utc>,
+    /// Positions and durations are in seconds
+    pub start_position: f64,
+    pub end_position: f64,
+    pub runtime: f64,
+    /// Time actually spent playing, seeking doesn't count
+    pub watched: f64,
+}
+
+/// Playback sessions of every account, kept in an SQLite database in the user
+/// data directory. Totals are aggregated by the database, except for the
+/// daily ones, which depend on the time zone.
+pub struct WatchHistory;
+
+impl WatchHistory {
+    fn path() -> PathBuf {
+        gtk::glib::user_data_dir()
+            .join("tsukimi")
+            .join("watch-history.db")
+    }
+
+    fn open() -> anyhow::Result<Connection> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)?;
+        Self::init(&conn)?;
+        Ok(conn)
+    }
+
+    fn init(conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)
+    }
+
+    pub async fn append(session: WatchSession) -> anyhow::Result<()> {
+        spawn_tokio_blocking(move || Self::insert(&mut Self::open()?, &session)).await
+    }
+
+    /// Recorded sessions of `account`, oldest first.
+    pub async fn load(account: String) -> anyhow::Result<Vec<WatchSession>> {
+        spawn_tokio_blocking(move || Self::sessions(&Self::open()?, &account)).await
+    }
+
+    /// Statistics of `account` as of `today`, days are counted in local time.
+    pub async fn stats(account: String, today: NaiveDate) -> anyhow::Result<WatchStats> {
+        spawn_tokio_blocking(move || WatchStats::query(&Self::open()?, &account, today, &Local))
+            .await
+    }
+
+    fn insert(conn: &mut Connection, session: &WatchSession) -> anyhow::Result<()> {
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO sessions (
+                account, item_id, item_name, series_id, series_name, season, episode,
+                season_episodes, play_method, started_at, start_position, end_position,
+                runtime, watched
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                session.account,
+                session.item_id,
+                session.item_name,
+                session.series_id,
+                session.series_name,
+                session.season,
+                session.episode,
+                session.season_episodes,
+                session.play_method,
+                session.started_at,
+                session.start_position,
+                session.end_position,
+                session.runtime,
+                session.watched,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        for genre in &session.genres {
+            tx.execute(
+                "INSERT INTO session_genres (session_id, genre) VALUES (?1, ?2)",
+                params![id, genre],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn sessions(conn: &Connection, account: &str) -> anyhow::Result<Vec<WatchSession>> {
+        let mut genres: HashMap<i64, Vec<String>> = HashMap::new();
+        let mut statement = conn.prepare(
+            "SELECT session_id, genre FROM session_genres
+            JOIN sessions ON sessions.id = session_genres.session_id
+            WHERE account = ?1",
+        )?;
+        for row in statement.query_map([account], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (id, genre) = row?;
+            genres.entry(id).or_default().push(genre);
+        }
+
+        let mut statement = conn.prepare(
+            "SELECT id, item_id, item_name, series_id, series_name, season, episode,
+                season_episodes, play_method, started_at, start_position, end_position,
+                runtime, watched
+            FROM sessions WHERE account = ?1 ORDER BY started_at, id",
+        )?;
+        let sessions = statement
+            .query_map([account], |row| {
+                Ok(WatchSession {
+                    account: account.to_owned(),
+                    item_id: row.get(1)?,
+                    item_name: row.get(2)?,
+                    series_id: row.get(3)?,
+                    series_name: row.get(4)?,
+                    season: row.get(5)?,
+                    episode: row.get(6)?,
+                    season_episodes: row.get(7)?,
+                    genres: genres.remove(&row.get::<_, i64>(0)?).unwrap_or_default(),
+                    play_method: row.get(8)?,
+                    started_at: row.get(9)?,
+                    start_position: row.get(10)?,
+                    end_position: row.get(11)?,
+                    runtime: row.get(12)?,
+                    watched: row.get(13)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(sessions)
+    }
+
+    pub fn to_json(sessions: &[WatchSession]) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(sessions)?)
+    }
+
+    pub fn to_csv(sessions: &[WatchSession]) -> String {
+        let mut csv = String::from(
+            "started_at,item_id,item_name,series_name,season,episode,genres,play_method,start_position,end_position,runtime,watched\n",
+        );
+        for session in sessions {
+            let fields = [
+                session.started_at.to_rfc3339(),
+                session.item_id.to_owned(),
+                session.item_name.to_owned(),
+                session.series_name.to_owned().unwrap_or_default(),
+                session.season.map(|s| s.to_string()).unwrap_or_default(),
+                session.episode.map(|e| e.to_string()).unwrap_or_default(),
+                session.genres.join("; "),
+                session.play_method.to_owned(),
+                format!("{:.0}", session.start_position),
+                format!("{:.0}", session.end_position),
+                format!("{:.0}", session.runtime),
+                format!("{:.0}", session.watched),
+            ];
+            let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&line.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedSeason {
+    pub series_name: String,
+    pub season: u32,
+}
+
+/// What the statistics page shows, watch times are in seconds.
+#[derive(Debug, Default)]
+pub struct WatchStats {
+    pub today: f64,
+    pub this_week: f64,
+    pub this_month: f64,
+    /// The last 7 days, oldest first
+    pub daily: Vec<(NaiveDate, f64)>,
+    /// The last 4 weeks by their Monday, oldest first
+    pub weekly: Vec<(NaiveDate, f64)>,
+    /// The last 6 months by their first day, oldest first
+    pub monthly: Vec<(NaiveDate, f64)>,
+    pub top_series: Vec<(String, f64)>,
+    pub top_genres: Vec<(String, f64)>,
+    /// Days in a row with something watched, up to today or yesterday
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub completed_seasons: Vec<CompletedSeason>,
+}
+
+impl WatchStats {
+    /// Days are counted in `tz`, like the start times are shown.
+    fn query<Tz: TimeZone>(
+        conn: &Connection, account: &str, today: NaiveDate, tz: &Tz,
+    ) -> anyhow::Result<Self> {
+        let mut statement =
+            conn.prepare("SELECT started_at, watched FROM sessions WHERE account = ?1")?;
+        let mut per_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        for row in statement.query_map([account], |row| {
+            Ok((row.get::<_, DateTime<Utc>>(0)?, row.get::<_, f64>(1)?))
+        })? {
+            let (started_at, watched) = row?;
+            *per_day
+                .entry(started_at.with_timezone(tz).date_naive())
+                .or_default() += watched;
+        }
+
+        let mut statement = conn.prepare(
+            "SELECT series_name, SUM(watched) AS total FROM sessions
+            WHERE account = ?1 AND series_name IS NOT NULL
+            GROUP BY series_name ORDER BY total DESC, series_name LIMIT ?2",
+        )?;
+        let top_series = statement
+            .query_map(params![account, TOP_ENTRIES], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut statement = conn.prepare(
+            "SELECT genre, SUM(watched) AS total FROM session_genres
+            JOIN sessions ON sessions.id = session_genres.session_id
+            WHERE account = ?1 GROUP BY genre ORDER BY total DESC, genre LIMIT ?2",
+        )?;
+        let top_genres = statement
+            .query_map(params![account, TOP_ENTRIES], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        // A season is completed once as many of its episodes were watched to
+        // the end as the playlist it was played from had
+        let mut statement = conn.prepare(
+            "SELECT MIN(series_name), season FROM sessions
+            WHERE account = ?1 AND series_id IS NOT NULL AND series_name IS NOT NULL
+                AND season IS NOT NULL AND episode IS NOT NULL
+            GROUP BY series_id, season
+            HAVING MAX(season_episodes) > 0 AND COUNT(DISTINCT CASE
+                WHEN runtime > 0 AND end_position >= runtime * ?2 THEN episode
+            END) >= MAX(season_episodes)
+            ORDER BY series_id, season",
+        )?;
+        let completed_seasons = statement
+            .query_map(params![account, COMPLETED_RATIO], |row| {
+                Ok(CompletedSeason {
+                    series_name: row.get(0)?,
+                    season: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(Self {
+            top_series,
+            top_genres,
+            completed_seasons,
+            ..Self::from_days(&per_day, today)
+        })
+    }
+
+    /// The watch times and streaks, from the watch time of each day.
+    fn from_days(per_day: &BTreeMap<NaiveDate, f64>, today: NaiveDate) -> Self {
+        let sum_since = |start: NaiveDate, end: NaiveDate| -> f64 {
+            per_day.range(start..end).map(|(_, watched)| watched).sum()
+        };
+        let week_start =
+            |date: NaiveDate| date - Days::new(date.weekday().num_days_from_monday() as u64);
+        let month_start = |date: NaiveDate| date.with_day(1).unwrap_or(date);
+        let tomorrow = today + Days::new(1);
+
+        let daily = (0..7)
+            .rev()
+            .map(|i| {
+                let day = today - Days::new(i);
+                (day, sum_since(day, day + Days::new(1)))
+            })
+            .collect();
+        let weekly = (0..4)
+            .rev()
+            .map(|i| {
+                let start = week_start(today) - Days::new(i * 7);
+                (start, sum_since(start, start + Days::new(7)))
+            })
+            .collect();
+        let monthly = (0..6)
+            .rev()
+            .filter_map(|i| {
+                let start = month_start(today).checked_sub_months(Months::new(i))?;
+                let end = start.checked_add_months(Months::new(1))?;
+                Some((start, sum_since(start, end)))
+            })
+            .collect();
+
+        let (current_streak, longest_streak) = streaks(per_day.keys().copied(), today);
+
+        Self {
+            today: sum_since(today, tomorrow),
+            this_week: sum_since(week_start(today), tomorrow),
+            this_month: sum_since(month_start(today), tomorrow),
+            daily,
+            weekly,
+            monthly,
+            current_streak,
+            longest_streak,
+            ..Default::default()
+        }
+    }
+}
+
+/// `days` has to be sorted and free of duplicates.
+fn streaks(days: impl Iterator<Item = NaiveDate>, today: NaiveDate) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut last: Option<NaiveDate> = None;
+    for day in days {
+        run = match last {
+            Some(last) if last + Days::new(1) == day => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        last = Some(day);
+    }
+
+    let current = match last {
+        Some(last) if last == today || last + Days::new(1) == today => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(day: u32, episode: u32, watched: f64) -> WatchSession {
+        WatchSession {
+            account: "server:user".to_owned(),
+            item_id: format!("ep{episode}"),
+            item_name: format!("Episode {episode}"),
+            series_id: Some("series".to_owned()),
+            series_name: Some("Series".to_owned()),
+            season: Some(1),
+            episode: Some(episode),
+            season_episodes: Some(2),
+            genres: vec!["Drama".to_owned()],
+            play_method: "DirectPlay".to_owned(),
+            started_at: format!("2026-03-{day:02}T12:00:00Z").parse().unwrap(),
+            start_position: 0.0,
+            end_position: watched,
+            runtime: 1400.0,
+            watched,
+        }
+    }
+
+    #[test]
+    fn stats_count_streaks_and_completed_seasons() {
+        let sessions = [
+            episode(2, 1, 1400.0),
+            episode(4, 2, 600.0),
+            episode(5, 2, 1400.0),
+        ];
+        let mut conn = Connection::open_in_memory().unwrap();
+        WatchHistory::init(&conn).unwrap();
+        for session in &sessions {
+            WatchHistory::insert(&mut conn, session).unwrap();
+        }
+        let today = NaiveDate::from_ymd_opt(2026, 3, 5).unwrap();
+        let stats = WatchStats::query(&conn, "server:user", today, &Utc).unwrap();
+
+        assert_eq!(stats.today, 1400.0);
+        assert_eq!(stats.current_streak, 2);
+        assert_eq!(stats.longest_streak, 2);
+        assert_eq!(stats.top_genres, vec![("Drama".to_owned(), 3400.0)]);
+        assert_eq!(
+            stats.completed_seasons,
+            vec![CompletedSeason {
+                series_name: "Series".to_owned(),
+                season: 1,
+            }]
+        );
+        assert_eq!(
+            WatchHistory::sessions(&conn, "server:user").unwrap(),
+            sessions
+        );
+        assert!(
+            WatchHistory::sessions(&conn, "server:other")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            WatchHistory::to_csv(&sessions[..1]).lines().nth(1),
+            Some(
+                "2026-03-02T12:00:00+00:00,ep1,Episode 1,Series,1,1,Drama,DirectPlay,0,1400,1400,1400"
+            )
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::{
     prelude::*,
     subclass::prelude::*,
@@ -16,9 +18,16 @@ use super::options_matcher::{
     match_sub_border_style,
     match_video_upscale,
 };
-use crate::ui::{
-    GlobalToast,
-    models::SETTINGS,
+use crate::{
+    client::{
+        jellyfin_client::JellyfinClient,
+        registry::CLIENTS,
+    },
+    ui::{
+        GlobalToast,
+        models::SETTINGS,
+        widgets::window::Window,
+    },
 };
 
 mod imp {
@@ -41,6 +50,14 @@ mod imp {
         pub seek_forward_adj: TemplateChild<gtk::Adjustment>,
         #[template_child]
         pub seek_backward_adj: TemplateChild<gtk::Adjustment>,
+        #[template_child]
+        pub up_next_adj: TemplateChild<gtk::Adjustment>,
+        #[template_child]
+        pub idle_guard_episodes_adj: TemplateChild<gtk::Adjustment>,
+        #[template_child]
+        pub idle_guard_hours_adj: TemplateChild<gtk::Adjustment>,
+        #[template_child]
+        pub idle_guard_timeout_adj: TemplateChild<gtk::Adjustment>,
 
         #[template_child]
         pub buffer_switchrow: TemplateChild<adw::SwitchRow>,
@@ -213,7 +230,7 @@ impl MPVControlSidebar {
 
         let action_hwdec = gio::ActionEntry::builder("hwdec")
             .parameter_type(Some(&i32::static_variant_type()))
-            .state(SETTINGS.mpv_hwdec().to_variant())
+            .state(SETTINGS.mpv_hwdec(&self.client()).to_variant())
             .activate(glib::clone!(
                 #[weak(rename_to = obj)]
                 self,
@@ -226,7 +243,7 @@ impl MPVControlSidebar {
                     let option = match_hwdec_interop(parameter);
                     obj.set_mpv_property("hwdec", option);
 
-                    SETTINGS.set_mpv_hwdec(parameter).unwrap();
+                    SETTINGS.set_mpv_hwdec(&obj.client(), parameter).unwrap();
 
                     action.set_state(&parameter.to_variant());
                 }
@@ -252,6 +269,30 @@ impl MPVControlSidebar {
                 "value",
             )
             .build();
+        SETTINGS
+            .bind("mpv-up-next-lead-time", &imp.up_next_adj.get(), "value")
+            .build();
+        SETTINGS
+            .bind(
+                "mpv-idle-guard-episodes",
+                &imp.idle_guard_episodes_adj.get(),
+                "value",
+            )
+            .build();
+        SETTINGS
+            .bind(
+                "mpv-idle-guard-hours",
+                &imp.idle_guard_hours_adj.get(),
+                "value",
+            )
+            .build();
+        SETTINGS
+            .bind(
+                "mpv-idle-guard-timeout",
+                &imp.idle_guard_timeout_adj.get(),
+                "value",
+            )
+            .build();
         imp.buffer_switchrow
             .set_active(SETTINGS.mpv_show_buffer_speed());
         imp.sub_font_button
@@ -277,12 +318,12 @@ impl MPVControlSidebar {
                 "active",
             )
             .build();
-        SETTINGS
-            .bind("mpv-cache-size", &imp.cache_size_adj.get(), "value")
-            .build();
-        SETTINGS
-            .bind("mpv-cache-time", &imp.cache_time_adj.get(), "value")
-            .build();
+        // Cache settings can be overridden per server, so they aren't bound
+        let client = self.client();
+        imp.cache_size_adj
+            .set_value(SETTINGS.mpv_cache_size(&client) as f64);
+        imp.cache_time_adj
+            .set_value(SETTINGS.mpv_cache_time(&client) as f64);
         SETTINGS
             .bind("mpv-deband", &imp.deband_switch.get(), "active")
             .build();
@@ -362,6 +403,28 @@ impl MPVControlSidebar {
         }
     }
 
+    /// Shows and applies the values of the server `client` is signed in to.
+    pub fn sync_server_settings(&self, client: &JellyfinClient) {
+        let imp = self.imp();
+        imp.cache_size_adj
+            .set_value(SETTINGS.mpv_cache_size(client) as f64);
+        imp.cache_time_adj
+            .set_value(SETTINGS.mpv_cache_time(client) as f64);
+        // The software renderer always uses its own hwdec
+        if SETTINGS.mpv_renderer() != 3 {
+            let _ =
+                self.activate_action("mpv.hwdec", Some(&SETTINGS.mpv_hwdec(client).to_variant()));
+        }
+    }
+
+    /// Client of the account the playing video belongs to.
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.root()
+            .and_downcast::<Window>()
+            .map(|window| window.imp().mpvnav.client())
+            .unwrap_or_else(|| CLIENTS.active())
+    }
+
     pub fn set_playback_speed(&self, value: f64) {
         let adj = &self.imp().playback_speed_adj;
         if (adj.value() - value).abs() > f64::EPSILON {
@@ -425,11 +488,13 @@ impl MPVControlSidebar {
     #[template_callback]
     pub fn on_cache_size(&self, _param: glib::ParamSpec, spin: adw::SpinRow) {
         self.set_mpv_property("demuxer-max-bytes", format!("{}MiB", spin.value()));
+        let _ = SETTINGS.set_mpv_cache_size(&self.client(), spin.value() as i32);
     }
 
     #[template_callback]
     pub fn on_cache_time(&self, _param: glib::ParamSpec, spin: adw::SpinRow) {
         self.set_mpv_property("cache-secs", spin.value());
+        let _ = SETTINGS.set_mpv_cache_time(&self.client(), spin.value() as i32);
     }
 
     #[template_callback]
@@ -40,7 +40,7 @@ impl DanmakuCacheMap {
     }
 
     pub fn cached_danmaku(&self, item: &TuItem) -> Option<CachedDanmaku> {
-        let entry = self.entries.get(&Self::cache_key(item)?)?;
+        let entry = self.entries.get(&item_cache_key(item)?)?;
         let episode_id = match entry {
             DanmakuCacheEntry::Series {
                 season,
@@ -71,7 +71,7 @@ impl DanmakuCacheMap {
     pub fn remember_manual_selection(
         &mut self, current_item: &TuItem, selected_episode: &TuItem, available_episodes: &[TuItem],
     ) -> anyhow::Result<()> {
-        let key = Self::cache_key(current_item)
+        let key = item_cache_key(current_item)
             .ok_or_else(|| anyhow::anyhow!("Current item has no stable cache key"))?;
         let selected_episode_id = Self::episode_id(selected_episode)?;
 
@@ -112,21 +112,6 @@ impl DanmakuCacheMap {
         Ok(())
     }
 
-    fn cache_key(item: &TuItem) -> Option<String> {
-        if item.series_name().is_some() {
-            if let Some(series_id) = item.series_id().filter(|id| !id.is_empty()) {
-                return Some(format!("series-id:{series_id}"));
-            }
-            return item
-                .series_name()
-                .filter(|name| !name.trim().is_empty())
-                .map(|name| format!("series-name:{}", name.trim().to_lowercase()));
-        }
-
-        let id = item.id();
-        (!id.is_empty()).then(|| format!("item:{id}"))
-    }
-
     fn episode_id(item: &TuItem) -> anyhow::Result<i64> {
         item.id()
             .parse()
@@ -140,3 +125,19 @@ impl DanmakuCacheMap {
         )
     }
 }
+
+/// Key shared by every episode of a series, or of a single item otherwise.
+pub(super) fn item_cache_key(item: &TuItem) -> Option<String> {
+    if item.series_name().is_some() {
+        if let Some(series_id) = item.series_id().filter(|id| !id.is_empty()) {
+            return Some(format!("series-id:{series_id}"));
+        }
+        return item
+            .series_name()
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| format!("series-name:{}", name.trim().to_lowercase()));
+    }
+
+    let id = item.id();
+    (!id.is_empty()).then(|| format!("item:{id}"))
+}
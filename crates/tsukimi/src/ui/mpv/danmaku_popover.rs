@@ -204,7 +204,16 @@ impl DanmakuPopover {
         let danmakw = page.danmakw();
         let imp = self.imp();
 
-        SETTINGS.bind_mpv_danmaku_enabled(&imp.danmaku_switch.get(), "active");
+        // The enabled state can be overridden per server, so it isn't bound
+        imp.danmaku_switch
+            .set_active(SETTINGS.mpv_danmaku_enabled(&page.client()));
+        imp.danmaku_switch.connect_active_notify(glib::clone!(
+            #[weak]
+            page,
+            move |switch| {
+                let _ = SETTINGS.set_mpv_danmaku_enabled(&page.client(), switch.is_active());
+            }
+        ));
         SETTINGS.bind_mpv_danmaku_opacity(&imp.danmaku_opacity_spin.get(), "value");
         SETTINGS.bind_mpv_danmaku_speed_factor(&imp.danmaku_speed_spin.get(), "value");
         SETTINGS.bind_mpv_danmaku_font_size(&imp.font_size_spin.get(), "value");
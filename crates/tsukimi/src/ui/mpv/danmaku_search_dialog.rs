@@ -202,7 +202,7 @@ impl DanmakuSearchDialog {
                     .map(SimpleListItem::from)
                     .collect::<Vec<_>>();
                 let is_empty = items.is_empty();
-                self.imp().view.set_store::<true>(items);
+                self.imp().view.set_local_store::<true>(items);
                 if is_empty {
                     self.show_toast(gettext("No matching anime found"));
                 }
@@ -218,10 +218,7 @@ impl DanmakuSearchDialog {
             self.imp().episode_list.remove(&child);
         }
 
-        let items = items
-            .into_iter()
-            .map(TuItem::from_simple)
-            .collect::<Vec<_>>();
+        let items = items.into_iter().map(TuItem::from).collect::<Vec<_>>();
         for item in &items {
             let row = adw::ActionRow::builder()
                 .title(item.name())
@@ -0,0 +1,149 @@
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk::{
+    glib,
+    subclass::prelude::*,
+};
+
+use super::page::MPVPage;
+use crate::{
+    alert_dialog,
+    ui::models::SETTINGS,
+    utils::spawn,
+};
+
+const MICROS_PER_HOUR: i64 = 3600 * 1_000_000;
+
+/// What "Continue" does once someone answers.
+#[derive(Clone, Copy)]
+enum StillWatching {
+    PlayNext,
+    Resume,
+}
+
+impl MPVPage {
+    /// Motion, key and click events in the player prove someone is watching.
+    pub(super) fn note_user_input(&self) {
+        let imp = self.imp();
+        imp.last_input_time.set(glib::monotonic_time());
+        imp.auto_advanced_count.set(0);
+    }
+
+    /// Moves on to the next video without the user asking for it, unless
+    /// that has happened too often in a row.
+    pub(super) async fn auto_advance(&self) {
+        let imp = self.imp();
+        let episodes = SETTINGS.mpv_idle_guard_episodes();
+        if (episodes > 0 && imp.auto_advanced_count.get() >= episodes as u32)
+            || self.idle_too_long()
+        {
+            self.ask_still_watching(StillWatching::PlayNext);
+            return;
+        }
+
+        imp.auto_advanced_count
+            .set(imp.auto_advanced_count.get() + 1);
+        imp.auto_advancing.set(true);
+        self.on_next_video().await;
+    }
+
+    /// Called with every position update while playing.
+    pub(super) fn check_idle_time(&self) {
+        if self.paused() || !self.idle_too_long() {
+            return;
+        }
+
+        self.imp().video.player().pause(true);
+        self.ask_still_watching(StillWatching::Resume);
+    }
+
+    fn idle_too_long(&self) -> bool {
+        let hours = SETTINGS.mpv_idle_guard_hours();
+        hours > 0
+            && glib::monotonic_time() - self.imp().last_input_time.get()
+                >= hours as i64 * MICROS_PER_HOUR
+    }
+
+    fn ask_still_watching(&self, action: StillWatching) {
+        let imp = self.imp();
+        if imp.idle_prompt_timeout.borrow().is_some() {
+            return;
+        }
+
+        let timeout = SETTINGS.mpv_idle_guard_timeout().max(1) as u32;
+        let body = |seconds: u32| {
+            gettext("Playback stops in {seconds} seconds")
+                .replace("{seconds}", &seconds.to_string())
+        };
+        let alert_dialog = adw::AlertDialog::builder()
+            .heading(gettext("Are You Still Watching?"))
+            .body(body(timeout))
+            .build();
+        alert_dialog.add_response("stop", &gettext("Stop"));
+        alert_dialog.add_response("continue", &gettext("Continue Watching"));
+        alert_dialog.set_response_appearance("continue", adw::ResponseAppearance::Suggested);
+        alert_dialog.set_default_response(Some("continue"));
+        alert_dialog.set_close_response("stop");
+
+        let remaining = std::cell::Cell::new(timeout);
+        let source = glib::timeout_add_seconds_local(
+            1,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                alert_dialog,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    let seconds = remaining.get().saturating_sub(1);
+                    remaining.set(seconds);
+                    if seconds > 0 {
+                        alert_dialog.set_body(&body(seconds));
+                        return glib::ControlFlow::Continue;
+                    }
+                    // Nobody answered, the close response stops playback
+                    obj.imp().idle_prompt_timeout.take();
+                    alert_dialog.close();
+                    glib::ControlFlow::Break
+                }
+            ),
+        );
+        imp.idle_prompt_timeout.replace(Some(source));
+
+        alert_dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, response| {
+                    if let Some(source) = obj.imp().idle_prompt_timeout.take() {
+                        source.remove();
+                    }
+
+                    if response != "continue" {
+                        // Reports the position playback really reached
+                        obj.on_stop_clicked();
+                        return;
+                    }
+
+                    obj.note_user_input();
+                    match action {
+                        StillWatching::PlayNext => {
+                            spawn(glib::clone!(
+                                #[weak]
+                                obj,
+                                async move {
+                                    obj.on_next_video().await;
+                                }
+                            ));
+                        }
+                        StillWatching::Resume => obj.imp().video.player().pause(false),
+                    }
+                }
+            ),
+        );
+
+        alert_dialog!(self, alert_dialog);
+    }
+}
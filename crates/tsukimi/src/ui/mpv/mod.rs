@@ -6,14 +6,23 @@ pub mod danmaku_popover;
 pub mod danmaku_scale_row;
 pub mod danmaku_search_dialog;
 mod danmaku_sync;
+mod idle_guard;
 pub mod menu_actions;
 
 pub mod mpris;
 pub mod options_matcher;
 pub mod page;
+pub mod playback_diagnostics;
+pub mod playback_info_dialog;
+mod segment_editor;
 pub mod sink;
+mod sleep_timer;
+pub mod track_memory;
+mod up_next;
+pub mod up_next_card;
 pub mod video_scale;
 pub mod volume_bar;
+mod watch_recorder;
 
 pub use danmaku_popover::{
     DanmakuPopover,
@@ -29,7 +29,7 @@ impl MPVPage {
         let Some(video) = self.current_video() else {
             return Metadata::new();
         };
-        let mut metadata = self.metadata_for_video(&video);
+        let mut metadata = self.with_sleep_timer(self.metadata_for_video(&video));
         if let Some(art_url) = self.imp().mpris_art_url.borrow().as_ref() {
             metadata.set_art_url(Some(art_url.clone()));
         }
@@ -60,6 +60,14 @@ impl MPVPage {
         builder.build()
     }
 
+    /// Lets MPRIS clients show when the sleep timer stops playback.
+    pub(super) fn with_sleep_timer(&self, mut metadata: Metadata) -> Metadata {
+        if let Some(description) = self.sleep_timer_description() {
+            metadata.set_comment(Some([description]));
+        }
+        metadata
+    }
+
     pub(super) fn notify_mpris_art_changed(&self, video: TuItem, mut metadata: Metadata) {
         let video_id = video.id();
         let source = select_picture_source(&video, CardOptions::default());
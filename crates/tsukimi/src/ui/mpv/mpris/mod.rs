@@ -95,7 +95,7 @@ impl MPVPage {
         let Some(current_video) = self.current_video() else {
             return;
         };
-        let metadata = self.metadata_for_video(&current_video);
+        let metadata = self.with_sleep_timer(self.metadata_for_video(&current_video));
         self.mpris_properties_changed([
             Property::Metadata(metadata.clone()),
             Property::CanPlay(true),
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::prelude::*;
 use dandanapi_client::SearchSearchEpisodesParams;
 use gettextrs::gettext;
@@ -20,7 +22,13 @@ use super::{
     DanmakuPopoverStatus,
     danmaku_cache_map::DanmakuCacheMap,
     danmaku_client::DanmakuClient,
+    playback_diagnostics::PlaybackDiagnostics,
+    playback_info_dialog::PlaybackInfoDialog,
     sink::MPVPlaySink,
+    track_memory::{
+        TrackChoice,
+        TrackMemory,
+    },
     video_scale::VideoScale,
 };
 use crate::{
@@ -28,8 +36,9 @@ use crate::{
         error::UserFacingError,
         jellyfin_client::{
             BackType,
-            JELLYFIN_CLIENT,
+            JellyfinClient,
         },
+        registry::CLIENTS,
         structs::{
             Back,
             MediaSegmentType,
@@ -40,13 +49,18 @@ use crate::{
     close_on_error,
     ui::{
         GlobalToast,
-        models::SETTINGS,
+        models::{
+            SETTINGS,
+            settings::SegmentSkipPolicy,
+            sleep_timer::SleepTimerMode,
+        },
         provider::tu_item::TuItem,
         widgets::{
             check_row::CheckRow,
             item::SelectedVideoSubInfo,
             item_utils::{
                 make_subtitle_version_choice,
+                make_video_version_choice,
                 make_video_version_choice_from_matcher,
             },
             song_widget::format_duration,
@@ -107,7 +121,7 @@ pub struct FallbackContext {
 }
 
 #[derive(Clone, Copy)]
-enum MpvTrackKind {
+pub(super) enum MpvTrackKind {
     Audio,
     Subtitle,
 }
@@ -155,13 +169,19 @@ mod imp {
             MediaSegment,
         },
         ui::{
-            models::SETTINGS,
+            models::{
+                SETTINGS,
+                sleep_timer::SleepTimer,
+                watch_history::WatchSession,
+            },
             mpv::{
                 DanmakuPopover,
                 VolumeBar,
                 danmaku_sync::DanmakuSync,
                 menu_actions::MenuActions,
                 sink::MPVPlaySink,
+                up_next::UpNextState,
+                up_next_card::UpNextCard,
                 video_scale::VideoScale,
             },
             provider::tu_item::TuItem,
@@ -203,6 +223,10 @@ mod imp {
         #[template_child]
         pub skip_segment_revealer: TemplateChild<gtk::Revealer>,
         #[template_child]
+        pub up_next_revealer: TemplateChild<gtk::Revealer>,
+        #[template_child]
+        pub up_next_card: TemplateChild<UpNextCard>,
+        #[template_child]
         pub skip_segment_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub network_speed_label: TemplateChild<gtk::Label>,
@@ -236,6 +260,23 @@ mod imp {
         pub suburl: RefCell<Option<String>>,
         pub skippable_segments: RefCell<Option<Vec<MediaSegment>>>,
         pub current_segment_end: Cell<Option<f64>>,
+        pub outro_start: Cell<Option<f64>>,
+        pub up_next_state: Cell<UpNextState>,
+        pub duration: Cell<f64>,
+        /// `glib::monotonic_time` of the last motion, key or click event
+        pub last_input_time: Cell<i64>,
+        pub auto_advanced_count: Cell<u32>,
+        pub auto_advancing: Cell<bool>,
+        pub idle_prompt_timeout: RefCell<Option<glib::SourceId>>,
+        pub sleep_timer: RefCell<SleepTimer>,
+        pub sleep_timer_source: RefCell<Option<glib::SourceId>>,
+        /// Volume before the sleep timer started fading it out
+        pub sleep_timer_volume: Cell<Option<i64>>,
+        pub sleep_timer_label: RefCell<Option<String>>,
+        pub watch_session: RefCell<Option<WatchSession>>,
+        /// `glib::monotonic_time` of the last report that fed `watch_session`
+        pub watch_session_report_time: Cell<i64>,
+        pub segment_mark_start: Cell<Option<f64>>,
         pub popover: RefCell<Option<PopoverMenu>>,
         pub popover_count: Cell<u32>,
         pub menu_actions: MenuActions,
@@ -269,7 +310,15 @@ mod imp {
         pub queued_playback_direct_mode: RefCell<Option<super::PlaybackDirectMode>>,
         pub retrying_playback: Cell<bool>,
         pub allow_fallback: Cell<bool>,
+        /// Modes that failed before the current one, for the playback info
+        pub fallback_steps: RefCell<Vec<super::PlaybackDirectMode>>,
+        pub playback_diagnostics: RefCell<Option<super::PlaybackDiagnostics>>,
         pub last_nonzero_volume: Cell<i64>,
+        /// Server whose setting overrides are applied to mpv
+        pub settings_server: RefCell<Option<String>>,
+        /// Whether the remembered tracks were already applied to this file
+        pub audio_track_restored: Cell<bool>,
+        pub sub_track_restored: Cell<bool>,
         pub danmaku_count: Cell<usize>,
         pub danmaku_generation: Cell<u64>,
         pub file_loaded: Cell<bool>,
@@ -316,6 +365,29 @@ mod imp {
                     mpv.on_playlist_clicked();
                 },
             );
+            klass.install_action(
+                "mpv.mark-segment-start",
+                None,
+                move |mpv, _action, _parameter| {
+                    mpv.mark_segment_start();
+                },
+            );
+            klass.install_action(
+                "mpv.mark-segment-end",
+                None,
+                move |mpv, _action, _parameter| {
+                    mpv.mark_segment_end();
+                },
+            );
+            klass.install_action(
+                "mpv.sleep-timer",
+                Some(glib::VariantTy::STRING),
+                move |mpv, _action, parameter| {
+                    if let Some(target) = parameter.and_then(|p| p.str()) {
+                        mpv.set_sleep_timer(super::SleepTimerMode::from_target(target));
+                    }
+                },
+            );
             klass.install_action_async(
                 "mpv.next-video",
                 None,
@@ -359,6 +431,7 @@ mod imp {
             let obj = self.obj();
 
             self.danmaku_popover_content.set_page(&obj);
+            self.up_next_card.set_page(&obj);
             obj.set_popover();
 
             obj.connect_root_notify(|obj| {
@@ -782,16 +855,51 @@ impl MPVPage {
         true
     }
 
+    /// mpv is configured with the global settings on startup, apply the
+    /// overrides of the server `client` is signed in to whenever a video from
+    /// another account plays.
+    fn apply_server_settings(&self, client: &JellyfinClient) {
+        let imp = self.imp();
+        let server = client.session().account_key();
+        if imp.settings_server.borrow().as_ref() == Some(&server) {
+            return;
+        }
+        imp.settings_server.replace(Some(server));
+
+        imp.video
+            .set_property("alang", SETTINGS.mpv_audio_preferred_lang_str(client));
+        imp.danmaku_popover_content
+            .set_enabled(SETTINGS.mpv_danmaku_enabled(client));
+        if let Some(window) = self.root().and_downcast_ref::<Window>() {
+            window
+                .imp()
+                .mpv_control_sidebar
+                .sync_server_settings(client);
+        }
+    }
+
+    /// Client of the account the playing video belongs to.
+    pub(super) fn client(&self) -> Arc<JellyfinClient> {
+        self.current_video()
+            .map(|item| item.client())
+            .unwrap_or_else(|| CLIENTS.active())
+    }
+
     pub fn play(
         &self, selected: Option<SelectedVideoSubInfo>, item: TuItem, episode_list: Vec<TuItem>,
         video_matcher: Option<String>, start_seconds: f64,
     ) {
+        if !self.imp().auto_advancing.replace(false) {
+            self.note_user_input();
+        }
         let should_search_danmaku = self
             .current_video()
             .as_ref()
             .is_none_or(|current| current.id() != item.id());
         let imp = self.imp();
         imp.file_loaded.set(false);
+        imp.audio_track_restored.set(false);
+        imp.sub_track_restored.set(false);
         imp.danmaku_sync.reset();
         imp.danmakw.stop_rendering();
         imp.danmakw.set_visible(false);
@@ -825,7 +933,9 @@ impl MPVPage {
         let series_id = item.series_id();
         self.imp().video_scale.reset_scale();
         self.imp().last_playback_position.set(start_seconds);
+        self.imp().duration.set(0.0);
         self.reset_skippable_segments();
+        self.reset_up_next();
 
         // If the video_matcher is None, field wont be updated
         if let Some(video_matcher) = video_matcher {
@@ -837,6 +947,8 @@ impl MPVPage {
         let track_list_changed = self.mpris_track_list_changed(&episode_list);
 
         self.set_current_video(Some(item.clone()));
+        // After the video changed, so the player's controls save to its server
+        self.apply_server_settings(&item.client());
         self.imp().current_episode_list.replace(episode_list);
 
         {
@@ -850,7 +962,7 @@ impl MPVPage {
             self.imp()
                 .danmaku_popover_content
                 .set_switch_sensitive(true);
-            if SETTINGS.mpv_danmaku_enabled() {
+            if SETTINGS.mpv_danmaku_enabled(&item.client()) {
                 self.auto_search_danmaku(&item);
             } else {
                 self.clear_danmaku();
@@ -860,13 +972,17 @@ impl MPVPage {
             selected: selected.to_owned(),
             start_seconds,
         }));
-        let direct_mode = self
-            .imp()
-            .queued_playback_direct_mode
-            .borrow_mut()
-            .take()
-            .unwrap_or_else(PlaybackDirectMode::direct);
+        let queued_mode = self.imp().queued_playback_direct_mode.borrow_mut().take();
+        match queued_mode {
+            Some(_) => {
+                let failed_mode = *self.imp().playback_direct_mode.borrow();
+                self.imp().fallback_steps.borrow_mut().push(failed_mode);
+            }
+            None => self.imp().fallback_steps.borrow_mut().clear(),
+        }
+        let direct_mode = queued_mode.unwrap_or_else(PlaybackDirectMode::direct);
         self.imp().playback_direct_mode.replace(direct_mode);
+        self.imp().playback_diagnostics.replace(None);
         self.imp().retrying_playback.set(false);
         self.imp().allow_fallback.set(true);
 
@@ -885,8 +1001,10 @@ impl MPVPage {
                 let sub_stream_index = selected.as_ref().map(|s| s.sub_index);
                 let media_source_id = selected.as_ref().map(|s| s.media_source_id.clone());
                 let id_clone = id.to_owned();
+                let client = obj.client();
+                let request_client = client.clone();
                 let playback_info = match spawn_tokio(async move {
-                    JELLYFIN_CLIENT
+                    request_client
                         .get_playbackinfo(
                             &id_clone,
                             sub_stream_index,
@@ -921,7 +1039,8 @@ impl MPVPage {
                             make_video_version_choice_from_matcher(video_version_list, matcher)
                                 .and_then(|index| playback_info.media_sources.get(index))
                         } else {
-                            playback_info.media_sources.first()
+                            make_video_version_choice(&playback_info.media_sources)
+                                .and_then(|choice| playback_info.media_sources.get(choice.index))
                         }
                     };
 
@@ -961,7 +1080,7 @@ impl MPVPage {
                             })
                             .collect();
 
-                        make_subtitle_version_choice(sub_version_list)
+                        make_subtitle_version_choice(sub_version_list, &client)
                             .and_then(|index| media_source.media_streams.get(index.0 as usize))
                     };
 
@@ -969,12 +1088,12 @@ impl MPVPage {
                     imp.video.set_slang(slang);
                 } else {
                     imp.video
-                        .set_slang(SETTINGS.mpv_subtitle_preferred_lang_str());
+                        .set_slang(SETTINGS.mpv_subtitle_preferred_lang_str(&client));
                 }
 
                 let sub_url = match media_stream {
                     Some(stream) if stream.is_external => match &stream.delivery_url {
-                        Some(url) => Some(JELLYFIN_CLIENT.get_streaming_url(url).await),
+                        Some(url) => Some(client.get_streaming_url(url).await),
                         None => {
                             println!("External Subtitle without selected source");
                             imp.obj()
@@ -992,7 +1111,7 @@ impl MPVPage {
 
                 imp.suburl.replace(sub_url);
 
-                let video_url = match media_source_stream_url(media_source).await {
+                let video_url = match media_source_stream_url(&client, media_source).await {
                     Some(video_url) => video_url,
                     None => {
                         obj.mark_stream_failed();
@@ -1002,40 +1121,62 @@ impl MPVPage {
                     }
                 };
 
-                imp.video.play(&video_url, start_seconds);
+                let stream_url = client.get_streaming_url(&video_url).await;
+                imp.playback_diagnostics
+                    .replace(Some(PlaybackDiagnostics::new(
+                        media_source,
+                        media_source_play_method(media_source),
+                        direct_mode,
+                        imp.fallback_steps.borrow().to_owned(),
+                        &stream_url,
+                    )));
+
+                imp.video.play(&stream_url, start_seconds);
             }
         ));
     }
 
+    pub fn playback_diagnostics(&self) -> Option<PlaybackDiagnostics> {
+        self.imp().playback_diagnostics.borrow().to_owned()
+    }
+
     fn reset_skippable_segments(&self) {
         let imp = self.imp();
         imp.skippable_segments.replace(None);
         imp.current_segment_end.set(None);
         imp.skip_segment_revealer.set_reveal_child(false);
+        self.reset_segment_editor();
     }
 
-    fn load_skippable_segments(&self, id: String) {
+    pub(super) fn load_skippable_segments(&self, id: String) {
         spawn_g_timeout(glib::clone!(
             #[weak(rename_to = obj)]
             self,
             async move {
                 let request_id = id.to_owned();
-                let Ok(segments) = spawn_tokio(async move {
-                    JELLYFIN_CLIENT.get_skippable_segments(&request_id).await
-                })
-                .await
+                let client = obj.client();
+                let Ok(segments) =
+                    spawn_tokio(async move { client.get_skippable_segments(&request_id).await })
+                        .await
                 else {
                     return;
                 };
+                // Up Next follows the outro even when it isn't skipped
+                let outro_start = segments
+                    .items
+                    .iter()
+                    .filter(|s| {
+                        s.segment_type == MediaSegmentType::Outro && s.end_ticks > s.start_ticks
+                    })
+                    .map(|s| s.start_seconds())
+                    .reduce(f64::min);
                 let segments = segments
                     .items
                     .into_iter()
                     .filter(|s| {
                         s.end_ticks > s.start_ticks
-                            && matches!(
-                                s.segment_type,
-                                MediaSegmentType::Intro | MediaSegmentType::Outro
-                            )
+                            && SETTINGS.segment_skip_policy(s.segment_type)
+                                != SegmentSkipPolicy::Ignore
                     })
                     .sorted_by_key(|s| s.start_ticks)
                     .collect::<Vec<_>>();
@@ -1047,6 +1188,7 @@ impl MPVPage {
                     return;
                 }
                 let imp = obj.imp();
+                imp.outro_start.set(outro_start);
                 imp.skippable_segments.replace(Some(segments));
                 obj.update_skip_segment_button(imp.video.position());
             }
@@ -1070,15 +1212,18 @@ impl MPVPage {
         let segment_end = current_segment.map(|(_, end)| end);
         self.imp().current_segment_end.set(segment_end);
 
-        if segment_end.is_some() && SETTINGS.auto_skip_intro_outro() {
-            return self.on_skip_segment_clicked();
-        }
-
         if let Some((kind, _)) = current_segment {
+            if SETTINGS.segment_skip_policy(kind) == SegmentSkipPolicy::AutoSkip {
+                return self.on_skip_segment_clicked();
+            }
+
             let label = match kind {
                 MediaSegmentType::Intro => gettext("Skip Intro"),
                 MediaSegmentType::Outro => gettext("Skip Outro"),
-                _ => unreachable!(),
+                MediaSegmentType::Recap => gettext("Skip Recap"),
+                MediaSegmentType::Preview => gettext("Skip Preview"),
+                MediaSegmentType::Commercial => gettext("Skip Commercial"),
+                _ => gettext("Skip"),
             };
             self.imp().skip_segment_button.set_label(&label);
         }
@@ -1106,8 +1251,10 @@ impl MPVPage {
     ) -> Option<String> {
         let stream_index = media_stream.index;
         let media_source_id_clone = media_source_id.to_owned();
+        let client = self.client();
+        let request_client = client.clone();
         let playback_info = spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            request_client
                 .get_playbackinfo(
                     &id,
                     Some(stream_index),
@@ -1131,11 +1278,12 @@ impl MPVPage {
             .delivery_url
             .to_owned()?;
 
-        Some(JELLYFIN_CLIENT.get_streaming_url(&url).await)
+        Some(client.get_streaming_url(&url).await)
     }
 
     async fn set_audio_and_video_tracks_dropdown(&self, value: MpvTracks) {
         let imp = self.imp();
+        self.restore_tracks(&value);
         self.bind_tracks(
             value.audio_tracks,
             &imp.audio_listbox.get(),
@@ -1169,6 +1317,7 @@ impl MPVPage {
             self,
             move |_| {
                 obj.set_track(kind, 0);
+                obj.remember_track(kind, None);
             }
         ));
         listbox.append(&row);
@@ -1188,10 +1337,91 @@ impl MPVPage {
                 self,
                 move |_| {
                     obj.set_track(kind, track.id);
+                    obj.remember_track(kind, Some(&track));
                 }
             ));
             listbox.append(&row);
         }
+
+        let Some(item) = self.current_video() else {
+            return;
+        };
+        if TrackMemory::load().choice(&item, kind).is_none() {
+            return;
+        }
+        let forget_row = adw::ButtonRow::builder()
+            .title(if item.series_name().is_some() {
+                gettext("Forget Choice for This Series")
+            } else {
+                gettext("Forget Choice for This Movie")
+            })
+            .start_icon_name("edit-clear-symbolic")
+            .build();
+        forget_row.connect_activated(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            #[weak]
+            listbox,
+            move |row| {
+                let Some(item) = obj.current_video() else {
+                    return;
+                };
+                if let Err(e) = TrackMemory::load().forget(&item, kind) {
+                    obj.toast(e.to_string());
+                    return;
+                }
+                listbox.remove(row);
+            }
+        ));
+        listbox.append(&forget_row);
+    }
+
+    fn remember_track(&self, kind: MpvTrackKind, track: Option<&MpvTrack>) {
+        let Some(item) = self.current_video() else {
+            return;
+        };
+        if let Err(e) = TrackMemory::load().remember(&item, kind, track) {
+            tracing::warn!("Failed to remember track: {e}");
+        }
+    }
+
+    /// Selects the tracks remembered for the series of the current video,
+    /// once per file. Tracks may show up later, e.g. external subtitles, so
+    /// this is retried on every track list change until one matches.
+    fn restore_tracks(&self, tracks: &MpvTracks) {
+        let imp = self.imp();
+        let Some(item) = self.current_video() else {
+            return;
+        };
+        let memory = TrackMemory::load();
+        for (kind, list, restored) in [
+            (
+                MpvTrackKind::Audio,
+                &tracks.audio_tracks,
+                &imp.audio_track_restored,
+            ),
+            (
+                MpvTrackKind::Subtitle,
+                &tracks.sub_tracks,
+                &imp.sub_track_restored,
+            ),
+        ] {
+            if restored.get() {
+                continue;
+            }
+            let track_id = match memory.choice(&item, kind) {
+                Some(TrackChoice::Off) => Some(0),
+                Some(TrackChoice::Track(track)) => track.find(list),
+                None => {
+                    restored.set(true);
+                    None
+                }
+            };
+            if let Some(track_id) = track_id {
+                self.set_track(kind, track_id);
+                restored.set(true);
+            }
+        }
     }
 
     fn set_track(&self, kind: MpvTrackKind, track_id: i64) {
@@ -1206,31 +1436,33 @@ impl MPVPage {
             .set_property(kind.property(), track.to_string());
     }
 
+    /// The video `offset` places away from the current one in the episode list
+    pub(super) fn neighbour_video(&self, offset: isize) -> Option<TuItem> {
+        let current_video = self.current_video()?;
+        let video_list = self.imp().current_episode_list.borrow();
+        video_list.iter().enumerate().find_map(|(i, item)| {
+            // Don't use id() here, because the same video maybe have different id
+            if item.index_number() == current_video.index_number()
+                && item.parent_index_number() == current_video.parent_index_number()
+            {
+                let new_index = (i as isize + offset) as usize;
+                video_list.get(new_index).cloned()
+            } else {
+                None
+            }
+        })
+    }
+
     async fn load_video(&self, offset: isize) {
         if self.paused() {
             self.imp().video.pause();
         }
 
-        let Some(current_video) = self.imp().current_video.borrow().to_owned() else {
+        if self.current_video().is_none() {
             return;
-        };
-
-        let next_item = {
-            let video_list = self.imp().current_episode_list.borrow();
-            video_list.iter().enumerate().find_map(|(i, item)| {
-                // Don't use id() here, because the same video maybe have different id
-                if item.index_number() == current_video.index_number()
-                    && item.parent_index_number() == current_video.parent_index_number()
-                {
-                    let new_index = (i as isize + offset) as usize;
-                    video_list.get(new_index).cloned()
-                } else {
-                    None
-                }
-            })
-        };
+        }
 
-        let Some(next_item) = next_item else {
+        let Some(next_item) = self.neighbour_video(offset) else {
             self.toast(gettext("No more video found"));
             self.on_stop_clicked();
             return;
@@ -1266,6 +1498,11 @@ impl MPVPage {
         mpv.display_stats_toggle();
     }
 
+    #[template_callback]
+    fn on_playback_info_clicked(&self) {
+        PlaybackInfoDialog::new(self).present(Some(self));
+    }
+
     fn listen_events(&self) {
         glib::spawn_future_local(glib::clone!(
             #[weak(rename_to = obj)]
@@ -1358,6 +1595,7 @@ impl MPVPage {
         let duration = format_duration(value as i64);
         let width_chars = duration.chars().count() as i32;
         imp.video_scale.set_range(0.0, value);
+        imp.duration.set(value);
         imp.progress_time_label.set_width_chars(width_chars);
         imp.duration_label.set_width_chars(width_chars);
         imp.duration_label.set_text(&duration);
@@ -1394,6 +1632,8 @@ impl MPVPage {
             imp.video_scale.set_value(value as f64);
         }
         self.update_skip_segment_button(value as f64);
+        self.update_up_next(value as f64);
+        self.check_idle_time();
 
         if let Some(time_millis) = imp
             .danmaku_sync
@@ -1494,8 +1734,13 @@ impl MPVPage {
             self,
             async move {
                 if value == 0 {
+                    // Stops playback once the sleep timer ran out of videos
+                    if obj.sleep_timer_item_finished() {
+                        return;
+                    }
                     match SETTINGS.mpv_action_after_video_end() {
-                        0 => obj.on_next_video().await,
+                        0 if obj.up_next_cancelled() => obj.on_stop_clicked(),
+                        0 => obj.auto_advance().await,
                         2 => obj.on_stop_clicked(),
                         _ => {}
                     }
@@ -1560,6 +1805,7 @@ impl MPVPage {
         let is_threshold = (old_x - x).abs() > 3.0 || (old_y - y).abs() > 3.0;
 
         if is_threshold {
+            self.note_user_input();
             if !self.toolbar_revealed() {
                 self.set_reveal_overlay(true);
             }
@@ -1696,12 +1942,14 @@ impl MPVPage {
         self.imp().danmakw.set_visible(false);
         self.remove_timeout();
         self.reset_skippable_segments();
+        self.reset_up_next();
         self.clear_danmaku();
         let current_video = self.current_video();
 
         let video = &self.imp().video;
         video.player().pause(true);
         video.stop();
+        self.stop_sleep_timer();
         let root = self.root();
         let window = root
             .and_downcast_ref::<crate::ui::widgets::window::Window>()
@@ -1744,17 +1992,19 @@ impl MPVPage {
 
     fn handle_callback(&self, backtype: BackType) {
         if let Some(back) = self.position_back() {
+            self.record_watch_history(&back, &backtype);
+            let client = self.client();
             crate::utils::spawn_tokio_without_await(async move {
-                let _ = JELLYFIN_CLIENT.position_back(&back, backtype).await;
+                let _ = client.position_back(&back, backtype).await;
             });
         }
     }
 
     async fn handle_callback_sync(&self, backtype: BackType) {
         if let Some(back) = self.position_back() {
-            let _ =
-                spawn_tokio(async move { JELLYFIN_CLIENT.position_back(&back, backtype).await })
-                    .await;
+            self.record_watch_history(&back, &backtype);
+            let client = self.client();
+            let _ = spawn_tokio(async move { client.position_back(&back, backtype).await }).await;
         }
     }
 
@@ -1782,6 +2032,7 @@ impl MPVPage {
 
     #[template_callback]
     fn right_click_cb(&self, _n: i32, x: f64, y: f64) {
+        self.note_user_input();
         if let Some(popover) = self.imp().popover.borrow().as_ref() {
             popover.set_pointing_to(Some(&Rectangle::new(x as i32, y as i32, 0, 0)));
             popover.popup();
@@ -1790,6 +2041,7 @@ impl MPVPage {
 
     #[template_callback]
     fn left_click_cb(&self) {
+        self.note_user_input();
         self.imp().video.pause();
     }
 
@@ -1826,6 +2078,7 @@ impl MPVPage {
     }
 
     pub fn key_pressed_cb(&self, key: gtk::gdk::Key, state: gtk::gdk::ModifierType) -> bool {
+        self.note_user_input();
         if key.to_lower() == gtk::gdk::Key::f || key == gtk::gdk::Key::Escape {
             return true;
         }
@@ -1964,9 +2217,9 @@ impl MPVPage {
     }
 }
 
-pub async fn direct_stream_url(source: &MediaSource) -> Option<String> {
+pub async fn direct_stream_url(client: &JellyfinClient, source: &MediaSource) -> Option<String> {
     let container = source.container.as_deref()?;
-    JELLYFIN_CLIENT
+    client
         .get_item_stream_url(
             container,
             source.item_id.as_ref().unwrap_or(&source.id),
@@ -1996,7 +2249,9 @@ fn media_source_play_method(source: &MediaSource) -> &'static str {
     "DirectPlay"
 }
 
-pub async fn media_source_stream_url(source: &MediaSource) -> Option<String> {
+pub async fn media_source_stream_url(
+    client: &JellyfinClient, source: &MediaSource,
+) -> Option<String> {
     if let Some(direct_url) = source.direct_stream_url.to_owned() {
         return Some(direct_url);
     }
@@ -2009,5 +2264,5 @@ pub async fn media_source_stream_url(source: &MediaSource) -> Option<String> {
         return Some(path);
     }
 
-    direct_stream_url(source).await
+    direct_stream_url(client, source).await
 }
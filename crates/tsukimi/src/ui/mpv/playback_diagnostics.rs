@@ -0,0 +1,274 @@
+use gettextrs::gettext;
+use mutsumi::MpvStats;
+
+use super::page::PlaybackDirectMode;
+use crate::client::structs::MediaSource;
+
+/// Query parameters that carry credentials and must not end up in bug reports.
+const SECRET_PARAMS: &[&str] = &["api_key", "apikey", "accesstoken", "x-emby-token"];
+
+/// Why the current stream is played the way it is.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackDiagnostics {
+    pub media_source_name: String,
+    pub media_source_id: String,
+    pub container: Option<String>,
+    pub bit_rate: Option<u64>,
+    pub play_method: &'static str,
+    pub direct_mode: PlaybackDirectMode,
+    /// Modes that failed before `direct_mode`, oldest first
+    pub fallback_steps: Vec<PlaybackDirectMode>,
+    pub stream_host: Option<String>,
+    pub transcoding_params: Vec<(String, String)>,
+    pub transcode_reasons: Vec<String>,
+}
+
+/// A titled list of label and value pairs, shown as one preferences group.
+pub struct DiagnosticsSection {
+    pub title: String,
+    pub rows: Vec<(String, String)>,
+}
+
+impl PlaybackDiagnostics {
+    pub fn new(
+        source: &MediaSource, play_method: &'static str, direct_mode: PlaybackDirectMode,
+        fallback_steps: Vec<PlaybackDirectMode>, stream_url: &str,
+    ) -> Self {
+        let transcoding_params = source
+            .transcoding_url
+            .as_deref()
+            .map(query_params)
+            .unwrap_or_default();
+
+        Self {
+            media_source_name: source.name.to_owned(),
+            media_source_id: source.id.to_owned(),
+            container: source.container.to_owned(),
+            bit_rate: source.bit_rate,
+            play_method,
+            direct_mode,
+            fallback_steps,
+            stream_host: url::Url::parse(stream_url).ok().and_then(|url| {
+                let host = url.host_str()?.to_owned();
+                Some(match url.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host,
+                })
+            }),
+            transcode_reasons: transcode_reasons(source, &transcoding_params),
+            transcoding_params,
+        }
+    }
+
+    pub fn sections(&self) -> Vec<DiagnosticsSection> {
+        let unknown = || gettext("Unknown");
+
+        let mut source = vec![
+            (gettext("Name"), self.media_source_name.to_owned()),
+            (gettext("Id"), self.media_source_id.to_owned()),
+            (
+                gettext("Container"),
+                self.container.to_owned().unwrap_or_else(unknown),
+            ),
+        ];
+        if let Some(bit_rate) = self.bit_rate {
+            source.push((gettext("Bitrate"), format_bitrate(bit_rate as f64)));
+        }
+
+        let mut method = vec![
+            (gettext("Play Method"), self.play_method.to_owned()),
+            (gettext("Requested Mode"), mode_label(self.direct_mode)),
+            (
+                gettext("Stream Host"),
+                self.stream_host.to_owned().unwrap_or_else(unknown),
+            ),
+        ];
+        if !self.transcode_reasons.is_empty() {
+            method.push((
+                gettext("Transcode Reasons"),
+                self.transcode_reasons.join(", "),
+            ));
+        }
+
+        let fallback = if self.fallback_steps.is_empty() {
+            vec![(gettext("Attempts"), gettext("First attempt succeeded"))]
+        } else {
+            self.fallback_steps
+                .iter()
+                .chain(std::iter::once(&self.direct_mode))
+                .enumerate()
+                .map(|(index, mode)| {
+                    let result = if index == self.fallback_steps.len() {
+                        gettext("Current")
+                    } else {
+                        gettext("Failed")
+                    };
+                    (
+                        gettext("Attempt {number}").replace("{number}", &(index + 1).to_string()),
+                        format!("{} — {result}", mode_label(*mode)),
+                    )
+                })
+                .collect()
+        };
+
+        let mut sections = vec![
+            DiagnosticsSection {
+                title: gettext("Media Source"),
+                rows: source,
+            },
+            DiagnosticsSection {
+                title: gettext("Play Method"),
+                rows: method,
+            },
+            DiagnosticsSection {
+                title: gettext("Fallback"),
+                rows: fallback,
+            },
+        ];
+        if !self.transcoding_params.is_empty() {
+            sections.push(DiagnosticsSection {
+                title: gettext("Transcoding Parameters"),
+                rows: self.transcoding_params.to_owned(),
+            });
+        }
+        sections
+    }
+
+    /// Plain text of every section and `stats`, for bug reports.
+    pub fn to_text(&self, stats: &MpvStats) -> String {
+        let mut sections = self.sections();
+        sections.push(stats_section(stats));
+
+        let mut text = format!("Tsukimi {}\n", crate::config::VERSION);
+        for section in sections {
+            text.push_str(&format!("\n[{}]\n", section.title));
+            for (label, value) in section.rows {
+                text.push_str(&format!("{label}: {value}\n"));
+            }
+        }
+        text
+    }
+}
+
+pub fn stats_section(stats: &MpvStats) -> DiagnosticsSection {
+    let unknown = || gettext("Unknown");
+    let dropped = match (stats.frame_drop_count, stats.decoder_frame_drop_count) {
+        (None, None) => unknown(),
+        (output, decoder) => gettext("{output} output, {decoder} decoder")
+            .replace("{output}", &output.unwrap_or_default().to_string())
+            .replace("{decoder}", &decoder.unwrap_or_default().to_string()),
+    };
+
+    DiagnosticsSection {
+        title: gettext("Live Statistics"),
+        rows: vec![
+            (
+                gettext("Video Bitrate"),
+                stats.video_bitrate.map_or_else(unknown, format_bitrate),
+            ),
+            (
+                gettext("Audio Bitrate"),
+                stats.audio_bitrate.map_or_else(unknown, format_bitrate),
+            ),
+            (gettext("Dropped Frames"), dropped),
+            (
+                gettext("Demuxer Cache"),
+                stats
+                    .demuxer_cache_duration
+                    .map_or_else(unknown, |seconds| format!("{seconds:.1} s")),
+            ),
+        ],
+    }
+}
+
+fn mode_label(mode: PlaybackDirectMode) -> String {
+    match (mode.enable_direct_play, mode.enable_direct_stream) {
+        (true, _) => gettext("Direct Play"),
+        (false, true) => gettext("Direct Stream"),
+        (false, false) => gettext("Transcode"),
+    }
+}
+
+fn format_bitrate(bits_per_second: f64) -> String {
+    if bits_per_second >= 1_000_000.0 {
+        format!("{:.2} Mbps", bits_per_second / 1_000_000.0)
+    } else {
+        format!("{:.0} kbps", bits_per_second / 1_000.0)
+    }
+}
+
+/// Query parameters of `url` without credentials.
+fn query_params(url: &str) -> Vec<(String, String)> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    url::form_urlencoded::parse(query.as_bytes())
+        .filter(|(key, _)| !SECRET_PARAMS.contains(&key.to_lowercase().as_str()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect()
+}
+
+/// Reasons from the media source, or from the transcoding URL on servers
+/// that only put them there.
+fn transcode_reasons(source: &MediaSource, params: &[(String, String)]) -> Vec<String> {
+    let split = |reasons: &str| -> Vec<String> {
+        reasons
+            .split(',')
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_owned)
+            .collect()
+    };
+
+    match &source.transcode_reasons {
+        Some(serde_json::Value::Array(reasons)) if !reasons.is_empty() => reasons
+            .iter()
+            .filter_map(|reason| reason.as_str().map(str::to_owned))
+            .collect(),
+        Some(serde_json::Value::String(reasons)) if !reasons.is_empty() => split(reasons),
+        _ => params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("TranscodeReasons"))
+            .map(|(_, reasons)| split(reasons))
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcoding_url_params_hide_secrets_and_give_reasons() {
+        let source: MediaSource = serde_json::from_value(serde_json::json!({
+            "Id": "source",
+            "MediaStreams": [],
+            "TranscodingUrl": "/videos/1/master.m3u8?VideoCodec=h264&api_key=secret\
+                &TranscodeReasons=ContainerNotSupported,AudioCodecNotSupported",
+        }))
+        .unwrap();
+
+        let diagnostics = PlaybackDiagnostics::new(
+            &source,
+            "Transcode",
+            PlaybackDirectMode::direct(),
+            Vec::new(),
+            "https://media.example.org:8096/videos/1/master.m3u8?api_key=secret",
+        );
+
+        assert!(
+            diagnostics
+                .transcoding_params
+                .iter()
+                .all(|(key, _)| key != "api_key")
+        );
+        assert_eq!(
+            diagnostics.transcode_reasons,
+            ["ContainerNotSupported", "AudioCodecNotSupported"]
+        );
+        assert_eq!(
+            diagnostics.stream_host.as_deref(),
+            Some("media.example.org:8096")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::cell::{
+    Cell,
+    RefCell,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    glib,
+    template_callbacks,
+};
+use mutsumi::MpvStats;
+
+use super::{
+    page::MPVPage,
+    playback_diagnostics::{
+        DiagnosticsSection,
+        stats_section,
+    },
+};
+
+mod imp {
+    use glib::subclass::InitializingObject;
+    use gtk::CompositeTemplate;
+
+    use super::*;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/playback_info_dialog.ui")]
+    pub struct PlaybackInfoDialog {
+        #[template_child]
+        pub toast: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub page: TemplateChild<adw::PreferencesPage>,
+        #[template_child]
+        pub stats_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub player: glib::WeakRef<MPVPage>,
+        pub stats: Cell<MpvStats>,
+        pub stats_rows: RefCell<Vec<adw::ActionRow>>,
+        pub refresh_source: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PlaybackInfoDialog {
+        const NAME: &'static str = "PlaybackInfoDialog";
+        type Type = super::PlaybackInfoDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PlaybackInfoDialog {}
+
+    impl WidgetImpl for PlaybackInfoDialog {}
+
+    impl AdwDialogImpl for PlaybackInfoDialog {
+        fn closed(&self) {
+            if let Some(source) = self.refresh_source.take() {
+                source.remove();
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Why the current stream plays the way it does, with live mpv statistics.
+    pub struct PlaybackInfoDialog(ObjectSubclass<imp::PlaybackInfoDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl PlaybackInfoDialog {
+    pub fn new(player: &MPVPage) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog.imp().player.set(Some(player));
+        dialog.setup();
+        dialog
+    }
+
+    fn setup(&self) {
+        let imp = self.imp();
+
+        let stats = stats_section(&MpvStats::default());
+        imp.stats_group.set_title(&stats.title);
+        let rows = stats
+            .rows
+            .into_iter()
+            .map(|(label, value)| {
+                let row = info_row(&label, &value);
+                imp.stats_group.add(&row);
+                row
+            })
+            .collect();
+        imp.stats_rows.replace(rows);
+
+        match imp
+            .player
+            .upgrade()
+            .and_then(|player| player.playback_diagnostics())
+        {
+            Some(diagnostics) => {
+                for section in diagnostics.sections() {
+                    imp.page.add(&section_group(section));
+                }
+            }
+            None => imp
+                .stats_group
+                .set_description(Some(&gettext("Playback has not started yet"))),
+        }
+
+        self.refresh_stats();
+        let source = glib::timeout_add_seconds_local(
+            1,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    obj.refresh_stats();
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+        imp.refresh_source.replace(Some(source));
+    }
+
+    fn refresh_stats(&self) {
+        let Some(player) = self.imp().player.upgrade() else {
+            return;
+        };
+        let mpv = *player.mpv();
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let stats = mpv.stats().await;
+                obj.imp().stats.set(stats);
+                let section = stats_section(&stats);
+                for (row, (_, value)) in obj.imp().stats_rows.borrow().iter().zip(section.rows) {
+                    row.set_subtitle(&value);
+                }
+            }
+        ));
+    }
+
+    #[template_callback]
+    fn on_copy_clicked(&self) {
+        let imp = self.imp();
+        let Some(diagnostics) = imp
+            .player
+            .upgrade()
+            .and_then(|player| player.playback_diagnostics())
+        else {
+            imp.toast
+                .add_toast(adw::Toast::new(&gettext("Nothing to copy yet")));
+            return;
+        };
+
+        self.clipboard()
+            .set_text(&diagnostics.to_text(&imp.stats.get()));
+        imp.toast.add_toast(adw::Toast::new(&gettext(
+            "Playback info copied to clipboard",
+        )));
+    }
+}
+
+fn section_group(section: DiagnosticsSection) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder()
+        .title(&section.title)
+        .build();
+    for (label, value) in section.rows {
+        group.add(&info_row(&label, &value));
+    }
+    group
+}
+
+fn info_row(label: &str, value: &str) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(label)
+        .subtitle(value)
+        .subtitle_selectable(true)
+        .build();
+    row.add_css_class("property");
+    row
+}
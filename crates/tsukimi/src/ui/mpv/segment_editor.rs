@@ -0,0 +1,222 @@
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk::{
+    glib,
+    subclass::prelude::*,
+};
+
+use super::page::MPVPage;
+use crate::{
+    alert_dialog,
+    client::{
+        error::UserFacingError,
+        structs::{
+            MediaSegment,
+            MediaSegmentType,
+        },
+    },
+    ui::{
+        GlobalToast,
+        provider::{
+            IS_ADMIN,
+            tu_item::TuItem,
+        },
+        widgets::song_widget::format_duration,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+impl MPVPage {
+    pub(super) fn reset_segment_editor(&self) {
+        self.imp().segment_mark_start.set(None);
+        let can_edit =
+            IS_ADMIN.load(std::sync::atomic::Ordering::Relaxed) && self.client().is_jellyfin();
+        self.action_set_enabled("mpv.mark-segment-start", can_edit);
+        self.action_set_enabled("mpv.mark-segment-end", false);
+    }
+
+    pub(super) fn mark_segment_start(&self) {
+        let imp = self.imp();
+        let position = imp.last_playback_position.get();
+        imp.segment_mark_start.set(Some(position));
+        self.action_set_enabled("mpv.mark-segment-end", true);
+        self.toast(
+            gettext("Segment start marked at {time}")
+                .replace("{time}", &format_duration(position as i64)),
+        );
+    }
+
+    pub(super) fn mark_segment_end(&self) {
+        let imp = self.imp();
+        let Some(start) = imp.segment_mark_start.get() else {
+            return;
+        };
+        let end = imp.last_playback_position.get();
+        if end <= start {
+            self.toast(gettext("Segment end must be after its start"));
+            return;
+        }
+        let Some(item) = self.current_video() else {
+            return;
+        };
+
+        let remaining_episodes = self.remaining_season_episodes(&item);
+
+        let segment_types = MediaSegmentType::SKIPPABLE;
+        let type_names = segment_types
+            .iter()
+            .map(|segment_type| segment_type_label(*segment_type))
+            .collect::<Vec<_>>();
+        let type_model =
+            gtk::StringList::new(&type_names.iter().map(String::as_str).collect::<Vec<_>>());
+        let type_dropdown = gtk::DropDown::builder()
+            .model(&type_model)
+            .halign(gtk::Align::Center)
+            .build();
+
+        let apply_check = gtk::CheckButton::builder()
+            .label(gettext("Apply to the remaining episodes of this season"))
+            .visible(!remaining_episodes.is_empty())
+            .build();
+
+        let content = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .build();
+        content.append(&type_dropdown);
+        content.append(&apply_check);
+
+        let alert_dialog = adw::AlertDialog::builder()
+            .heading(gettext("Save Media Segment"))
+            .body(format!(
+                "{} - {}",
+                format_duration(start as i64),
+                format_duration(end as i64)
+            ))
+            .extra_child(&content)
+            .build();
+
+        alert_dialog.add_response("close", &gettext("Cancel"));
+        alert_dialog.add_response("save", &gettext("Save"));
+        alert_dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+
+        alert_dialog.connect_response(
+            Some("save"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                type_dropdown,
+                #[weak]
+                apply_check,
+                move |_, _| {
+                    let segment_type = segment_types
+                        .get(type_dropdown.selected() as usize)
+                        .copied()
+                        .unwrap_or(MediaSegmentType::Intro);
+
+                    let mut item_ids = vec![item.id()];
+                    if apply_check.is_active() {
+                        item_ids.extend(remaining_episodes.iter().map(|episode| episode.id()));
+                    }
+
+                    obj.save_media_segment(item_ids, segment_type, start, end);
+                }
+            ),
+        );
+
+        alert_dialog!(self, alert_dialog);
+    }
+
+    fn remaining_season_episodes(&self, item: &TuItem) -> Vec<TuItem> {
+        if item.series_name().is_none() {
+            return Vec::new();
+        }
+
+        self.imp()
+            .current_episode_list
+            .borrow()
+            .iter()
+            .filter(|episode| {
+                episode.parent_index_number() == item.parent_index_number()
+                    && episode.index_number() > item.index_number()
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn save_media_segment(
+        &self, item_ids: Vec<String>, segment_type: MediaSegmentType, start: f64, end: f64,
+    ) {
+        self.reset_segment_editor();
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let total = item_ids.len();
+                let mut failed = 0;
+                let mut last_error = None;
+                let client = obj.client();
+
+                let provider_client = client.clone();
+                let provider_id =
+                    match spawn_tokio(
+                        async move { provider_client.media_segments_provider_id().await },
+                    )
+                    .await
+                    {
+                        Ok(provider_id) => provider_id,
+                        Err(e) => {
+                            obj.toast(e.to_user_facing());
+                            return;
+                        }
+                    };
+
+                for item_id in item_ids {
+                    let segment = MediaSegment::from_seconds(&item_id, segment_type, start, end);
+                    let client = client.clone();
+                    let provider_id = provider_id.to_owned();
+                    if let Err(e) = spawn_tokio(async move {
+                        client
+                            .replace_media_segment(&item_id, &segment, &provider_id)
+                            .await
+                    })
+                    .await
+                    {
+                        failed += 1;
+                        last_error = Some(e);
+                    }
+                }
+
+                match last_error {
+                    Some(e) if failed == total => obj.toast(e.to_user_facing()),
+                    Some(_) => obj.toast(
+                        gettext("{failed} of {total} segments failed to save")
+                            .replace("{failed}", &failed.to_string())
+                            .replace("{total}", &total.to_string()),
+                    ),
+                    None => obj.toast(gettext("Media segment saved")),
+                }
+
+                if let Some(item) = obj.current_video() {
+                    obj.load_skippable_segments(item.id());
+                }
+            }
+        ));
+    }
+}
+
+fn segment_type_label(segment_type: MediaSegmentType) -> String {
+    match segment_type {
+        MediaSegmentType::Intro => gettext("Intro"),
+        MediaSegmentType::Outro => gettext("Outro"),
+        MediaSegmentType::Recap => gettext("Recap"),
+        MediaSegmentType::Preview => gettext("Preview"),
+        MediaSegmentType::Commercial => gettext("Commercial"),
+        _ => gettext("Unknown"),
+    }
+}
@@ -1,9 +1,6 @@
 use std::cell::Cell;
 
-use crate::{
-    client::jellyfin_client::JELLYFIN_CLIENT,
-    utils::spawn,
-};
+use crate::utils::spawn;
 
 use adw::{
     prelude::*,
@@ -94,6 +91,7 @@ impl MPVPlaySink {
         &self.imp().player
     }
 
+    /// Plays `url`, the full stream URL of the video.
     pub fn play(&self, url: &str, start_seconds: f64) {
         let url = url.to_owned();
 
@@ -101,8 +99,6 @@ impl MPVPlaySink {
             #[weak(rename_to = obj)]
             self,
             async move {
-                let url = JELLYFIN_CLIENT.get_streaming_url(&url).await;
-
                 info!("Now Playing: {}", url);
                 obj.imp().position.set(start_seconds);
                 obj.imp().paused.set(false);
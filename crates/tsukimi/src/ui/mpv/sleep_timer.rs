@@ -0,0 +1,133 @@
+use gettextrs::gettext;
+use gtk::{
+    glib,
+    subclass::prelude::*,
+};
+
+use super::page::MPVPage;
+use crate::ui::{
+    GlobalToast,
+    models::sleep_timer::SleepTimerMode,
+};
+
+impl MPVPage {
+    pub(super) fn set_sleep_timer(&self, mode: SleepTimerMode) {
+        self.stop_sleep_timer();
+        if mode == SleepTimerMode::Off {
+            self.toast(gettext("Sleep timer cancelled"));
+            self.notify_mpris_track_changed();
+            return;
+        }
+
+        let imp = self.imp();
+        imp.sleep_timer
+            .borrow_mut()
+            .set(mode, glib::monotonic_time());
+        let source = glib::timeout_add_seconds_local(
+            1,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || obj.sleep_timer_tick()
+            ),
+        );
+        imp.sleep_timer_source.replace(Some(source));
+        if let Some(description) = self.sleep_timer_description() {
+            self.toast(description);
+        }
+        self.notify_mpris_track_changed();
+    }
+
+    pub(super) fn sleep_timer_description(&self) -> Option<String> {
+        self.imp()
+            .sleep_timer
+            .borrow()
+            .describe(glib::monotonic_time())
+    }
+
+    /// Whether the sleep timer wants playback to stop after the current video.
+    pub(super) fn sleep_timer_on_last_item(&self) -> bool {
+        self.imp().sleep_timer.borrow().on_last_item()
+    }
+
+    /// Counts the video that just finished, and stops playback once the
+    /// sleep timer ran out of videos. Returns whether it stopped.
+    pub(super) fn sleep_timer_item_finished(&self) -> bool {
+        let finished = self.imp().sleep_timer.borrow_mut().item_finished();
+        if finished {
+            self.finish_sleep_timer();
+        } else if self.imp().sleep_timer.borrow().is_active() {
+            self.notify_mpris_track_changed();
+        }
+        finished
+    }
+
+    fn sleep_timer_tick(&self) -> glib::ControlFlow {
+        let imp = self.imp();
+        let now = glib::monotonic_time();
+        let item_remaining = imp.duration.get() - imp.last_playback_position.get();
+        let (active, expired, factor, description) = {
+            let timer = imp.sleep_timer.borrow();
+            (
+                timer.is_active(),
+                timer.expired(now),
+                timer.volume_factor(now, item_remaining),
+                timer.describe(now),
+            )
+        };
+
+        if !active || expired {
+            // Returning `Break` removes the source already
+            imp.sleep_timer_source.take();
+            if expired {
+                self.finish_sleep_timer();
+            } else {
+                self.stop_sleep_timer();
+            }
+            return glib::ControlFlow::Break;
+        }
+
+        if factor < 1.0 {
+            let volume = match imp.sleep_timer_volume.get() {
+                Some(volume) => volume,
+                None => {
+                    let volume = imp.volume_adj.value().round() as i64;
+                    imp.sleep_timer_volume.set(Some(volume));
+                    volume
+                }
+            };
+            imp.video
+                .set_volume((volume as f64 * factor).round() as i64);
+        }
+
+        if imp.sleep_timer_label.replace(description.clone()) != description {
+            self.notify_mpris_track_changed();
+        }
+        glib::ControlFlow::Continue
+    }
+
+    /// Stops playback, which reports the position it reached to the server.
+    fn finish_sleep_timer(&self) {
+        self.imp().sleep_timer.borrow_mut().cancel();
+        self.on_stop_clicked();
+    }
+
+    /// Called once playback stopped, for whatever reason.
+    pub(super) fn stop_sleep_timer(&self) {
+        let imp = self.imp();
+        imp.sleep_timer.borrow_mut().cancel();
+        imp.sleep_timer_label.take();
+        if let Some(source) = imp.sleep_timer_source.take() {
+            source.remove();
+        }
+        self.restore_sleep_timer_volume();
+    }
+
+    fn restore_sleep_timer_volume(&self) {
+        if let Some(volume) = self.imp().sleep_timer_volume.take() {
+            self.imp().video.set_volume(volume);
+        }
+    }
+}
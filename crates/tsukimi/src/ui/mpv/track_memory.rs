@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use mutsumi::MpvTrack;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    danmaku_cache_map::item_cache_key,
+    page::MpvTrackKind,
+};
+use crate::ui::{
+    models::SETTINGS,
+    provider::tu_item::TuItem,
+};
+
+/// A track picked by the user. Track ids change between files, so the next
+/// episode is matched by what the track is rather than where it is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackMatch {
+    pub lang: String,
+    pub title: String,
+    pub codec: String,
+}
+
+impl TrackMatch {
+    fn from_track(track: &MpvTrack) -> Self {
+        Self {
+            lang: track.lang.to_owned(),
+            title: track.title.to_owned(),
+            codec: track.codec.to_owned(),
+        }
+    }
+
+    /// Id of the track in `tracks` that is most like this one. The language
+    /// has to match when it is known, otherwise the title.
+    pub fn find(&self, tracks: &[MpvTrack]) -> Option<i64> {
+        let known_lang = self.lang != "unknown";
+        tracks
+            .iter()
+            .filter(|track| {
+                if known_lang {
+                    track.lang == self.lang
+                } else {
+                    track.title == self.title
+                }
+            })
+            .max_by_key(|track| {
+                (track.title == self.title) as u8 * 2 + (track.codec == self.codec) as u8
+            })
+            .map(|track| track.id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackChoice {
+    Off,
+    Track(TrackMatch),
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct TrackMemoryEntry {
+    audio: Option<TrackChoice>,
+    subtitle: Option<TrackChoice>,
+}
+
+impl TrackMemoryEntry {
+    fn choice_mut(&mut self, kind: MpvTrackKind) -> &mut Option<TrackChoice> {
+        match kind {
+            MpvTrackKind::Audio => &mut self.audio,
+            MpvTrackKind::Subtitle => &mut self.subtitle,
+        }
+    }
+}
+
+/// Audio and subtitle tracks picked in the player, per series or movie.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TrackMemory {
+    entries: HashMap<String, TrackMemoryEntry>,
+}
+
+impl TrackMemory {
+    pub fn load() -> Self {
+        serde_json::from_str(&SETTINGS.track_memory()).unwrap_or_default()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        SETTINGS.set_track_memory(&serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Series ids are only unique on one server, and users on the same server
+    /// pick their own tracks.
+    fn entry_key(item: &TuItem) -> Option<String> {
+        Some(format!(
+            "{}:{}",
+            item.client().session().account_key(),
+            item_cache_key(item)?
+        ))
+    }
+
+    pub fn choice(&self, item: &TuItem, kind: MpvTrackKind) -> Option<TrackChoice> {
+        let entry = self.entries.get(&Self::entry_key(item)?)?;
+        match kind {
+            MpvTrackKind::Audio => entry.audio.to_owned(),
+            MpvTrackKind::Subtitle => entry.subtitle.to_owned(),
+        }
+    }
+
+    /// Remembers `track` for the series of `item`, `None` turns the track off.
+    pub fn remember(
+        &mut self, item: &TuItem, kind: MpvTrackKind, track: Option<&MpvTrack>,
+    ) -> anyhow::Result<()> {
+        let key = Self::entry_key(item)
+            .ok_or_else(|| anyhow::anyhow!("Current item has no stable cache key"))?;
+        let choice = track.map_or(TrackChoice::Off, |track| {
+            TrackChoice::Track(TrackMatch::from_track(track))
+        });
+        self.entries
+            .entry(key)
+            .or_default()
+            .choice_mut(kind)
+            .replace(choice);
+        self.save()
+    }
+
+    pub fn forget(&mut self, item: &TuItem, kind: MpvTrackKind) -> anyhow::Result<()> {
+        let Some(key) = Self::entry_key(item) else {
+            return Ok(());
+        };
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.choice_mut(kind).take();
+            if entry.audio.is_none() && entry.subtitle.is_none() {
+                self.entries.remove(&key);
+            }
+        }
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: i64, lang: &str, title: &str, codec: &str) -> MpvTrack {
+        MpvTrack {
+            id,
+            title: title.to_owned(),
+            lang: lang.to_owned(),
+            codec: codec.to_owned(),
+            type_: "audio".to_owned(),
+        }
+    }
+
+    #[test]
+    fn find_prefers_language_then_title_and_codec() {
+        let tracks = [
+            track(1, "jpn", "Stereo", "aac"),
+            track(2, "eng", "Commentary", "aac"),
+            track(3, "eng", "Surround", "eac3"),
+        ];
+        let remembered = TrackMatch {
+            lang: "eng".to_owned(),
+            title: "Surround".to_owned(),
+            codec: "aac".to_owned(),
+        };
+        assert_eq!(remembered.find(&tracks), Some(3));
+
+        let remembered = TrackMatch {
+            lang: "ger".to_owned(),
+            title: "Surround".to_owned(),
+            codec: "eac3".to_owned(),
+        };
+        assert_eq!(remembered.find(&tracks), None);
+    }
+}
@@ -0,0 +1,156 @@
+use adw::prelude::*;
+use gtk::{
+    glib,
+    subclass::prelude::*,
+};
+
+use super::page::MPVPage;
+use crate::{
+    client::error::UserFacingError,
+    ui::{
+        GlobalToast,
+        models::SETTINGS,
+        provider::tu_item::TuItem,
+        widgets::window::Window,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpNextState {
+    #[default]
+    Hidden,
+    Shown,
+    /// Moving on to the next video before the current one ended
+    Advancing,
+    /// The user doesn't want the next video, stop at the end instead
+    Cancelled,
+}
+
+impl MPVPage {
+    pub(super) fn reset_up_next(&self) {
+        let imp = self.imp();
+        imp.up_next_state.set(UpNextState::Hidden);
+        imp.outro_start.set(None);
+        imp.up_next_revealer.set_reveal_child(false);
+    }
+
+    /// Shows the Up Next card `mpv-up-next-lead-time` seconds before the
+    /// end, or when the outro starts.
+    pub(super) fn update_up_next(&self, position: f64) {
+        let imp = self.imp();
+        let state = imp.up_next_state.get();
+        if matches!(state, UpNextState::Advancing | UpNextState::Cancelled) {
+            return;
+        }
+
+        let lead = SETTINGS.mpv_up_next_lead_time() as f64;
+        let duration = imp.duration.get();
+        if lead <= 0.0
+            || duration <= 0.0
+            || SETTINGS.mpv_action_after_video_end() != 0
+            || self.sleep_timer_on_last_item()
+        {
+            self.hide_up_next();
+            return;
+        }
+
+        let end_start = (duration - lead).max(0.0);
+        let start = imp
+            .outro_start
+            .get()
+            .map_or(end_start, |outro| outro.min(end_start));
+        // Started by the outro, the countdown still lasts `lead` seconds
+        let deadline = (start + lead).min(duration);
+        if position < start {
+            self.hide_up_next();
+            return;
+        }
+
+        if state == UpNextState::Hidden {
+            let Some(next) = self.neighbour_video(1) else {
+                return;
+            };
+            imp.up_next_card.set_item(&next);
+            imp.up_next_revealer.set_reveal_child(true);
+            imp.up_next_state.set(UpNextState::Shown);
+        }
+        imp.up_next_card
+            .set_countdown((deadline - position).max(0.0).ceil() as u32);
+
+        // The end of the file plays the next video by itself
+        if position >= deadline && deadline + 1.0 < duration {
+            imp.up_next_state.set(UpNextState::Advancing);
+            imp.up_next_revealer.set_reveal_child(false);
+            spawn(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                async move {
+                    // This video ends here, it won't reach the end of the file
+                    if !obj.sleep_timer_item_finished() {
+                        obj.auto_advance().await;
+                    }
+                }
+            ));
+        }
+    }
+
+    fn hide_up_next(&self) {
+        let imp = self.imp();
+        if imp.up_next_state.get() == UpNextState::Shown {
+            imp.up_next_state.set(UpNextState::Hidden);
+        }
+        imp.up_next_revealer.set_reveal_child(false);
+    }
+
+    pub(super) fn up_next_cancelled(&self) -> bool {
+        self.imp().up_next_state.get() == UpNextState::Cancelled
+    }
+
+    pub fn up_next_play_now(&self) {
+        let imp = self.imp();
+        imp.up_next_state.set(UpNextState::Advancing);
+        imp.up_next_revealer.set_reveal_child(false);
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                obj.on_next_video().await;
+            }
+        ));
+    }
+
+    pub fn up_next_cancel(&self) {
+        let imp = self.imp();
+        imp.up_next_state.set(UpNextState::Cancelled);
+        imp.up_next_revealer.set_reveal_child(false);
+    }
+
+    pub fn up_next_go_to_series(&self) {
+        let Some(series_id) = self.current_video().and_then(|item| item.series_id()) else {
+            return;
+        };
+        let Some(window) = self.root().and_downcast::<Window>() else {
+            return;
+        };
+
+        let client = self.client();
+        self.up_next_cancel();
+        self.on_stop_clicked();
+        spawn(glib::clone!(
+            #[weak]
+            window,
+            async move {
+                let fetch_client = client.clone();
+                match spawn_tokio(async move { fetch_client.get_item_info(&series_id).await }).await
+                {
+                    Ok(item) => TuItem::from_simple(item, &client).activate(&window),
+                    Err(e) => window.toast(e.to_user_facing()),
+                }
+            }
+        ));
+    }
+}
@@ -0,0 +1,140 @@
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use super::page::MPVPage;
+use crate::ui::{
+    provider::tu_item::TuItem,
+    widgets::{
+        picture_loader::PictureLoader,
+        tu_item::{
+            CardOptions,
+            CardShape,
+            select_picture_source,
+        },
+    },
+};
+
+mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/up_next_card.ui")]
+    pub struct UpNextCard {
+        #[template_child]
+        pub picture_bin: TemplateChild<adw::Bin>,
+        #[template_child]
+        pub countdown_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub title_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub subtitle_label: TemplateChild<gtk::Label>,
+
+        pub page: glib::WeakRef<MPVPage>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for UpNextCard {
+        const NAME: &'static str = "UpNextCard";
+        type Type = super::UpNextCard;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for UpNextCard {}
+    impl WidgetImpl for UpNextCard {}
+    impl BinImpl for UpNextCard {}
+}
+
+glib::wrapper! {
+    /// The episode that plays next, shown shortly before the current one ends.
+    pub struct UpNextCard(ObjectSubclass<imp::UpNextCard>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for UpNextCard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[template_callbacks]
+impl UpNextCard {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn set_page(&self, page: &MPVPage) {
+        self.imp().page.set(Some(page));
+    }
+
+    pub fn set_item(&self, item: &TuItem) {
+        let imp = self.imp();
+
+        imp.title_label.set_text(&item.name());
+        imp.subtitle_label.set_text(&match item.series_name() {
+            Some(series_name) => format!(
+                "{series_name} · S{}E{}",
+                item.parent_index_number(),
+                item.index_number()
+            ),
+            None => String::new(),
+        });
+
+        let source = select_picture_source(
+            item,
+            CardOptions {
+                shape: CardShape::Backdrop,
+                prefer_thumb: true,
+                ..Default::default()
+            },
+        );
+        imp.picture_bin
+            .set_child(source.map(PictureLoader::new_for_source).as_ref());
+    }
+
+    pub fn set_countdown(&self, seconds: u32) {
+        self.imp()
+            .countdown_label
+            .set_text(&gettext("Up Next in {seconds}s").replace("{seconds}", &seconds.to_string()));
+    }
+
+    #[template_callback]
+    fn on_play_now_clicked(&self) {
+        if let Some(page) = self.imp().page.upgrade() {
+            page.up_next_play_now();
+        }
+    }
+
+    #[template_callback]
+    fn on_cancel_clicked(&self) {
+        if let Some(page) = self.imp().page.upgrade() {
+            page.up_next_cancel();
+        }
+    }
+
+    #[template_callback]
+    fn on_series_clicked(&self) {
+        if let Some(page) = self.imp().page.upgrade() {
+            page.up_next_go_to_series();
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::Utc;
+use gtk::{
+    glib,
+    subclass::prelude::*,
+};
+use tracing::warn;
+
+use super::page::MPVPage;
+use crate::{
+    client::{
+        jellyfin_client::BackType,
+        structs::Back,
+    },
+    ui::{
+        models::watch_history::{
+            WatchHistory,
+            WatchSession,
+        },
+        provider::tu_item::EPISODE,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+/// Progress is reported every 10 seconds while playing, so a longer gap
+/// between two reports means playback was paused in between.
+const MAX_REPORT_GAP: f64 = 10.0;
+
+impl MPVPage {
+    /// Feeds the local watch history with what is reported to the server.
+    pub(super) fn record_watch_history(&self, back: &Back, backtype: &BackType) {
+        let imp = self.imp();
+        let position = back.tick as f64 / 10_000_000.0;
+        let now = glib::monotonic_time();
+
+        if matches!(backtype, BackType::Start) {
+            if imp
+                .watch_session
+                .borrow()
+                .as_ref()
+                .is_some_and(|session| session.item_id == back.id)
+            {
+                return;
+            }
+            self.finish_watch_session();
+            self.start_watch_session(back, position);
+            imp.watch_session_report_time.set(now);
+            return;
+        }
+
+        let elapsed = (now - imp.watch_session_report_time.replace(now)) as f64 / 1e6;
+        if let Some(session) = imp.watch_session.borrow_mut().as_mut()
+            && session.item_id == back.id
+        {
+            session.end_position = position;
+            session.watched += elapsed.clamp(0.0, MAX_REPORT_GAP);
+        }
+
+        if matches!(backtype, BackType::Stop) {
+            self.finish_watch_session();
+        }
+    }
+
+    fn start_watch_session(&self, back: &Back, position: f64) {
+        let Some(video) = self.current_video().filter(|video| video.id() == back.id) else {
+            return;
+        };
+
+        let is_episode = video.item_type() == EPISODE;
+        let season = video.parent_index_number();
+        let season_episodes = is_episode.then(|| {
+            self.imp()
+                .current_episode_list
+                .borrow()
+                .iter()
+                .filter(|item| {
+                    item.series_id() == video.series_id() && item.parent_index_number() == season
+                })
+                .count() as u32
+        });
+
+        self.imp().watch_session.replace(Some(WatchSession {
+            account: video.client().session().account_key(),
+            item_id: back.id.to_owned(),
+            item_name: video.name(),
+            series_id: video.series_id(),
+            series_name: video.series_name(),
+            season: is_episode.then_some(season),
+            episode: is_episode.then(|| video.index_number()),
+            season_episodes,
+            genres: Vec::new(),
+            play_method: back.playmethod.to_owned(),
+            started_at: Utc::now(),
+            start_position: position,
+            end_position: position,
+            runtime: video.run_time_ticks() as f64 / 10_000_000.0,
+            watched: 0.0,
+        }));
+
+        // Episodes rarely carry genres, the series does
+        let id = video.series_id().unwrap_or_else(|| video.id());
+        let item_id = back.id.to_owned();
+        let client = video.client();
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let Ok(item) = spawn_tokio(async move { client.get_item_info(&id).await }).await
+                else {
+                    return;
+                };
+                let genres = item
+                    .genres
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|genre| genre.name)
+                    .collect();
+                if let Some(session) = obj.imp().watch_session.borrow_mut().as_mut()
+                    && session.item_id == item_id
+                {
+                    session.genres = genres;
+                }
+            }
+        ));
+    }
+
+    fn finish_watch_session(&self) {
+        let Some(session) = self.imp().watch_session.take() else {
+            return;
+        };
+        spawn(async move {
+            if let Err(e) = WatchHistory::append(session).await {
+                warn!("Failed to record watch history: {}", e);
+            }
+        });
+    }
+}
@@ -1,8 +1,5 @@
 use crate::{
-    client::{
-        error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
-    },
+    client::error::UserFacingError,
     ui::{
         GlobalToast,
         widgets::{
@@ -45,10 +42,11 @@ macro_rules! impl_has_likeaction {
                             spawn(
                                 glib::clone!(#[weak] obj, #[strong] id, async move {
 
+                                    let client = obj.item().client();
                                     let result = if active {
-                                        spawn_tokio(async move {JELLYFIN_CLIENT.like(&id).await} ).await
+                                        spawn_tokio(async move {client.like(&id).await} ).await
                                     } else {
-                                        spawn_tokio(async move {JELLYFIN_CLIENT.unlike(&id).await} ).await
+                                        spawn_tokio(async move {client.unlike(&id).await} ).await
                                     };
 
                                     match result {
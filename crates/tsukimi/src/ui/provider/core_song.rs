@@ -1,4 +1,7 @@
-use std::cell::RefCell;
+use std::{
+    cell::RefCell,
+    sync::Arc,
+};
 
 use gtk::{
     glib,
@@ -8,7 +11,13 @@ use gtk::{
     },
 };
 
-use crate::ui::widgets::song_widget::State;
+use crate::{
+    client::{
+        jellyfin_client::JellyfinClient,
+        registry::CLIENTS,
+    },
+    ui::widgets::song_widget::State,
+};
 
 pub mod imp {
     use std::cell::Cell;
@@ -38,6 +47,7 @@ pub mod imp {
         pub image_source: RefCell<Option<PictureSource>>,
         #[property(get, set)]
         pub duration: Cell<u64>,
+        pub client: RefCell<Option<Arc<JellyfinClient>>>,
     }
 
     #[glib::derived_properties]
@@ -71,6 +81,20 @@ impl CoreSong {
             .property("state", State::Unplayed)
             .build()
     }
+
+    /// Client of the account the song is streamed from, the active one for
+    /// songs that weren't given one.
+    pub fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| CLIENTS.active())
+    }
+
+    pub fn set_client(&self, client: Arc<JellyfinClient>) {
+        self.imp().client.replace(Some(client));
+    }
 }
 
 impl Default for CoreSong {
@@ -1,34 +1,97 @@
+use gettextrs::gettext;
 use serde::{
     Deserialize,
     Serialize,
 };
 
+use crate::client::structs::MediaSource;
+
 #[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub struct Descriptor {
     pub content: String,
     pub type_: DescriptorType,
+    /// Score added to versions matching the rule. Rules without a weight
+    /// count more the higher they are in the list.
+    #[serde(default)]
+    pub weight: Option<u32>,
+    /// Versions matching the rule are never chosen
+    #[serde(default)]
+    pub exclude: bool,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub enum DescriptorType {
     String,
     Regex,
+    Resolution,
+    VideoCodec,
+    HdrType,
+    Bitrate,
+    AudioChannels,
+    AudioLanguage,
+    Container,
 }
 
 impl DescriptorType {
+    /// In the order of the descriptor type combo rows
+    const ALL: [Self; 9] = [
+        Self::String,
+        Self::Regex,
+        Self::Resolution,
+        Self::VideoCodec,
+        Self::HdrType,
+        Self::Bitrate,
+        Self::AudioChannels,
+        Self::AudioLanguage,
+        Self::Container,
+    ];
+
     pub fn from_str(s: &str) -> Self {
-        match s {
-            "String" => Self::String,
-            "Regex" => Self::Regex,
-            _ => panic!("Invalid DescriptorType"),
-        }
+        Self::ALL
+            .into_iter()
+            .find(|type_| type_.to_string() == s)
+            .expect("Invalid DescriptorType")
     }
 
     pub fn from_u32(u: u32) -> Self {
-        match u {
-            0 => Self::String,
-            1 => Self::Regex,
-            _ => panic!("Invalid DescriptorType"),
+        Self::ALL
+            .get(u as usize)
+            .cloned()
+            .expect("Invalid DescriptorType")
+    }
+
+    pub fn to_u32(&self) -> u32 {
+        Self::ALL
+            .iter()
+            .position(|type_| type_ == self)
+            .unwrap_or_default() as u32
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::String => gettext("String"),
+            Self::Regex => gettext("Regular Expression"),
+            Self::Resolution => gettext("Resolution"),
+            Self::VideoCodec => gettext("Video Codec"),
+            Self::HdrType => gettext("HDR Type"),
+            Self::Bitrate => gettext("Bitrate (Mbps)"),
+            Self::AudioChannels => gettext("Audio Channels"),
+            Self::AudioLanguage => gettext("Audio Language"),
+            Self::Container => gettext("Container"),
+        }
+    }
+
+    pub fn example(&self) -> String {
+        match self {
+            Self::String => gettext("eg. 1080p"),
+            Self::Regex => gettext("eg. 1080p.*WebDL"),
+            Self::Resolution => gettext("eg. >=1080"),
+            Self::VideoCodec => gettext("eg. hevc"),
+            Self::HdrType => gettext("eg. DOVI"),
+            Self::Bitrate => gettext("eg. <=40"),
+            Self::AudioChannels => gettext("eg. >=6"),
+            Self::AudioLanguage => gettext("eg. jpn"),
+            Self::Container => gettext("eg. mkv"),
         }
     }
 }
@@ -36,8 +99,15 @@ impl DescriptorType {
 impl std::fmt::Display for DescriptorType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let str = match self {
-            DescriptorType::String => "String".to_string(),
-            DescriptorType::Regex => "Regex".to_string(),
+            DescriptorType::String => "String",
+            DescriptorType::Regex => "Regex",
+            DescriptorType::Resolution => "Resolution",
+            DescriptorType::VideoCodec => "VideoCodec",
+            DescriptorType::HdrType => "HdrType",
+            DescriptorType::Bitrate => "Bitrate",
+            DescriptorType::AudioChannels => "AudioChannels",
+            DescriptorType::AudioLanguage => "AudioLanguage",
+            DescriptorType::Container => "Container",
         };
         write!(f, "{str}")
     }
@@ -55,6 +125,186 @@ impl VecSerialize<Descriptor> for Vec<Descriptor> {
 
 impl Descriptor {
     pub fn new(content: String, type_: DescriptorType) -> Self {
-        Self { content, type_ }
+        Self {
+            content,
+            type_,
+            weight: None,
+            exclude: false,
+        }
+    }
+
+    /// Short description of the rule, e.g. "Resolution >=1080"
+    pub fn summary(&self) -> String {
+        match self.type_ {
+            DescriptorType::String | DescriptorType::Regex => self.content.to_owned(),
+            _ => format!("{} {}", self.type_.label(), self.content),
+        }
+    }
+
+    pub fn matches(&self, source: &MediaSource) -> bool {
+        let content = self.content.trim().to_lowercase();
+        let video = source
+            .media_streams
+            .iter()
+            .find(|stream| stream.stream_type == "Video");
+        let mut audio = source
+            .media_streams
+            .iter()
+            .filter(|stream| stream.stream_type == "Audio");
+        let eq = |value: Option<&String>| value.is_some_and(|v| v.eq_ignore_ascii_case(&content));
+
+        match self.type_ {
+            DescriptorType::String => source.name.to_lowercase().contains(&content),
+            DescriptorType::Regex => {
+                regex::Regex::new(&content).is_ok_and(|re| re.is_match(&source.name.to_lowercase()))
+            }
+            DescriptorType::Resolution => video
+                .and_then(|stream| stream.height)
+                .is_some_and(|height| compare(&content, height as f64)),
+            DescriptorType::VideoCodec => eq(video.and_then(|stream| stream.codec.as_ref())),
+            DescriptorType::HdrType => video
+                .and_then(|stream| stream.video_range_type.as_ref())
+                .is_some_and(|range| range.to_lowercase().contains(&content)),
+            DescriptorType::Bitrate => source
+                .bit_rate
+                .is_some_and(|bit_rate| compare(&content, bit_rate as f64 / 1_000_000.0)),
+            DescriptorType::AudioChannels => audio.any(|stream| {
+                stream
+                    .channels
+                    .is_some_and(|channels| compare(&content, channels as f64))
+            }),
+            DescriptorType::AudioLanguage => audio.any(|stream| eq(stream.language.as_ref())),
+            DescriptorType::Container => source.container.as_ref().is_some_and(|container| {
+                container
+                    .to_lowercase()
+                    .split(',')
+                    .any(|c| c.trim() == content)
+            }),
+        }
+    }
+}
+
+/// Compares `value` with an expression like `>=1080`, `<8` or `2160p`.
+fn compare(expr: &str, value: f64) -> bool {
+    let expr = expr.replace(' ', "");
+    let (op, number) = [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|op| expr.strip_prefix(op).map(|number| (op, number)))
+        .unwrap_or(("=", expr.as_str()));
+    let Ok(number) = number.trim_end_matches('p').parse::<f64>() else {
+        return false;
+    };
+    match op {
+        ">=" => value >= number,
+        "<=" => value <= number,
+        ">" => value > number,
+        "<" => value < number,
+        _ => (value - number).abs() < f64::EPSILON,
+    }
+}
+
+/// The version picked by [`choose_version`] and the rules behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionChoice {
+    pub index: usize,
+    /// Matched rules of the chosen version, e.g. "HEVC (+2)"
+    pub reasons: Vec<String>,
+    /// Every version was excluded, so the best of them was used anyway
+    pub all_excluded: bool,
+}
+
+/// Picks the highest scoring version, the first one wins ties.
+pub fn choose_version(
+    descriptors: &[Descriptor], sources: &[MediaSource],
+) -> Option<VersionChoice> {
+    let len = descriptors.len();
+    let scored: Vec<_> = sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| {
+            let mut score = 0_u64;
+            let mut excluded = false;
+            let mut reasons = Vec::new();
+            for (position, descriptor) in descriptors.iter().enumerate() {
+                if !descriptor.matches(source) {
+                    continue;
+                }
+                if descriptor.exclude {
+                    excluded = true;
+                    continue;
+                }
+                // Without weights, a rule outweighs all rules below it
+                let weight = descriptor
+                    .weight
+                    .map_or(1_u64 << (len - position - 1).min(32), u64::from);
+                score += weight;
+                reasons.push(format!("{} (+{weight})", descriptor.summary()));
+            }
+            (index, excluded, score, reasons)
+        })
+        .collect();
+
+    let all_excluded = scored.iter().all(|(_, excluded, ..)| *excluded);
+    scored
+        .into_iter()
+        .filter(|(_, excluded, ..)| all_excluded || !excluded)
+        .min_by_key(|(index, _, score, _)| (std::cmp::Reverse(*score), *index))
+        .map(|(index, _, _, reasons)| VersionChoice {
+            index,
+            reasons,
+            all_excluded,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(name: &str, height: u64, codec: &str) -> MediaSource {
+        serde_json::from_value(serde_json::json!({
+            "Id": name,
+            "Name": name,
+            "MediaStreams": [{
+                "Type": "Video",
+                "IsExternal": false,
+                "Index": 0,
+                "Height": height,
+                "Codec": codec,
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn unweighted_rules_keep_list_priority() {
+        let sources = [
+            source("720p WebDL", 720, "h264"),
+            source("1080p BluRay", 1080, "h264"),
+            source("1080p WebDL", 1080, "hevc"),
+        ];
+        let descriptors = [
+            Descriptor::new("1080p".to_owned(), DescriptorType::String),
+            Descriptor::new("webdl".to_owned(), DescriptorType::String),
+        ];
+        let choice = choose_version(&descriptors, &sources).unwrap();
+        assert_eq!(choice.index, 2);
+
+        let mut exclude = Descriptor::new("hevc".to_owned(), DescriptorType::VideoCodec);
+        exclude.exclude = true;
+        let mut resolution = Descriptor::new(">=1080".to_owned(), DescriptorType::Resolution);
+        resolution.weight = Some(5);
+        let choice = choose_version(&[exclude, resolution], &sources).unwrap();
+        assert_eq!(choice.index, 1);
+        assert!(!choice.all_excluded);
+    }
+
+    #[test]
+    fn legacy_descriptors_deserialize() {
+        let descriptors: Vec<Descriptor> =
+            serde_json::from_str(r#"[{"content":"1080p","type_":"String"}]"#).unwrap();
+        assert_eq!(
+            descriptors[0],
+            Descriptor::new("1080p".to_owned(), DescriptorType::String)
+        );
     }
 }
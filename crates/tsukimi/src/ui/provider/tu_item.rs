@@ -1,6 +1,9 @@
-use std::cell::{
-    Cell,
-    RefCell,
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    sync::Arc,
 };
 
 use adw::prelude::*;
@@ -32,6 +35,7 @@ pub mod item_type {
     pub const BOX_SET: &str = "BoxSet";
     pub const TAG: &str = "Tag";
     pub const GENRE: &str = "Genre";
+    pub const STUDIO: &str = "Studio";
     pub const MUSIC_GENRE: &str = "MusicGenre";
     pub const EPISODE: &str = "Episode";
     pub const VIEWS: &str = "Views";
@@ -67,7 +71,8 @@ use crate::{
     bing_song_model,
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
+        registry::CLIENTS,
         structs::{
             SimpleListItem,
             SongWidgetView,
@@ -217,6 +222,9 @@ pub mod imp {
         path: RefCell<Option<String>>,
         #[property(get, set)]
         playback_position_ticks: Cell<u64>,
+        #[property(get, set, nullable)]
+        server_name: RefCell<Option<String>>,
+        pub(super) client: RefCell<Option<Arc<JellyfinClient>>>,
     }
 
     #[glib::derived_properties]
@@ -340,7 +348,7 @@ impl From<SearchAnimeDetails> for SimpleListItem {
 
 impl From<SearchAnimeDetails> for TuItem {
     fn from(anime: SearchAnimeDetails) -> Self {
-        Self::from_simple(SimpleListItem::from(anime))
+        Self::from(SimpleListItem::from(anime))
     }
 }
 
@@ -358,7 +366,7 @@ impl From<SearchEpisodesAnime> for SimpleListItem {
 
 impl From<SearchEpisodesAnime> for TuItem {
     fn from(anime: SearchEpisodesAnime) -> Self {
-        Self::from_simple(SimpleListItem::from(anime))
+        Self::from(SimpleListItem::from(anime))
     }
 }
 
@@ -378,13 +386,16 @@ impl From<SearchEpisodeDetails> for SimpleListItem {
 
 impl From<SearchEpisodeDetails> for TuItem {
     fn from(episode: SearchEpisodeDetails) -> Self {
-        Self::from_simple(SimpleListItem::from(episode))
+        Self::from(SimpleListItem::from(episode))
     }
 }
 
 impl TuItem {
-    pub fn from_simple(item: SimpleListItem) -> Self {
-        Self::from(item)
+    /// Item fetched with `client`, which all requests about it go to.
+    pub fn from_simple(item: SimpleListItem, client: &Arc<JellyfinClient>) -> Self {
+        let tu_item = Self::from(item);
+        tu_item.imp().client.replace(Some(client.to_owned()));
+        tu_item
     }
 
     pub fn update_user_data(&self, user_data: &Option<UserData>) {
@@ -407,6 +418,24 @@ impl TuItem {
             return;
         };
 
+        if !self.is_on_active_server() {
+            if !self.switch_to_item_server(&window) {
+                return;
+            }
+            if self.can_direct_play() {
+                spawn(glib::clone!(
+                    #[strong(rename_to = item)]
+                    self,
+                    #[weak]
+                    window,
+                    async move {
+                        item.play_video(&window).await;
+                    }
+                ));
+                return;
+            }
+        }
+
         match self.item_type().as_str() {
             SERIES | MOVIE | VIDEO | MUSIC_VIDEO | ADULT_VIDEO => {
                 let page = ItemPage::new(self);
@@ -429,8 +458,8 @@ impl TuItem {
                 let page = ListPage::new(self.to_owned());
                 push_page_with_tag(window, page, self.id(), &self.name());
             }
-            TAG | GENRE | MUSIC_GENRE => {
-                let page = SingleGrid::new();
+            TAG | GENRE | MUSIC_GENRE | STUDIO => {
+                let page = SingleGrid::new(self.client());
                 let id = self.id();
 
                 let mut parent_id = None;
@@ -444,33 +473,35 @@ impl TuItem {
                 let parent_id_clone = parent_id.to_owned();
 
                 let list_type = self.item_type();
-                page.connect_sort_changed_tokio(move |sort_by, sort_order, filters_list| {
-                    let id = id.to_owned();
-                    let parent_id = parent_id.to_owned();
-                    let list_type = list_type.to_owned();
-                    async move {
-                        JELLYFIN_CLIENT
-                            .get_inlist(
-                                parent_id,
-                                0,
-                                &list_type,
-                                &id,
-                                &sort_order,
-                                &sort_by,
-                                &filters_list,
-                            )
-                            .await
-                    }
-                });
+                page.connect_sort_changed_tokio(
+                    move |client, sort_by, sort_order, filters_list| {
+                        let id = id.to_owned();
+                        let parent_id = parent_id.to_owned();
+                        let list_type = list_type.to_owned();
+                        async move {
+                            client
+                                .get_inlist(
+                                    parent_id,
+                                    0,
+                                    &list_type,
+                                    &id,
+                                    &sort_order,
+                                    &sort_by,
+                                    &filters_list,
+                                )
+                                .await
+                        }
+                    },
+                );
                 let id = self.id();
                 let list_type = self.item_type();
                 page.connect_end_edge_overshot_tokio(
-                    move |sort_by, sort_order, n_items, filters_list| {
+                    move |client, sort_by, sort_order, n_items, filters_list| {
                         let id = id.to_owned();
                         let parent_id = parent_id_clone.to_owned();
                         let list_type = list_type.to_owned();
                         async move {
-                            JELLYFIN_CLIENT
+                            client
                                 .get_inlist(
                                     parent_id,
                                     n_items,
@@ -487,23 +518,25 @@ impl TuItem {
                 push_page_with_tag(window, page, self.id(), &self.name());
             }
             FOLDER => {
-                let page = SingleGrid::new();
+                let page = SingleGrid::new(self.client());
                 page.set_list_type(ListType::Folder);
                 let id = self.id();
-                page.connect_sort_changed_tokio(move |sort_by, sort_order, filters_list| {
-                    let id = id.to_owned();
-                    async move {
-                        JELLYFIN_CLIENT
-                            .get_folder_include(&id, &sort_by, &sort_order, 0, &filters_list)
-                            .await
-                    }
-                });
+                page.connect_sort_changed_tokio(
+                    move |client, sort_by, sort_order, filters_list| {
+                        let id = id.to_owned();
+                        async move {
+                            client
+                                .get_folder_include(&id, &sort_by, &sort_order, 0, &filters_list)
+                                .await
+                        }
+                    },
+                );
                 let id = self.id();
                 page.connect_end_edge_overshot_tokio(
-                    move |sort_by, sort_order, n_items, filters_list| {
+                    move |client, sort_by, sort_order, n_items, filters_list| {
                         let id = id.to_owned();
                         async move {
-                            JELLYFIN_CLIENT
+                            client
                                 .get_folder_include(
                                     &id,
                                     &sort_by,
@@ -563,51 +596,130 @@ impl TuItem {
     }
 
     pub async fn play_album(&self, obj: &impl IsA<gtk::Widget>) {
+        let Some(songs) = self.fetch_songs(obj).await else {
+            return;
+        };
+
+        let Some(first) = songs.first() else {
+            return;
+        };
+
+        let model = gio::ListStore::new::<CoreSong>();
+        model.extend_from_slice(&songs);
+        bing_song_model!(obj, model, first.to_owned());
+    }
+
+    /// Songs of an album or an artist, errors are shown as a toast on `obj`.
+    async fn fetch_songs(&self, obj: &impl IsA<gtk::Widget>) -> Option<Vec<CoreSong>> {
         let id = self.id();
+        let client = self.client();
+        let fetch_client = client.clone();
+        let is_artist = self.item_type() == MUSIC_ARTIST;
 
         let mut events = fetch_with_cache(
             &format!("audio_{}", id),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_songs(&id).await },
+            async move {
+                if is_artist {
+                    fetch_client.get_artist_songs(&id).await
+                } else {
+                    fetch_client.get_songs(&id).await
+                }
+            },
         )
         .await;
 
-        let Some(event) = events.recv().await else {
-            return;
-        };
-
-        let songs = match event {
+        let songs = match events.recv().await? {
             CacheEvent::Data { data, .. } => data,
             CacheEvent::Error(e) => {
                 obj.toast(e.to_user_facing());
-                return;
+                return None;
             }
         };
 
-        let song_widgets = songs
-            .items
-            .into_iter()
-            .map(|song| {
-                let item = TuItem::from_simple(song);
-                let song_widget = SongWidget::new(item, SongWidgetView::MusicAlbumItem);
-                song_widget.coresong()
-            })
-            .collect::<Vec<_>>();
-
-        let Some(first) = song_widgets.first() else {
-            return;
+        Some(
+            songs
+                .items
+                .into_iter()
+                .map(|song| {
+                    let item = TuItem::from_simple(song, &client);
+                    let song_widget = SongWidget::new(item, SongWidgetView::MusicAlbumItem);
+                    song_widget.coresong()
+                })
+                .collect(),
+        )
+    }
+
+    /// Queues this song, or the songs of this album or artist, in the music
+    /// player. Starts playing them when nothing plays.
+    pub async fn queue_songs(&self, obj: &impl IsA<gtk::Widget>, play_next: bool) {
+        let songs = if self.item_type() == AUDIO {
+            vec![SongWidget::new(self.to_owned(), SongWidgetView::MusicAlbumItem).coresong()]
+        } else {
+            let Some(songs) = self.fetch_songs(obj).await else {
+                return;
+            };
+            songs
         };
 
-        let model = gio::ListStore::new::<CoreSong>();
-        model.extend_from_slice(&song_widgets);
-        bing_song_model!(obj, model, first.to_owned());
+        if songs.is_empty() {
+            return;
+        }
+        let Some(window) = obj.root().and_downcast::<Window>() else {
+            return;
+        };
+        window.queue_songs(songs, play_next).await;
     }
 
     pub async fn play_video(&self, obj: &impl IsA<gtk::Widget>) {
-        self.direct_play_video_id(obj, self.to_owned(), Vec::new())
+        let Some(window) = obj.root().and_downcast::<Window>() else {
+            return;
+        };
+        if !self.switch_to_item_server(&window) {
+            return;
+        }
+        self.direct_play_video_id(&window, self.to_owned(), Vec::new())
             .await;
     }
 
+    /// Client of the account this item was fetched with. Items that don't
+    /// come from a server, like danmaku search results, get the active one.
+    pub fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| CLIENTS.active())
+    }
+
+    /// Key of the account this item was fetched with, `None` for items that
+    /// don't come from a server.
+    pub fn account_key(&self) -> Option<String> {
+        self.imp()
+            .client
+            .borrow()
+            .as_ref()
+            .map(|client| client.session().account_key())
+    }
+
+    fn is_on_active_server(&self) -> bool {
+        self.client().session().account_key() == CLIENTS.active().session().account_key()
+    }
+
+    /// Makes the account an item was fetched with the active one, returns
+    /// `false` if that account is not signed in anymore.
+    fn switch_to_item_server(&self, window: &Window) -> bool {
+        if self.is_on_active_server() {
+            return true;
+        }
+        let account = self.client().session().account.to_owned();
+        if CLIENTS.get_by_key(&account.key()).is_none() {
+            window.toast(gettext("Server not found"));
+            return false;
+        }
+        window.switch_account(&account)
+    }
+
     pub async fn direct_play_video_id(
         &self, obj: &impl IsA<gtk::Widget>, video: TuItem, episode_list: Vec<TuItem>,
     ) {
@@ -624,9 +736,11 @@ impl TuItem {
 
     pub async fn play_series(&self, obj: &impl IsA<gtk::Widget>) {
         let id = self.id();
+        let client = self.client();
+        let fetch_client = client.clone();
 
         let nextup_list =
-            match spawn_tokio(async move { JELLYFIN_CLIENT.get_shows_next_up(&id).await }).await {
+            match spawn_tokio(async move { fetch_client.get_shows_next_up(&id).await }).await {
                 Ok(list) => list,
                 Err(e) => {
                     obj.toast(e.to_user_facing());
@@ -641,11 +755,11 @@ impl TuItem {
 
         self.direct_play_video_id(
             obj,
-            TuItem::from_simple(nextup_item.to_owned()),
+            TuItem::from_simple(nextup_item.to_owned(), &client),
             nextup_list
                 .items
                 .into_iter()
-                .map(TuItem::from_simple)
+                .map(|item| TuItem::from_simple(item, &client))
                 .collect(),
         )
         .await;
@@ -1,4 +1,7 @@
-use std::cell::RefCell;
+use std::{
+    cell::RefCell,
+    sync::Arc,
+};
 
 use gtk::glib::{
     self,
@@ -8,7 +11,10 @@ use gtk::glib::{
 
 use super::tu_item::TuItem;
 use crate::{
-    client::structs::SimpleListItem,
+    client::{
+        jellyfin_client::JellyfinClient,
+        structs::SimpleListItem,
+    },
     ui::widgets::{
         lazy_diff_view::OnSameKey,
         tu_item::TuItemProgressbarAnimation,
@@ -65,8 +71,8 @@ impl TuObject {
         glib::Object::builder().property("item", item).build()
     }
 
-    pub fn from_simple(item: SimpleListItem) -> Self {
-        TuObject::new(TuItem::from_simple(item))
+    pub fn from_simple(item: SimpleListItem, client: &Arc<JellyfinClient>) -> Self {
+        TuObject::new(TuItem::from_simple(item, client))
     }
 
     pub fn activate<T>(&self, listview: &T)
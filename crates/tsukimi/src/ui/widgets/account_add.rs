@@ -15,7 +15,7 @@ use crate::{
         Account,
         account::ServerType,
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        registry::CLIENTS,
     },
     ui::models::SETTINGS,
     utils::spawn_tokio,
@@ -151,11 +151,13 @@ impl AccountWindow {
         let server = format!("{protocol}{server}");
         let server_type = ServerType::from_index(imp.server_type.selected());
         let account = match spawn_tokio(async move {
-            let login = JELLYFIN_CLIENT
+            let login = CLIENTS
+                .active()
                 .login(&server, &port, server_type, &username, &password)
                 .await?;
             let servername = if servername.is_empty() {
-                JELLYFIN_CLIENT
+                CLIENTS
+                    .active()
                     .get_server_info_public(&server, &port, server_type)
                     .await?
                     .server_name
@@ -2,11 +2,16 @@
 
 use super::utils::GlobalToast;
 use crate::{
-    client::jellyfin_client::JELLYFIN_CLIENT,
+    client::{
+        error::UserFacingError,
+        registry::CLIENTS,
+        structs::MediaSegmentType,
+    },
     ui::{
         models::{
             SETTINGS,
             jellyfin_cache_path,
+            settings_bundle::SettingsBundle,
         },
         provider::descriptor::{
             Descriptor,
@@ -69,7 +74,17 @@ mod imp {
         #[template_child]
         pub merge_resume_next_up_control: TemplateChild<adw::SwitchRow>,
         #[template_child]
-        pub auto_skip_intro_outro_control: TemplateChild<adw::SwitchRow>,
+        pub all_servers_resume_control: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub segment_policy_intro_comborow: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub segment_policy_outro_comborow: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub segment_policy_recap_comborow: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub segment_policy_preview_comborow: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub segment_policy_commercial_comborow: TemplateChild<adw::ComboRow>,
         #[template_child]
         pub selectlastcontrol: TemplateChild<adw::SwitchRow>,
         #[template_child]
@@ -100,24 +115,27 @@ mod imp {
         pub add_version_preferences_dialog: TemplateChild<adw::Dialog>,
 
         #[template_child]
-        pub descriptor_string_label: TemplateChild<gtk::Label>,
+        pub descriptor_example_label: TemplateChild<gtk::Label>,
         #[template_child]
-        pub descriptor_regex_label: TemplateChild<gtk::Label>,
-
-        #[template_child]
-        pub descriptor_string_label_edit: TemplateChild<gtk::Label>,
-        #[template_child]
-        pub descriptor_regex_label_edit: TemplateChild<gtk::Label>,
+        pub descriptor_example_label_edit: TemplateChild<gtk::Label>,
 
         #[template_child]
         pub descriptor_type_comborow: TemplateChild<adw::ComboRow>,
         #[template_child]
         pub descriptor_entryrow: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub descriptor_weight_spinrow: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub descriptor_exclude_switchrow: TemplateChild<adw::SwitchRow>,
 
         #[template_child]
         pub descriptor_type_comborow_edit: TemplateChild<adw::ComboRow>,
         #[template_child]
         pub descriptor_entryrow_edit: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub descriptor_weight_spinrow_edit: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub descriptor_exclude_switchrow_edit: TemplateChild<adw::SwitchRow>,
 
         #[template_child]
         pub descriptors_listbox: TemplateChild<gtk::ListBox>,
@@ -137,6 +155,9 @@ mod imp {
         #[template_child]
         pub folder_button_content: TemplateChild<adw::ButtonContent>,
 
+        #[template_child]
+        pub export_secrets_control: TemplateChild<adw::SwitchRow>,
+
         pub now_editing_descriptor: RefCell<Option<Descriptor>>,
 
         pub descriptor_grab_x: Cell<f64>,
@@ -240,7 +261,8 @@ impl AccountSettings {
             self.toast(gettext("Passwords do not match!"));
             return;
         }
-        match spawn_tokio(async move { JELLYFIN_CLIENT.change_password(&new_password).await }).await
+        match spawn_tokio(async move { CLIENTS.active().change_password(&new_password).await })
+            .await
         {
             Ok(_) => {
                 self.toast(gettext(
@@ -253,6 +275,131 @@ impl AccountSettings {
         };
     }
 
+    #[template_callback]
+    async fn on_export_settings(&self) {
+        let include_secrets = self.imp().export_secrets_control.is_active();
+        let json = match SettingsBundle::export(include_secrets).and_then(|b| b.to_json()) {
+            Ok(json) => json,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        let filedialog = gtk::FileDialog::builder()
+            .modal(true)
+            .title(gettext("Export Settings"))
+            .initial_name("tsukimi-settings.json")
+            .build();
+        let Ok(file) = filedialog.save_future(Some(self)).await else {
+            return;
+        };
+
+        match file
+            .replace_contents_future(
+                json.into_bytes(),
+                None,
+                false,
+                gio::FileCreateFlags::REPLACE_DESTINATION,
+            )
+            .await
+        {
+            Ok(_) => self.toast(gettext("Settings exported")),
+            Err((_, e)) => self.toast(e.to_string()),
+        }
+    }
+
+    #[template_callback]
+    async fn on_import_settings(&self) {
+        let json_filter = gtk::FileFilter::new();
+        json_filter.set_name(Some("JSON"));
+        json_filter.add_suffix("json");
+        let model = gio::ListStore::new::<gtk::FileFilter>();
+        model.append(&json_filter);
+        let filedialog = gtk::FileDialog::builder()
+            .modal(true)
+            .title(gettext("Import Settings"))
+            .filters(&model)
+            .build();
+        let Ok(file) = filedialog.open_future(Some(self)).await else {
+            return;
+        };
+
+        let bundle = match file.load_contents_future().await {
+            Ok((bytes, _)) => SettingsBundle::parse(&String::from_utf8_lossy(&bytes)),
+            Err(e) => Err(e.into()),
+        };
+        let (bundle, changes) = match bundle.and_then(|b| b.changes().map(|c| (b, c))) {
+            Ok(result) => result,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        if changes.is_empty() {
+            self.toast(gettext("Settings are already up to date"));
+            return;
+        }
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        for change in &changes {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&change.title))
+                .subtitle(glib::markup_escape_text(&change.detail))
+                .subtitle_lines(2)
+                .build();
+            list.append(&row);
+        }
+        let scrolled = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .propagate_natural_height(true)
+            .max_content_height(360)
+            .child(&list)
+            .build();
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Import Settings?"))
+            .body(
+                gettext("{count} settings will change")
+                    .replace("{count}", &changes.len().to_string()),
+            )
+            .extra_child(&scrolled)
+            .build();
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("import", &gettext("Import")),
+        ]);
+        dialog.set_response_appearance("import", adw::ResponseAppearance::Suggested);
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("import"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _| {
+                    if let Err(e) = bundle.apply() {
+                        obj.toast(e.to_user_facing());
+                        return;
+                    }
+                    obj.refersh_descriptors();
+                    obj.toast(gettext("Settings imported"));
+                    let window = obj.window();
+                    spawn(async move {
+                        window.set_servers().await;
+                        window.set_nav_servers();
+                    });
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
     pub fn set_sidebar(&self) {
         let imp = self.imp();
         imp.sidebarcontrol.set_active(SETTINGS.overlay());
@@ -442,13 +589,42 @@ impl AccountSettings {
             .build();
         SETTINGS
             .bind(
-                "auto-skip-intro-outro",
-                &imp.auto_skip_intro_outro_control.get(),
+                "all-servers-resume",
+                &imp.all_servers_resume_control.get(),
                 "active",
             )
             .build();
+        for (key, comborow) in [
+            (
+                "segment-policy-intro",
+                imp.segment_policy_intro_comborow.get(),
+            ),
+            (
+                "segment-policy-outro",
+                imp.segment_policy_outro_comborow.get(),
+            ),
+            (
+                "segment-policy-recap",
+                imp.segment_policy_recap_comborow.get(),
+            ),
+            (
+                "segment-policy-preview",
+                imp.segment_policy_preview_comborow.get(),
+            ),
+            (
+                "segment-policy-commercial",
+                imp.segment_policy_commercial_comborow.get(),
+            ),
+        ] {
+            SETTINGS.bind(key, &comborow, "selected").build();
+        }
+        // Carry the deprecated auto-skip switch over into the new policies
+        imp.segment_policy_intro_comborow
+            .set_selected(SETTINGS.segment_skip_policy(MediaSegmentType::Intro) as u32);
+        imp.segment_policy_outro_comborow
+            .set_selected(SETTINGS.segment_skip_policy(MediaSegmentType::Outro) as u32);
 
-        if JELLYFIN_CLIENT.session().account.user_id.is_empty() {
+        if CLIENTS.active().session().account.user_id.is_empty() {
             return;
         }
 
@@ -457,7 +633,7 @@ impl AccountSettings {
             self,
             async move {
                 let source =
-                    spawn_tokio(async move { JELLYFIN_CLIENT.get_user_avatar_source().await })
+                    spawn_tokio(async move { CLIENTS.active().get_user_avatar_source().await })
                         .await;
                 let source = match source {
                     Ok(source) => source,
@@ -489,6 +665,8 @@ impl AccountSettings {
 
         // Reset the dialog
         imp.descriptor_entryrow.set_text("");
+        imp.descriptor_weight_spinrow.set_value(0.0);
+        imp.descriptor_exclude_switchrow.set_active(false);
 
         dialog.present(Some(self));
     }
@@ -520,62 +698,53 @@ impl AccountSettings {
 
     #[template_callback]
     fn on_descriptor_type_changed_comborow(&self, _param: glib::ParamSpec, combo: adw::ComboRow) {
-        match combo.selected() {
-            0 => {
-                self.imp().descriptor_string_label.set_visible(true);
-                self.imp().descriptor_regex_label.set_visible(false);
-            }
-            1 => {
-                self.imp().descriptor_string_label.set_visible(false);
-                self.imp().descriptor_regex_label.set_visible(true);
-            }
-            _ => unreachable!(),
-        }
+        self.imp()
+            .descriptor_example_label
+            .set_label(&DescriptorType::from_u32(combo.selected()).example());
     }
 
     #[template_callback]
     fn on_descriptor_type_changed_comborow_edit(
         &self, _param: glib::ParamSpec, combo: adw::ComboRow,
     ) {
-        match combo.selected() {
-            0 => {
-                self.imp().descriptor_string_label_edit.set_visible(true);
-                self.imp().descriptor_regex_label_edit.set_visible(false);
-            }
-            1 => {
-                self.imp().descriptor_string_label_edit.set_visible(false);
-                self.imp().descriptor_regex_label_edit.set_visible(true);
-            }
-            _ => unreachable!(),
+        self.imp()
+            .descriptor_example_label_edit
+            .set_label(&DescriptorType::from_u32(combo.selected()).example());
+    }
+
+    fn descriptor_from_rows(
+        &self, type_row: &adw::ComboRow, entry_row: &adw::EntryRow, weight_row: &adw::SpinRow,
+        exclude_row: &adw::SwitchRow,
+    ) -> Option<Descriptor> {
+        let descriptor_content = entry_row.text();
+        if descriptor_content.is_empty() {
+            self.toast(gettext("Descriptor cannot be empty!"));
+            return None;
+        }
+
+        let type_ = DescriptorType::from_u32(type_row.selected());
+        if type_ == DescriptorType::Regex
+            && let Err(e) = regex::Regex::new(&descriptor_content)
+        {
+            self.toast(format!("{}: {}", gettext("Invalid regex"), e));
         }
+
+        let mut descriptor = Descriptor::new(descriptor_content.to_string(), type_);
+        let weight = weight_row.value() as u32;
+        descriptor.weight = (weight > 0).then_some(weight);
+        descriptor.exclude = exclude_row.is_active();
+        Some(descriptor)
     }
 
     pub fn add_preferred_version(&self) {
         let imp = self.imp();
-        let descriptor = match imp.descriptor_type_comborow.selected() {
-            0 => {
-                let descriptor_content = imp.descriptor_entryrow.text();
-                if descriptor_content.is_empty() {
-                    self.toast(gettext("Descriptor cannot be empty!"));
-                    return;
-                }
-
-                Descriptor::new(descriptor_content.to_string(), DescriptorType::String)
-            }
-            1 => {
-                let descriptor_content = imp.descriptor_entryrow.text();
-                if descriptor_content.is_empty() {
-                    self.toast(gettext("Descriptor cannot be empty!"));
-                    return;
-                }
-                match regex::Regex::new(&descriptor_content) {
-                    Ok(_) => {}
-                    Err(e) => self.toast(format!("{}: {}", gettext("Invalid regex"), e)),
-                }
-
-                Descriptor::new(descriptor_content.to_string(), DescriptorType::Regex)
-            }
-            _ => unreachable!(),
+        let Some(descriptor) = self.descriptor_from_rows(
+            &imp.descriptor_type_comborow,
+            &imp.descriptor_entryrow,
+            &imp.descriptor_weight_spinrow,
+            &imp.descriptor_exclude_switchrow,
+        ) else {
+            return;
         };
 
         SETTINGS
@@ -595,30 +764,13 @@ impl AccountSettings {
             .to_owned()
             .expect("No descriptor to edit");
 
-        let descriptor = match imp.descriptor_type_comborow_edit.selected() {
-            0 => {
-                let descriptor_content = imp.descriptor_entryrow_edit.text();
-                if descriptor_content.is_empty() {
-                    self.toast(gettext("Descriptor cannot be empty!"));
-                    return;
-                }
-
-                Descriptor::new(descriptor_content.to_string(), DescriptorType::String)
-            }
-            1 => {
-                let descriptor_content = imp.descriptor_entryrow_edit.text();
-                if descriptor_content.is_empty() {
-                    self.toast(gettext("Descriptor cannot be empty!"));
-                    return;
-                }
-                match regex::Regex::new(&descriptor_content) {
-                    Ok(_) => {}
-                    Err(e) => self.toast(format!("{}: {}", gettext("Invalid regex"), e)),
-                }
-
-                Descriptor::new(descriptor_content.to_string(), DescriptorType::Regex)
-            }
-            _ => unreachable!(),
+        let Some(descriptor) = self.descriptor_from_rows(
+            &imp.descriptor_type_comborow_edit,
+            &imp.descriptor_entryrow_edit,
+            &imp.descriptor_weight_spinrow_edit,
+            &imp.descriptor_exclude_switchrow_edit,
+        ) else {
+            return;
         };
 
         SETTINGS
@@ -646,8 +798,19 @@ impl AccountSettings {
         group.remove_all();
 
         for (index, descriptor) in descriptors.iter().enumerate() {
+            let subtitle = if descriptor.exclude {
+                format!("{} · {}", descriptor.type_.label(), gettext("Excluded"))
+            } else if let Some(weight) = descriptor.weight {
+                format!(
+                    "{} · {}",
+                    descriptor.type_.label(),
+                    gettext("Weight {weight}").replace("{weight}", &weight.to_string())
+                )
+            } else {
+                descriptor.type_.label()
+            };
             let row = adw::ActionRow::builder()
-                .subtitle(descriptor.type_.to_string())
+                .subtitle(subtitle)
                 .title(&descriptor.content)
                 .activatable(true)
                 .build();
@@ -668,14 +831,12 @@ impl AccountSettings {
                     let imp = obj.imp();
 
                     imp.descriptor_entryrow_edit.set_text(&descriptor.content);
-                    match descriptor.type_ {
-                        DescriptorType::String => {
-                            imp.descriptor_type_comborow_edit.set_selected(0);
-                        }
-                        DescriptorType::Regex => {
-                            imp.descriptor_type_comborow_edit.set_selected(1);
-                        }
-                    }
+                    imp.descriptor_type_comborow_edit
+                        .set_selected(descriptor.type_.to_u32());
+                    imp.descriptor_weight_spinrow_edit
+                        .set_value(descriptor.weight.unwrap_or_default() as f64);
+                    imp.descriptor_exclude_switchrow_edit
+                        .set_active(descriptor.exclude);
 
                     imp.now_editing_descriptor
                         .replace(Some(descriptor.to_owned()));
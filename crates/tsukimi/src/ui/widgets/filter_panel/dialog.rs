@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use super::{
     FiltersList,
     FiltersRow,
@@ -11,8 +13,12 @@ use gtk::{
     template_callbacks,
 };
 
+use crate::client::jellyfin_client::JellyfinClient;
+
 mod imp {
 
+    use std::cell::OnceCell;
+
     use glib::subclass::InitializingObject;
     use gtk::{
         CompositeTemplate,
@@ -21,7 +27,7 @@ mod imp {
 
     use super::*;
 
-    #[derive(Debug, Default, CompositeTemplate)]
+    #[derive(Default, CompositeTemplate)]
     #[template(resource = "/moe/tsuna/tsukimi/ui/filter.ui")]
     pub struct FilterPanelDialog {
         #[template_child]
@@ -55,6 +61,9 @@ mod imp {
         pub video_types_row: TemplateChild<FiltersRow>,
         #[template_child]
         pub resolution_combo: TemplateChild<adw::ComboRow>,
+
+        /// Account the filters are listed from
+        pub client: OnceCell<Arc<JellyfinClient>>,
     }
 
     #[glib::object_subclass]
@@ -85,19 +94,23 @@ glib::wrapper! {
         @extends gtk::Widget, adw::Dialog, adw::PreferencesDialog, @implements gtk::Accessible, gtk::Root, gtk::Buildable, gtk::ConstraintTarget;
 }
 
-impl Default for FilterPanelDialog {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[template_callbacks]
 impl FilterPanelDialog {
     const LOADING_STACK_PAGE: &'static str = "loading";
     const VIEW_STACK_PAGE: &'static str = "view";
 
-    pub fn new() -> Self {
-        glib::Object::new()
+    pub fn new(client: Arc<JellyfinClient>) -> Self {
+        let dialog: Self = glib::Object::new();
+        let _ = dialog.imp().client.set(client);
+        dialog
+    }
+
+    pub fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("FilterPanelDialog is created with a client")
+            .clone()
     }
 
     pub fn loading_page(&self) {
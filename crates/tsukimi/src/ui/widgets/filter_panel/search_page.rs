@@ -12,7 +12,6 @@ use gtk::template_callbacks;
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
         structs::FilterItem,
     },
     ui::GlobalToast,
@@ -148,10 +147,9 @@ impl FilterDialogSearchPage {
 
                 dialog.loading_page();
 
+                let client = dialog.client();
                 let filters =
-                    match spawn_tokio(async move { JELLYFIN_CLIENT.filters(&filter_type).await })
-                        .await
-                    {
+                    match spawn_tokio(async move { client.filters(&filter_type).await }).await {
                         Ok(filters) => filters,
                         Err(e) => {
                             obj.toast(e.to_user_facing());
@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::Arc,
+};
 
 use gettextrs::gettext;
 use glib::Object;
@@ -26,13 +29,21 @@ use super::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
+        registry::CLIENTS,
         structs::*,
     },
     fraction,
     fraction_reset,
     ui::{
         SETTINGS,
+        models::home_layout::{
+            HomeLayouts,
+            HomeSectionKind,
+            PinnedRow,
+            PinnedSource,
+            ServerHomeLayout,
+        },
         provider::tu_item::TuItem,
     },
     utils::{
@@ -42,6 +53,7 @@ use crate::{
         fetch_with_cache,
         spawn,
         spawn_g_timeout,
+        spawn_tokio,
     },
 };
 
@@ -50,9 +62,11 @@ mod imp {
     use std::{
         cell::{
             Cell,
+            OnceCell,
             RefCell,
         },
         collections::HashMap,
+        sync::Arc,
     };
 
     use glib::subclass::InitializingObject;
@@ -65,7 +79,13 @@ mod imp {
         subclass::prelude::*,
     };
 
-    use crate::ui::widgets::hortu_scrolled::HortuScrolled;
+    use crate::{
+        client::{
+            jellyfin_client::JellyfinClient,
+            registry::CLIENTS,
+        },
+        ui::widgets::hortu_scrolled::HortuScrolled,
+    };
 
     // Object holding the state
     #[derive(CompositeTemplate, Default)]
@@ -74,18 +94,25 @@ mod imp {
         #[template_child]
         pub root: TemplateChild<gtk::ScrolledWindow>,
         #[template_child]
+        pub sections_box: TemplateChild<gtk::Box>,
+        #[template_child]
         pub libsbox: TemplateChild<gtk::Box>,
         #[template_child]
         pub hishortu: TemplateChild<HortuScrolled>,
         #[template_child]
         pub nextuphortu: TemplateChild<HortuScrolled>,
         #[template_child]
+        pub allservershortu: TemplateChild<HortuScrolled>,
+        #[template_child]
         pub libhortu: TemplateChild<HortuScrolled>,
         pub selection: gtk::SingleSelection,
 
         pub libs_hortu: RefCell<HashMap<String, WeakRef<HortuScrolled>>>,
+        pub pinned_hortu: RefCell<HashMap<String, HortuScrolled>>,
         pub next_up_date_cutoff: RefCell<String>,
         pub last_merge_resume_and_next_up: Cell<Option<bool>>,
+        /// Account the page shows, the page is rebuilt when switching accounts
+        pub client: OnceCell<Arc<JellyfinClient>>,
     }
 
     #[glib::object_subclass]
@@ -107,6 +134,7 @@ mod imp {
     impl ObjectImpl for HomePage {
         fn constructed(&self) {
             self.parent_constructed();
+            let _ = self.client.set(CLIENTS.active());
             let obj = self.obj();
             obj.setup_next_up_morebutton();
             obj.init_load();
@@ -141,6 +169,14 @@ impl HomePage {
         Object::builder().build()
     }
 
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("HomePage is constructed")
+            .clone()
+    }
+
     pub fn init_load(&self) {
         spawn(glib::clone!(
             #[weak(rename_to = obj)]
@@ -169,24 +205,202 @@ impl HomePage {
             .last_merge_resume_and_next_up
             .replace(Some(merge_resume_and_next_up))
             .is_some_and(|previous| previous != merge_resume_and_next_up);
+        let layout = HomeLayouts::of(&self.client());
+        self.apply_layout(&layout);
+        // The all servers row takes the place of both resume and next up
+        let all_servers = SETTINGS.all_servers_resume() && SETTINGS.accounts().len() > 1;
+        let resume_visible = layout.is_visible(&HomeSectionKind::Resume);
         futures_util::join!(
-            self.setup_history(enable_cache, merge_resume_and_next_up_changed),
-            self.setup_next_up(enable_cache, merge_resume_and_next_up_changed),
-            self.setup_library(enable_cache)
+            self.setup_history(
+                enable_cache,
+                merge_resume_and_next_up_changed,
+                resume_visible && !all_servers
+            ),
+            self.setup_next_up(
+                enable_cache,
+                merge_resume_and_next_up_changed,
+                layout.is_visible(&HomeSectionKind::NextUp) && !all_servers
+            ),
+            self.setup_all_servers(resume_visible && all_servers),
+            self.setup_library(enable_cache, &layout),
+            self.setup_pinned(enable_cache, &layout)
         );
         fraction!(self);
     }
 
-    pub async fn setup_history(&self, enable_cache: bool, force_cache_emit: bool) {
+    /// Orders the sections of the home page and creates or drops pinned rows
+    /// to match `layout`.
+    fn apply_layout(&self, layout: &ServerHomeLayout) {
+        let imp = self.imp();
+
+        imp.pinned_hortu.borrow_mut().retain(|id, hortu| {
+            let keep = layout.sections.iter().any(
+                |section| matches!(&section.kind, HomeSectionKind::Pinned(row) if &row.id == id),
+            );
+            if !keep {
+                imp.sections_box.remove(&*hortu);
+            }
+            keep
+        });
+
+        let mut previous: Option<gtk::Widget> = None;
+        for section in &layout.sections {
+            let widget: gtk::Widget = match &section.kind {
+                HomeSectionKind::Libraries => {
+                    imp.libhortu.set_visible(section.visible);
+                    imp.libhortu.get().upcast()
+                }
+                HomeSectionKind::Resume => {
+                    imp.sections_box
+                        .reorder_child_after(&imp.hishortu.get(), previous.as_ref());
+                    previous = Some(imp.hishortu.get().upcast());
+                    imp.allservershortu.get().upcast()
+                }
+                HomeSectionKind::NextUp => imp.nextuphortu.get().upcast(),
+                HomeSectionKind::Latest => {
+                    imp.libsbox.set_visible(section.visible);
+                    imp.libsbox.get().upcast()
+                }
+                HomeSectionKind::Pinned(row) => {
+                    let hortu = self.pinned_hortu(row);
+                    hortu.set_visible(section.visible);
+                    hortu.upcast()
+                }
+            };
+            imp.sections_box
+                .reorder_child_after(&widget, previous.as_ref());
+            previous = Some(widget);
+        }
+    }
+
+    fn pinned_hortu(&self, row: &PinnedRow) -> HortuScrolled {
+        if let Some(hortu) = self.imp().pinned_hortu.borrow().get(&row.id) {
+            hortu.set_title(row.title.as_str());
+            return hortu.to_owned();
+        }
+
+        let hortu = HortuScrolled::new();
+        hortu.set_title(row.title.as_str());
+
+        let item_type = match &row.source {
+            PinnedSource::Collection { id } => Some((id, "BoxSet")),
+            PinnedSource::Playlist { id } => Some((id, "Playlist")),
+            PinnedSource::Genre { id } => Some((id, "Genre")),
+            PinnedSource::Filter { .. } => None,
+        };
+        if let Some((id, item_type)) = item_type {
+            hortu.set_moreview(true);
+            let id = id.to_owned();
+            let name = row.title.to_owned();
+            hortu.connect_morebutton(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    let item = SimpleListItem {
+                        id: id.to_owned(),
+                        name: name.to_owned(),
+                        item_type: item_type.to_owned(),
+                        ..Default::default()
+                    };
+                    TuItem::from_simple(item, &obj.client()).activate(&obj);
+                }
+            ));
+        }
+
+        self.imp()
+            .pinned_hortu
+            .borrow_mut()
+            .insert(row.id.to_owned(), hortu.to_owned());
+        self.imp().sections_box.append(&hortu);
+        hortu
+    }
+
+    async fn setup_pinned(&self, enable_cache: bool, layout: &ServerHomeLayout) {
+        let rows = layout
+            .sections
+            .iter()
+            .filter(|section| section.visible)
+            .filter_map(|section| match &section.kind {
+                HomeSectionKind::Pinned(row) => Some(self.setup_pinned_row(row, enable_cache)),
+                _ => None,
+            });
+        futures_util::future::join_all(rows).await;
+    }
+
+    async fn setup_pinned_row(&self, row: &PinnedRow, enable_cache: bool) {
+        let Some(hortu) = self.imp().pinned_hortu.borrow().get(&row.id).cloned() else {
+            return;
+        };
+
+        let params = row.source.params();
+        let client = self.client();
+        let fetch_client = client.clone();
+        let mut events = fetch_with_cache(
+            &format!("pinned_{}", row.id),
+            if enable_cache {
+                CachePolicy::ReadCacheAndRefresh
+            } else {
+                CachePolicy::RefreshIfChanged
+            },
+            async move { fetch_client.get_pinned_items(&params).await },
+        )
+        .await;
+
+        while let Some(event) = events.recv().await {
+            match event {
+                CacheEvent::Data { data, .. } => {
+                    hortu.set_items(data, &client);
+                }
+                CacheEvent::Error(e) => {
+                    self.toast(e.to_user_facing());
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn setup_all_servers(&self, visible: bool) {
+        let hortu = self.imp().allservershortu.get();
+        hortu.set_visible(visible);
+        if !visible {
+            return;
+        }
+
+        let clients = SETTINGS
+            .accounts()
+            .iter()
+            .filter_map(|account| CLIENTS.get(account).ok())
+            .collect();
+        let (items, failed) = spawn_tokio(async move {
+            JellyfinClient::get_all_servers_continue_watching(clients, 16).await
+        })
+        .await;
+
+        if !failed.is_empty() {
+            self.toast(
+                gettext("Could not reach {servers}").replace("{servers}", &failed.join(", ")),
+            );
+        }
+        hortu.set_items_of_servers(items);
+    }
+
+    pub async fn setup_history(&self, enable_cache: bool, force_cache_emit: bool, visible: bool) {
         let hortu = self.imp().hishortu.get();
 
-        if SETTINGS.merge_resume_and_next_up() && JELLYFIN_CLIENT.is_jellyfin() {
+        if !visible {
+            hortu.set_visible(false);
+            return;
+        }
+
+        let client = self.client();
+        if SETTINGS.merge_resume_and_next_up() && client.is_jellyfin() {
             // if merged, next up will contain resume items, so hide history
             hortu.set_visible(false);
             return;
         }
         hortu.set_visible(true);
 
+        let fetch_client = client.clone();
         let mut events = fetch_with_cache(
             "history",
             if enable_cache {
@@ -196,14 +410,14 @@ impl HomePage {
             } else {
                 CachePolicy::RefreshIfChanged
             },
-            async { JELLYFIN_CLIENT.get_resume(12).await },
+            async move { fetch_client.get_resume(12).await },
         )
         .await;
 
         while let Some(event) = events.recv().await {
             match event {
                 CacheEvent::Data { data, .. } => {
-                    hortu.set_items(data.items);
+                    hortu.set_items(data.items, &client);
                 }
                 CacheEvent::Error(e) => {
                     self.toast(e.to_user_facing());
@@ -213,10 +427,11 @@ impl HomePage {
         }
     }
 
-    pub async fn setup_next_up(&self, enable_cache: bool, force_cache_emit: bool) {
+    pub async fn setup_next_up(&self, enable_cache: bool, force_cache_emit: bool, visible: bool) {
         let hortu = self.imp().nextuphortu.get();
+        let client = self.client();
 
-        if !JELLYFIN_CLIENT.is_jellyfin() {
+        if !visible || !client.is_jellyfin() {
             hortu.set_visible(false);
             return;
         }
@@ -230,7 +445,7 @@ impl HomePage {
 
         hortu.set_visible(true);
 
-        let next_up_date_cutoff = JELLYFIN_CLIENT.next_up_date_cutoff();
+        let next_up_date_cutoff = client.next_up_date_cutoff();
         self.imp()
             .next_up_date_cutoff
             .replace(next_up_date_cutoff.clone());
@@ -243,16 +458,17 @@ impl HomePage {
             CachePolicy::RefreshIfChanged
         };
 
+        let fetch_client = client.clone();
         let mut events = if SETTINGS.merge_resume_and_next_up() {
             fetch_with_cache("next_up_merged", cache_policy, async move {
-                JELLYFIN_CLIENT
+                fetch_client
                     .get_next_up_merged(12, &next_up_date_cutoff)
                     .await
             })
             .await
         } else {
             fetch_with_cache("next_up", cache_policy, async move {
-                JELLYFIN_CLIENT.get_next_up(12, &next_up_date_cutoff).await
+                fetch_client.get_next_up(12, &next_up_date_cutoff).await
             })
             .await
         };
@@ -260,7 +476,7 @@ impl HomePage {
         while let Some(event) = events.recv().await {
             match event {
                 CacheEvent::Data { data, .. } => {
-                    hortu.set_items(data.items);
+                    hortu.set_items(data.items, &client);
                 }
                 CacheEvent::Error(e) => {
                     self.toast(e.to_user_facing());
@@ -282,7 +498,7 @@ impl HomePage {
                 };
                 let next_up_date_cutoff = obj.imp().next_up_date_cutoff.borrow().clone();
 
-                let page = SingleGrid::new();
+                let page = SingleGrid::new(obj.client());
 
                 page.set_list_type(ListType::NextUp);
                 page.set_card_options(CardOptions {
@@ -293,23 +509,19 @@ impl HomePage {
 
                 let title = if SETTINGS.merge_resume_and_next_up() {
                     page.set_is_resume(true);
-                    page.connect_sort_changed_tokio(move |_, _, _| {
+                    page.connect_sort_changed_tokio(move |client, _, _, _| {
                         let next_up_date_cutoff_initial = next_up_date_cutoff.clone();
                         async move {
-                            JELLYFIN_CLIENT
+                            client
                                 .get_next_up_merged(100, &next_up_date_cutoff_initial)
                                 .await
                         }
                     });
                     gettext("Continue Watching")
                 } else {
-                    page.connect_sort_changed_tokio(move |_, _, _| {
+                    page.connect_sort_changed_tokio(move |client, _, _, _| {
                         let next_up_date_cutoff_initial = next_up_date_cutoff.clone();
-                        async move {
-                            JELLYFIN_CLIENT
-                                .get_next_up(100, &next_up_date_cutoff_initial)
-                                .await
-                        }
+                        async move { client.get_next_up(100, &next_up_date_cutoff_initial).await }
                     });
                     gettext("Next Up")
                 };
@@ -319,9 +531,11 @@ impl HomePage {
         ));
     }
 
-    pub async fn setup_library(&self, enable_cache: bool) {
+    pub async fn setup_library(&self, enable_cache: bool, layout: &ServerHomeLayout) {
         let hortu = self.imp().libhortu.get();
+        let client = self.client();
 
+        let fetch_client = client.clone();
         let mut events = fetch_with_cache(
             "library",
             if enable_cache {
@@ -329,7 +543,7 @@ impl HomePage {
             } else {
                 CachePolicy::RefreshAndEmitLatest
             },
-            async { JELLYFIN_CLIENT.get_library().await },
+            async move { fetch_client.get_library().await },
         )
         .await;
 
@@ -337,9 +551,17 @@ impl HomePage {
             match event {
                 CacheEvent::Data { data, source } => {
                     if enable_cache || matches!(source, CacheSource::Network) {
-                        hortu.set_items(data.items.clone());
+                        hortu.set_items(data.items.clone(), &client);
                     }
-                    self.setup_libsview(data.items, enable_cache);
+                    let views = if layout.is_visible(&HomeSectionKind::Latest) {
+                        data.items
+                            .into_iter()
+                            .filter(|view| !layout.is_library_hidden(&view.id))
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+                    self.setup_libsview(views, enable_cache);
                 }
                 CacheEvent::Error(e) => {
                     self.toast(e.to_user_facing());
@@ -371,7 +593,21 @@ impl HomePage {
             }
         }
 
+        // Keep the rows in server order, also when a hidden library is shown again
+        let mut previous: Option<HortuScrolled> = None;
         for view in items {
+            let hortu = self
+                .imp()
+                .libs_hortu
+                .borrow()
+                .get(&view.id)
+                .and_then(|w| w.upgrade());
+            let hortu = hortu.unwrap_or_else(|| self.setup_hortu(view.to_owned()));
+            self.imp()
+                .libsbox
+                .reorder_child_after(&hortu, previous.as_ref());
+            previous = Some(hortu);
+
             spawn(glib::clone!(
                 #[weak(rename_to = obj)]
                 self,
@@ -400,11 +636,7 @@ impl HomePage {
             #[weak(rename_to = obj)]
             self,
             move |_| {
-                let list_item = TuItem::default();
-                list_item.set_id(ac_view.id.to_owned());
-                list_item.set_name(ac_view.name.to_owned());
-                list_item.set_item_type(ac_view.item_type.to_owned());
-                list_item.set_collection_type(ac_view.collection_type.to_owned());
+                let list_item = TuItem::from_simple(ac_view.to_owned(), &obj.client());
                 list_item.activate(&obj);
             }
         ));
@@ -432,6 +664,8 @@ impl HomePage {
 
         let hortu = hortu.unwrap_or_else(|| self.setup_hortu(view));
 
+        let client = self.client();
+        let fetch_client = client.clone();
         let mut events = fetch_with_cache(
             &format!("library_{}", view_id),
             if enable_cache {
@@ -441,9 +675,9 @@ impl HomePage {
             },
             async move {
                 if collection_type.as_deref() == Some("livetv") {
-                    JELLYFIN_CLIENT.get_channels().await.map(|x| x.items)
+                    fetch_client.get_channels().await.map(|x| x.items)
                 } else {
-                    JELLYFIN_CLIENT.get_latest(&view_id).await
+                    fetch_client.get_latest(&view_id).await
                 }
             },
         )
@@ -452,7 +686,7 @@ impl HomePage {
         while let Some(event) = events.recv().await {
             match event {
                 CacheEvent::Data { data, .. } => {
-                    hortu.set_items(data);
+                    hortu.set_items(data, &client);
                 }
                 CacheEvent::Error(e) => {
                     self.toast(e.to_user_facing());
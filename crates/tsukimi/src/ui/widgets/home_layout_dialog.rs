@@ -0,0 +1,457 @@
+use std::{
+    cell::{
+        OnceCell,
+        RefCell,
+    },
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use super::{
+    filter_panel::FilterPanelDialog,
+    utils::GlobalToast,
+};
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        registry::CLIENTS,
+        structs::SimpleListItem,
+    },
+    ui::models::home_layout::{
+        HomeLayouts,
+        HomeSectionKind,
+        PinnedSource,
+        ServerHomeLayout,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/home_layout_dialog.ui")]
+    pub struct HomeLayoutDialog {
+        #[template_child]
+        pub sections_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub latest_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub layout: RefCell<ServerHomeLayout>,
+        pub library_rows: RefCell<Vec<adw::SwitchRow>>,
+        /// Account whose home is laid out
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HomeLayoutDialog {
+        const NAME: &'static str = "HomeLayoutDialog";
+        type Type = super::HomeLayoutDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HomeLayoutDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let _ = self.client.set(CLIENTS.active());
+            let obj = self.obj();
+            self.layout.replace(HomeLayouts::of(&obj.client()));
+            obj.update_sections();
+            spawn(glib::clone!(
+                #[weak]
+                obj,
+                async move {
+                    obj.load_libraries().await;
+                }
+            ));
+        }
+    }
+
+    impl WidgetImpl for HomeLayoutDialog {}
+    impl AdwDialogImpl for HomeLayoutDialog {}
+}
+
+glib::wrapper! {
+    pub struct HomeLayoutDialog(ObjectSubclass<imp::HomeLayoutDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for HomeLayoutDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[template_callbacks]
+impl HomeLayoutDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("HomeLayoutDialog is constructed")
+            .clone()
+    }
+
+    fn edit_layout<F>(&self, f: F)
+    where
+        F: FnOnce(&mut ServerHomeLayout),
+    {
+        let result = {
+            let mut layout = self.imp().layout.borrow_mut();
+            f(&mut layout);
+            HomeLayouts::save_for(&self.client(), &layout)
+        };
+        if let Err(e) = result {
+            self.toast(e.to_string());
+        }
+        self.update_sections();
+    }
+
+    fn update_sections(&self) {
+        let imp = self.imp();
+        imp.sections_list.remove_all();
+
+        let layout = imp.layout.borrow();
+        let len = layout.sections.len();
+        for (index, section) in layout.sections.iter().enumerate() {
+            let row = adw::ActionRow::builder()
+                .title(section.kind.title())
+                .use_markup(false)
+                .build();
+
+            if let HomeSectionKind::Pinned(pinned) = &section.kind {
+                row.set_subtitle(&match pinned.source {
+                    PinnedSource::Collection { .. } => gettext("Collection"),
+                    PinnedSource::Playlist { .. } => gettext("Playlist"),
+                    PinnedSource::Genre { .. } => gettext("Genre"),
+                    PinnedSource::Filter { .. } => gettext("Saved Filter"),
+                });
+            }
+
+            let up_button = self.section_button("go-up-symbolic", &gettext("Move Up"));
+            up_button.set_sensitive(index > 0);
+            up_button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| obj.edit_layout(|layout| layout.move_section(index, true))
+            ));
+            row.add_suffix(&up_button);
+
+            let down_button = self.section_button("go-down-symbolic", &gettext("Move Down"));
+            down_button.set_sensitive(index + 1 < len);
+            down_button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| obj.edit_layout(|layout| layout.move_section(index, false))
+            ));
+            row.add_suffix(&down_button);
+
+            if matches!(section.kind, HomeSectionKind::Pinned(_)) {
+                let remove_button = self.section_button("user-trash-symbolic", &gettext("Remove"));
+                remove_button.connect_clicked(glib::clone!(
+                    #[weak(rename_to = obj)]
+                    self,
+                    move |_| obj.edit_layout(|layout| layout.remove_section(index))
+                ));
+                row.add_suffix(&remove_button);
+            }
+
+            let switch = gtk::Switch::builder()
+                .active(section.visible)
+                .valign(gtk::Align::Center)
+                .build();
+            switch.connect_active_notify(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |switch| {
+                    let visible = switch.is_active();
+                    obj.edit_layout(|layout| {
+                        if let Some(section) = layout.sections.get_mut(index) {
+                            section.visible = visible;
+                        }
+                    });
+                }
+            ));
+            row.add_suffix(&switch);
+
+            imp.sections_list.append(&row);
+        }
+    }
+
+    fn section_button(&self, icon_name: &str, tooltip: &str) -> gtk::Button {
+        gtk::Button::builder()
+            .icon_name(icon_name)
+            .tooltip_text(tooltip)
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build()
+    }
+
+    async fn libraries(&self) -> Vec<SimpleListItem> {
+        let client = self.client();
+        match spawn_tokio(async move { client.get_library().await }).await {
+            Ok(list) => list.items,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                Vec::new()
+            }
+        }
+    }
+
+    async fn load_libraries(&self) {
+        let imp = self.imp();
+        for row in imp.library_rows.take() {
+            imp.latest_group.remove(&row);
+        }
+
+        let mut rows = Vec::new();
+        for library in self.libraries().await {
+            let row = adw::SwitchRow::builder()
+                .title(&library.name)
+                .use_markup(false)
+                .active(!imp.layout.borrow().is_library_hidden(&library.id))
+                .build();
+            row.connect_active_notify(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |row| {
+                    let hidden = !row.is_active();
+                    obj.edit_layout(|layout| layout.set_library_hidden(&library.id, hidden));
+                }
+            ));
+            imp.latest_group.add(&row);
+            rows.push(row);
+        }
+        imp.library_rows.replace(rows);
+    }
+
+    #[template_callback]
+    async fn on_pin_collection(&self) {
+        self.pin_container("BoxSet").await;
+    }
+
+    #[template_callback]
+    async fn on_pin_playlist(&self) {
+        self.pin_container("Playlist").await;
+    }
+
+    #[template_callback]
+    async fn on_pin_genre(&self) {
+        let client = self.client();
+        let genres = match spawn_tokio(async move { client.get_genres().await }).await {
+            Ok(list) => list.items,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+        self.pick_item(&gettext("Pin a Genre"), genres, |id| PinnedSource::Genre {
+            id,
+        });
+    }
+
+    async fn pin_container(&self, item_type: &'static str) {
+        let client = self.client();
+        let containers =
+            match spawn_tokio(async move { client.get_user_containers(item_type).await }).await {
+                Ok(list) => list.items,
+                Err(e) => {
+                    self.toast(e.to_user_facing());
+                    return;
+                }
+            };
+        if item_type == "Playlist" {
+            self.pick_item(&gettext("Pin a Playlist"), containers, |id| {
+                PinnedSource::Playlist { id }
+            });
+        } else {
+            self.pick_item(&gettext("Pin a Collection"), containers, |id| {
+                PinnedSource::Collection { id }
+            });
+        }
+    }
+
+    /// Lets the user choose one of `items` and pins it as a row named after it.
+    fn pick_item<F>(&self, heading: &str, items: Vec<SimpleListItem>, source: F)
+    where
+        F: Fn(String) -> PinnedSource + 'static,
+    {
+        if items.is_empty() {
+            self.toast(gettext("Nothing to pin"));
+            return;
+        }
+
+        let names = items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>();
+        let combo_row = adw::ComboRow::builder()
+            .title(gettext("Item"))
+            .model(&gtk::StringList::new(&names))
+            .enable_search(true)
+            .build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&combo_row);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(heading)
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("pin", &gettext("Pin"))]);
+        dialog.set_response_appearance("pin", adw::ResponseAppearance::Suggested);
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("pin"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                combo_row,
+                move |_, _| {
+                    let Some(item) = items.get(combo_row.selected() as usize) else {
+                        return;
+                    };
+                    let pinned = source(item.id.to_owned());
+                    obj.edit_layout(|layout| layout.add_pinned(item.name.to_owned(), pinned));
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    #[template_callback]
+    async fn on_pin_filter(&self) {
+        let libraries = self.libraries().await;
+
+        let mut names = vec![gettext("All Libraries")];
+        names.extend(libraries.iter().map(|library| library.name.to_owned()));
+        let library_row = adw::ComboRow::builder()
+            .title(gettext("Library"))
+            .model(&gtk::StringList::new(
+                &names.iter().map(String::as_str).collect::<Vec<_>>(),
+            ))
+            .build();
+        let title_entry = adw::EntryRow::builder().title(gettext("Title")).build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&title_entry);
+        list.append(&library_row);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Pin a Saved Filter"))
+            .body(gettext("Choose the filters on the next page"))
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("next", &gettext("Next"))]);
+        dialog.set_response_appearance("next", adw::ResponseAppearance::Suggested);
+        dialog.set_response_enabled("next", false);
+        dialog.set_close_response("cancel");
+
+        title_entry.connect_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                dialog.set_response_enabled("next", !entry.text().trim().is_empty());
+            }
+        ));
+
+        dialog.connect_response(
+            Some("next"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                title_entry,
+                #[weak]
+                library_row,
+                move |_, _| {
+                    let title = title_entry.text().trim().to_string();
+                    let parent_id = (library_row.selected() as usize)
+                        .checked_sub(1)
+                        .and_then(|index| libraries.get(index))
+                        .map(|library| library.id.to_owned());
+                    obj.choose_filters(title, parent_id);
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    fn choose_filters(&self, title: String, parent_id: Option<String>) {
+        let panel = FilterPanelDialog::new(self.client());
+        panel.connect_applied(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            #[weak]
+            panel,
+            move |_| {
+                let filters = panel.filters_list();
+                if filters.is_empty() {
+                    panel.add_toast(adw::Toast::new(&gettext("No filters selected")));
+                    return;
+                }
+                panel.close();
+                let source = PinnedSource::Filter {
+                    parent_id: parent_id.to_owned(),
+                    params: filters.to_kv(),
+                };
+                obj.edit_layout(|layout| layout.add_pinned(title.to_owned(), source));
+            }
+        ));
+        panel.present(Some(self));
+    }
+
+    #[template_callback]
+    fn on_reset(&self) {
+        if let Err(e) = HomeLayouts::reset(&self.client()) {
+            self.toast(e.to_string());
+        }
+        self.imp().layout.replace(HomeLayouts::of(&self.client()));
+        self.update_sections();
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                obj.load_libraries().await;
+            }
+        ));
+    }
+}
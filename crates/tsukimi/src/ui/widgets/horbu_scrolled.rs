@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::{
     prelude::*,
     subclass::prelude::*,
@@ -9,9 +11,12 @@ use gtk::{
 };
 
 use crate::{
-    client::structs::{
-        SGTitem,
-        Urls,
+    client::{
+        jellyfin_client::JellyfinClient,
+        structs::{
+            SGTitem,
+            Urls,
+        },
     },
     utils::spawn,
 };
@@ -80,7 +85,7 @@ impl HorbuScrolled {
         glib::Object::new()
     }
 
-    pub fn set_items(&self, items: &[SGTitem], type_: &str) {
+    pub fn set_items(&self, items: &[SGTitem], type_: &str, client: &Arc<JellyfinClient>) {
         if items.is_empty() {
             return;
         }
@@ -95,6 +100,7 @@ impl HorbuScrolled {
 
         let wrapbox = imp.wrapbox.get();
         let type_ = type_.to_string();
+        let client = client.to_owned();
 
         spawn(glib::clone!(
             #[weak]
@@ -120,11 +126,12 @@ impl HorbuScrolled {
                     let button = gtk::Button::builder().child(&buttoncontent).build();
 
                     let type_ = type_.to_string();
+                    let client = client.to_owned();
                     button.connect_clicked(glib::clone!(
                         #[weak]
                         obj,
                         move |_| {
-                            result.activate(&obj, type_.to_string());
+                            result.activate(&obj, type_.to_string(), client.to_owned());
                         }
                     ));
 
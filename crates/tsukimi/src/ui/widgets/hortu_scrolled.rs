@@ -1,21 +1,32 @@
+use std::sync::Arc;
+
 use adw::{
     prelude::*,
     subclass::prelude::*,
 };
 use gtk::{
     CompositeTemplate,
+    gdk,
+    gio,
     glib,
     template_callbacks,
 };
 
 use crate::{
-    client::structs::SimpleListItem,
+    client::{
+        jellyfin_client::JellyfinClient,
+        structs::SimpleListItem,
+    },
     ui::{
-        provider::tu_object::TuObject,
+        provider::{
+            tu_item::TuItem,
+            tu_object::TuObject,
+        },
         widgets::{
             fix::ScrolledWindowFixExt,
             hor_controls::HorControlsExt,
             lazy_diff_view::LazyDiffView,
+            selection_bar::SelectionBar,
             tu_item::{
                 CardOptions,
                 CardShape,
@@ -58,6 +69,10 @@ mod imp {
         pub left_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub right_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub select_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub selection_bar: TemplateChild<SelectionBar>,
 
         #[property(get, set, default_value = false)]
         pub moreview: Cell<bool>,
@@ -78,6 +93,10 @@ mod imp {
         pub hide_right_animation: OnceCell<adw::TimedAnimation>,
         pub is_hovering: Cell<bool>,
         pub item_cache: RefCell<HashMap<String, TuObject>>,
+        pub selection: OnceCell<gtk::MultiSelection>,
+        #[property(get)]
+        pub selection_mode: Cell<bool>,
+        pub selection_anchor: Cell<Option<u32>>,
         #[property(get, set, builder(CardShape::default()))]
         pub resolved_card_shape: Cell<CardShape>,
     }
@@ -105,6 +124,20 @@ mod imp {
 
             let obj = self.obj();
 
+            let store = gio::ListStore::new::<TuObject>();
+            let _ = self.selection.set(gtk::MultiSelection::new(Some(store)));
+            self.selection_bar.set_model(self.selection.get());
+            self.selection_bar.connect_done(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.imp().select_button.set_active(false)
+            ));
+            self.selection_bar.connect_items_removed(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.sync_diffview()
+            ));
+
             self.diffview.set_orientation(gtk::Orientation::Horizontal);
             self.diffview
                 .scroll()
@@ -117,10 +150,25 @@ mod imp {
                     let tu_item = TuListItem::default();
                     if let Some(obj) = weak_obj.upgrade() {
                         tu_item.set_card_options(obj.card_options());
+                        // `watch` drops the handler together with recycled rows.
+                        obj.selection().connect_closure(
+                            "selection-changed",
+                            false,
+                            glib::closure_local!(
+                                #[weak]
+                                obj,
+                                #[watch]
+                                tu_item,
+                                move |_: gtk::MultiSelection, _: u32, _: u32| {
+                                    obj.update_item_selected(&tu_item);
+                                }
+                            ),
+                        );
                     }
 
                     let gesture = gtk::GestureClick::new();
                     gesture.set_button(1);
+                    let weak_obj = weak_obj.clone();
                     gesture.connect_released(glib::clone!(
                         #[weak]
                         tu_item,
@@ -129,6 +177,12 @@ mod imp {
                                 return;
                             }
                             gesture.set_state(gtk::EventSequenceState::Claimed);
+                            if let Some(obj) = weak_obj.upgrade()
+                                && obj.selection_mode()
+                            {
+                                obj.toggle_item(&tu_item.item(), gesture.current_event_state());
+                                return;
+                            }
                             tu_item.item().activate(&tu_item);
                         }
                     ));
@@ -136,12 +190,17 @@ mod imp {
 
                     tu_item.upcast::<gtk::Widget>()
                 },
-                |widget, tu_obj: &TuObject| {
-                    let tu_item = widget
-                        .downcast_ref::<TuListItem>()
-                        .expect("LazyDiffView row must be a TuListItem");
-                    tu_item.set_item(tu_obj.item());
-                },
+                glib::clone!(
+                    #[weak]
+                    obj,
+                    move |widget, tu_obj: &TuObject| {
+                        let tu_item = widget
+                            .downcast_ref::<TuListItem>()
+                            .expect("LazyDiffView row must be a TuListItem");
+                        tu_item.set_item(tu_obj.item());
+                        obj.update_item_selected(tu_item);
+                    }
+                ),
             );
 
             self.obj().connect_scroll_controls();
@@ -190,7 +249,21 @@ impl HortuScrolled {
         }
     }
 
-    pub fn set_items(&self, items: Vec<SimpleListItem>) {
+    /// Shows `items`, listed by `client`.
+    pub fn set_items(&self, items: Vec<SimpleListItem>, client: &Arc<JellyfinClient>) {
+        let clients = vec![client.to_owned(); items.len()];
+        self.set_items_of(items, clients, false);
+    }
+
+    /// Shows items of several accounts, each badged with its server name.
+    pub fn set_items_of_servers(&self, items: Vec<(SimpleListItem, Arc<JellyfinClient>)>) {
+        let (items, clients) = items.into_iter().unzip();
+        self.set_items_of(items, clients, true);
+    }
+
+    fn set_items_of(
+        &self, items: Vec<SimpleListItem>, clients: Vec<Arc<JellyfinClient>>, badge: bool,
+    ) {
         let imp = self.imp();
 
         if items.is_empty() {
@@ -210,12 +283,18 @@ impl HortuScrolled {
 
         let items = items
             .into_iter()
-            .map(|item| {
+            .zip(clients)
+            .map(|(item, client)| {
                 let mut cache = imp.item_cache.borrow_mut();
                 let object = if let Some(object) = cache.get(&item.id) {
                     object.clone()
                 } else {
-                    let object = TuObject::from_simple(item.to_owned());
+                    let object = TuObject::from_simple(item.to_owned(), &client);
+                    if badge {
+                        object
+                            .item()
+                            .set_server_name(Some(client.session().account.servername.as_str()));
+                    }
                     cache.insert(object.item().key(), object.clone());
                     object
                 };
@@ -226,11 +305,97 @@ impl HortuScrolled {
             })
             .collect::<Vec<_>>();
 
+        let store = self.store();
+        store.splice(0, store.n_items(), &items);
         imp.diffview.set_items(items);
 
         imp.revealer.set_reveal_child(true);
     }
 
+    fn selection(&self) -> gtk::MultiSelection {
+        self.imp()
+            .selection
+            .get()
+            .expect("HortuScrolled is not constructed")
+            .clone()
+    }
+
+    fn store(&self) -> gio::ListStore {
+        self.selection()
+            .model()
+            .and_downcast::<gio::ListStore>()
+            .expect("HortuScrolled selection must wrap a ListStore")
+    }
+
+    /// Shows the items left in the store after the selection bar removed some.
+    fn sync_diffview(&self) {
+        let store = self.store();
+        let items = (0..store.n_items())
+            .filter_map(|position| store.item(position).and_downcast::<TuObject>())
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            self.imp().select_button.set_active(false);
+            self.imp().revealer.set_reveal_child(false);
+        }
+        self.imp().diffview.set_items(items);
+    }
+
+    fn position_of(&self, item: &TuItem) -> Option<u32> {
+        let store = self.store();
+        (0..store.n_items()).find(|position| {
+            store
+                .item(*position)
+                .and_downcast::<TuObject>()
+                .is_some_and(|object| object.item() == *item)
+        })
+    }
+
+    /// Click toggles a single item, shift-click selects everything between the
+    /// last clicked item and this one.
+    fn toggle_item(&self, item: &TuItem, modifiers: gdk::ModifierType) {
+        let Some(position) = self.position_of(item) else {
+            return;
+        };
+        let imp = self.imp();
+        let selection = self.selection();
+
+        match imp.selection_anchor.get() {
+            Some(anchor) if modifiers.contains(gdk::ModifierType::SHIFT_MASK) => {
+                let start = anchor.min(position);
+                let n_items = anchor.max(position) - start + 1;
+                selection.select_range(start, n_items, false);
+            }
+            _ if selection.is_selected(position) => {
+                selection.unselect_item(position);
+            }
+            _ => {
+                selection.select_item(position, false);
+            }
+        }
+        imp.selection_anchor.set(Some(position));
+    }
+
+    fn update_item_selected(&self, tu_item: &TuListItem) {
+        let selected = self
+            .position_of(&tu_item.item())
+            .is_some_and(|position| self.selection().is_selected(position));
+        if selected {
+            tu_item.add_css_class("selected-item");
+        } else {
+            tu_item.remove_css_class("selected-item");
+        }
+    }
+
+    #[template_callback]
+    fn on_select_toggled(&self, button: &gtk::ToggleButton) {
+        let imp = self.imp();
+        let active = button.is_active();
+        imp.selection_mode.set(active);
+        imp.selection_anchor.set(None);
+        imp.selection_bar.reveal(active);
+        self.notify_selection_mode();
+    }
+
     #[template_callback]
     fn on_rightbutton_clicked(&self) {
         self.scroll_controls_anime::<true>();
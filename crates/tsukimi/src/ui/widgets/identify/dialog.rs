@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
 
 use adw::{
     prelude::*,
@@ -13,7 +16,7 @@ use gtk::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
         structs::{
             ExternalIdInfo,
             RemoteSearchInfo,
@@ -38,12 +41,13 @@ mod imp {
 
     use super::*;
 
-    #[derive(Debug, Default, CompositeTemplate, glib::Properties)]
+    #[derive(Default, CompositeTemplate, glib::Properties)]
     #[template(resource = "/moe/tsuna/tsukimi/ui/identify_dialog.ui")]
     #[properties(wrapper_type = super::IdentifyDialog)]
     pub struct IdentifyDialog {
         #[property(get, set, construct_only)]
         pub id: OnceCell<String>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
         #[property(get, set, construct_only)]
         pub itemtype: OnceCell<String>,
         #[template_child]
@@ -101,11 +105,21 @@ glib::wrapper! {
 
 #[template_callbacks]
 impl IdentifyDialog {
-    pub fn new(id: &str, itemtype: &str) -> Self {
-        glib::Object::builder()
+    pub fn new(id: &str, itemtype: &str, client: Arc<JellyfinClient>) -> Self {
+        let dialog: Self = glib::Object::builder()
             .property("id", id)
             .property("itemtype", itemtype)
-            .build()
+            .build();
+        let _ = dialog.imp().client.set(client);
+        dialog
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("IdentifyDialog is created with a client")
+            .clone()
     }
 
     pub fn init(&self) {
@@ -119,7 +133,9 @@ impl IdentifyDialog {
     async fn get_data(&self) {
         let id = self.id();
         let id_clone = id.to_owned();
-        match spawn_tokio(async move { JELLYFIN_CLIENT.get_external_id_info(&id).await }).await {
+        let client = self.client();
+        let client_clone = client.clone();
+        match spawn_tokio(async move { client.get_external_id_info(&id).await }).await {
             Ok(data) => {
                 self.imp().stack.set_visible_child_name("page");
                 self.load_data(data);
@@ -128,7 +144,7 @@ impl IdentifyDialog {
                 self.toast(e.to_user_facing());
             }
         }
-        match spawn_tokio(async move { JELLYFIN_CLIENT.get_item_info(&id_clone).await }).await {
+        match spawn_tokio(async move { client_clone.get_item_info(&id_clone).await }).await {
             Ok(item) => {
                 self.imp()
                     .path_row
@@ -206,15 +222,12 @@ impl IdentifyDialog {
 
         imp.stack.set_visible_child_name("loading");
 
-        match spawn_tokio(async move {
-            JELLYFIN_CLIENT
-                .remote_search(&type_, &remote_search_info)
-                .await
-        })
-        .await
+        let client = self.client();
+        match spawn_tokio(async move { client.remote_search(&type_, &remote_search_info).await })
+            .await
         {
             Ok(data) => {
-                let search_page = super::IdentifyDialogSearchPage::new(&self.id());
+                let search_page = super::IdentifyDialogSearchPage::new(&self.id(), self.client());
                 search_page.extend_item(data, self.itemtype());
                 self.imp().stack.set_visible_child_name("page");
                 self.imp().navigation_view.push(&search_page);
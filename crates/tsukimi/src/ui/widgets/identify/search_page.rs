@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::{
     prelude::*,
     subclass::prelude::*,
@@ -16,7 +18,7 @@ use serde_json::Value;
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
         structs::RemoteSearchResult,
     },
     ui::{
@@ -45,12 +47,13 @@ mod imp {
 
     use super::*;
 
-    #[derive(Debug, Default, CompositeTemplate, glib::Properties)]
+    #[derive(Default, CompositeTemplate, glib::Properties)]
     #[template(resource = "/moe/tsuna/tsukimi/ui/identify_dialog_search_page.ui")]
     #[properties(wrapper_type = super::IdentifyDialogSearchPage)]
     pub struct IdentifyDialogSearchPage {
         #[property(get, set, construct_only)]
         pub id: OnceCell<String>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
 
         #[template_child]
         pub grid: TemplateChild<gtk::GridView>,
@@ -98,8 +101,18 @@ glib::wrapper! {
 
 #[template_callbacks]
 impl IdentifyDialogSearchPage {
-    pub fn new(id: &str) -> Self {
-        glib::Object::builder().property("id", id).build()
+    pub fn new(id: &str, client: Arc<JellyfinClient>) -> Self {
+        let page: Self = glib::Object::builder().property("id", id).build();
+        let _ = page.imp().client.set(client);
+        page
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("IdentifyDialogSearchPage is created with a client")
+            .clone()
     }
 
     #[template_callback]
@@ -151,10 +164,9 @@ impl IdentifyDialogSearchPage {
                         #[strong]
                         value,
                         async move {
+                            let client = obj.client();
                             match spawn_tokio(async move {
-                                JELLYFIN_CLIENT
-                                    .apply_remote_search(&id, value, replace)
-                                    .await
+                                client.apply_remote_search(&id, value, replace).await
                             })
                             .await
                             {
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::subclass::prelude::*;
 use gtk::{
     glib,
@@ -8,7 +10,7 @@ use gtk::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
     },
     ui::{
         GlobalToast,
@@ -37,12 +39,13 @@ mod imp {
         utils::spawn,
     };
 
-    #[derive(Debug, Default, CompositeTemplate, glib::Properties)]
+    #[derive(Default, CompositeTemplate, glib::Properties)]
     #[template(resource = "/moe/tsuna/tsukimi/ui/images_dialog.ui")]
     #[properties(wrapper_type = super::ImagesDialog)]
     pub struct ImagesDialog {
         #[property(get, set, construct_only)]
         pub id: OnceCell<String>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
         #[property(get, set, construct_only)]
         pub item_type: OnceCell<String>,
 
@@ -144,16 +147,26 @@ mod imp {
         pub async fn set_card(&self, card: &ImageInfoCard, item: &ImageItem) {
             card.set_loading_visible();
             card.set_size(&item.width, &item.height, &item.size);
-            card.set_picture(&item.image_type, &self.obj().id(), &None)
-                .await;
+            card.set_picture(
+                &self.obj().client(),
+                &item.image_type,
+                &self.obj().id(),
+                &None,
+            )
+            .await;
         }
 
         pub async fn add_backdrop(&self, item: &ImageItem) {
             let card = ImageInfoCard::new("Backdrop", &self.obj().id());
             card.set_loading_visible();
             card.set_size(&item.width, &item.height, &item.size);
-            card.set_picture(&item.image_type, &self.obj().id(), &item.image_index)
-                .await;
+            card.set_picture(
+                &self.obj().client(),
+                &item.image_type,
+                &self.obj().id(),
+                &item.image_index,
+            )
+            .await;
             self.size_group.add_widget(&card.imp().stack.get());
             self.wrapbox.append(&card);
         }
@@ -198,11 +211,21 @@ impl ImagesDialog {
     const LOADING_STACK_PAGE: &'static str = "loading";
     const VIEW_STACK_PAGE: &'static str = "view";
 
-    pub fn new(id: &str, item_type: &str) -> Self {
-        glib::Object::builder()
+    pub fn new(id: &str, item_type: &str, client: Arc<JellyfinClient>) -> Self {
+        let dialog: Self = glib::Object::builder()
             .property("id", id)
             .property("item-type", item_type)
-            .build()
+            .build();
+        let _ = dialog.imp().client.set(client);
+        dialog
+    }
+
+    pub fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ImagesDialog is created with a client")
+            .clone()
     }
 
     pub fn loading_page(&self) {
@@ -223,7 +246,8 @@ impl ImagesDialog {
 
     pub async fn set_image_items(&self) {
         let id = self.id();
-        match spawn_tokio(async move { JELLYFIN_CLIENT.get_image_items(&id).await }).await {
+        let client = self.client();
+        match spawn_tokio(async move { client.get_image_items(&id).await }).await {
             Ok(items) => {
                 while let Some(item) = self.imp().wrapbox.first_child() {
                     self.imp().wrapbox.remove(&item);
@@ -1,4 +1,7 @@
-use std::io::Read;
+use std::{
+    io::Read,
+    sync::Arc,
+};
 
 use adw::subclass::prelude::*;
 use gettextrs::gettext;
@@ -12,7 +15,7 @@ use gtk::template_callbacks;
 use reqwest::Response;
 
 use crate::{
-    client::jellyfin_client::JELLYFIN_CLIENT,
+    client::jellyfin_client::JellyfinClient,
     ui::GlobalToast,
     utils::{
         spawn,
@@ -105,7 +108,9 @@ impl ImageDialogEditPage {
             .build()
     }
 
-    async fn post_image(&self, id: String, image_type: String) -> Result<Response> {
+    async fn post_image(
+        &self, client: Arc<JellyfinClient>, id: String, image_type: String,
+    ) -> Result<Response> {
         let file = match self.imp().image_drop_row.imp().image_file.upgrade() {
             Some(file) => file,
             None => return Err(anyhow!("No file found")),
@@ -141,7 +146,7 @@ impl ImageDialogEditPage {
         let bytes = STANDARD.encode(bytes);
 
         spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            client
                 .post_image(&id, &image_type, bytes, &content_type)
                 .await
         })
@@ -156,6 +161,7 @@ impl ImageDialogEditPage {
 
         dialog.loading_page();
 
+        let client = dialog.client();
         let id = self.id();
         let image_type = self.image_type();
 
@@ -164,13 +170,13 @@ impl ImageDialogEditPage {
             let image_index = self.image_index();
 
             spawn_tokio(async move {
-                JELLYFIN_CLIENT
+                client
                     .post_image_url(&id, &image_type, image_index, &url)
                     .await
             })
             .await
         } else {
-            self.post_image(id, image_type).await
+            self.post_image(client, id, image_type).await
         };
 
         match result {
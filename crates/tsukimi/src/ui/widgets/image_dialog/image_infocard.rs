@@ -9,7 +9,7 @@ use gtk::{
 };
 
 use crate::{
-    client::jellyfin_client::JELLYFIN_CLIENT,
+    client::jellyfin_client::JellyfinClient,
     ui::{
         GlobalToast,
         widgets::{
@@ -206,12 +206,13 @@ impl ImageInfoCard {
         let id = self.imgid();
         let img_type = self.imgtype();
         let image_index = self.image_index();
+        let Some(client) = self.image_dialog().map(|dialog| dialog.client()) else {
+            return;
+        };
 
-        match spawn_tokio(async move {
-            JELLYFIN_CLIENT
-                .delete_image(&id, &img_type, Some(image_index))
-                .await
-        })
+        match spawn_tokio(
+            async move { client.delete_image(&id, &img_type, Some(image_index)).await },
+        )
         .await
         {
             Ok(_) => {
@@ -240,10 +241,10 @@ impl ImageInfoCard {
         self.imp().label2.set_text(&str);
     }
 
-    pub async fn set_picture(&self, img_type: &str, id: &str, image_index: &Option<u32>) {
-        let path = JELLYFIN_CLIENT
-            .get_image_path(id, img_type, *image_index)
-            .await;
+    pub async fn set_picture(
+        &self, client: &JellyfinClient, img_type: &str, id: &str, image_index: &Option<u32>,
+    ) {
+        let path = client.get_image_path(id, img_type, *image_index).await;
 
         let picture = self.imp().picture.get();
 
@@ -11,10 +11,7 @@ use adw::prelude::*;
 use gtk::template_callbacks;
 
 use crate::{
-    client::{
-        error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
-    },
+    client::error::UserFacingError,
     ui::{
         GlobalToast,
         widgets::eu_item::{
@@ -139,6 +136,9 @@ impl ImageDialogSearchPage {
             return;
         };
 
+        let Some(client) = self.image_dialog().map(|dialog| dialog.client()) else {
+            return;
+        };
         let id = self.id();
         let image_type = self.image_type();
 
@@ -169,9 +169,11 @@ impl ImageDialogSearchPage {
                         provider_name,
                         #[strong]
                         url,
+                        #[strong]
+                        client,
                         async move {
                             match spawn_tokio(async move {
-                                JELLYFIN_CLIENT
+                                client
                                     .download_remote_images(&id, &image_type, &provider_name, &url)
                                     .await
                             })
@@ -213,8 +215,9 @@ impl ImageDialogSearchPage {
         let if_all_language = imp.all_languages_check.is_active();
         let providers = self.providers();
 
+        let client = dialog.client();
         let remote_image_list = match spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            client
                 .get_remote_image_list(&id, 0, if_all_language, &type_, &providers)
                 .await
         })
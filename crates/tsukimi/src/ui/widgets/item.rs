@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use super::{
     episode_switcher::{
         EpisodeButton,
@@ -6,6 +8,10 @@ use super::{
     fix::ScrolledWindowFixExt,
     hor_controls::HorControlsExt,
     item_utils::*,
+    remote_control_dialog::{
+        PlayRequest,
+        RemoteControlDialog,
+    },
     song_widget::format_duration,
     utils::{
         GlobalToast,
@@ -16,16 +22,18 @@ use super::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
         picture_source::PictureSource,
         structs::*,
     },
     ui::{
+        models::SETTINGS,
         mpv::page::{
             PlaybackDirectMode,
             media_source_stream_url,
         },
         provider::{
+            descriptor::VersionChoice,
             dropdown_factory::{
                 DropdownList,
                 DropdownListBuilder,
@@ -105,6 +113,7 @@ pub(crate) mod imp {
                 hortu_scrolled::HortuScrolled,
                 item_actionbox::ItemActionsBox,
                 item_carousel::ItemCarousel,
+                selection_bar::SelectionBar,
                 tu_overview_item::imp::ViewGroup,
                 utils::TuItemBuildExt,
             },
@@ -176,6 +185,8 @@ pub(crate) mod imp {
         #[template_child]
         pub namedropdown: TemplateChild<gtk::DropDown>,
         #[template_child]
+        pub version_reason_label: TemplateChild<gtk::Label>,
+        #[template_child]
         pub subdropdown: TemplateChild<gtk::DropDown>,
         #[template_child]
         pub carousel: TemplateChild<ItemCarousel>,
@@ -223,6 +234,11 @@ pub(crate) mod imp {
 
         #[template_child]
         pub episode_switcher: TemplateChild<EpisodeSwitcher>,
+        #[template_child]
+        pub select_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub selection_bar: TemplateChild<SelectionBar>,
+        pub episode_selection: OnceCell<gtk::MultiSelection>,
 
         pub show_left_animation: OnceCell<adw::TimedAnimation>,
         pub hide_left_animation: OnceCell<adw::TimedAnimation>,
@@ -281,6 +297,16 @@ pub(crate) mod imp {
             let store = gtk::gio::ListStore::new::<TuObject>();
             self.selection.set_model(Some(&store));
             self.itemlist.set_model(Some(&self.selection));
+            let _ = self
+                .episode_selection
+                .set(gtk::MultiSelection::new(Some(store)));
+            self.selection_bar.set_model(self.episode_selection.get());
+            let select_button = self.select_button.get();
+            self.selection_bar.connect_done(glib::clone!(
+                #[weak]
+                select_button,
+                move |_| select_button.set_active(false)
+            ));
             self.itemlist.set_factory(Some(
                 gtk::SignalListItemFactory::new()
                     .tu_overview_item(ViewGroup::EpisodesView, Default::default()),
@@ -288,6 +314,7 @@ pub(crate) mod imp {
             self.obj().connect_scroll_controls();
 
             let item = self.obj().item();
+            self.actionbox.set_client(item.client());
 
             if item.item_type() == "Series"
                 || (item.item_type() == "Episode" && item.series_name().is_some())
@@ -330,6 +357,10 @@ impl ItemPage {
         Object::builder().property("item", item).build()
     }
 
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.item().client()
+    }
+
     pub async fn setup(&self) {
         let item = self.item();
         let type_ = item.item_type();
@@ -408,15 +439,15 @@ impl ItemPage {
     pub async fn update_intro(&self, current_item: TuItem) {
         let item = self.item();
 
+        let client = self.client();
         let id = current_item.id();
-        let current_item =
-            match spawn_tokio(async move { JELLYFIN_CLIENT.get_item_info(&id).await }).await {
-                Ok(item) => TuItem::from_simple(item),
-                Err(e) => {
-                    self.toast(e.to_user_facing());
-                    current_item
-                }
-            };
+        let current_item = match spawn_tokio(async move { client.get_item_info(&id).await }).await {
+            Ok(item) => TuItem::from_simple(item, &self.client()),
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                current_item
+            }
+        };
 
         if item.item_type() == "Series" || item.item_type() == "Episode" {
             self.set_intro::<false>(&current_item).await;
@@ -451,9 +482,10 @@ impl ItemPage {
         play_button.set_sensitive(false);
         spinner.set_visible(true);
 
+        let client = self.client();
         let intro_id_clone = intro_id.to_owned();
         let playback = match spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            client
                 .get_playbackinfo(
                     &intro_id_clone,
                     None,
@@ -516,14 +548,11 @@ impl ItemPage {
         };
         self.set_current_season(Some(season_id.to_owned()));
 
+        let client = self.client();
         let mut events = fetch_with_cache(
             &format!("season_{season_id}"),
             CachePolicy::ReadCacheAndRefresh,
-            async move {
-                JELLYFIN_CLIENT
-                    .get_episodes_all(&series_id, &season_id)
-                    .await
-            },
+            async move { client.get_episodes_all(&series_id, &season_id).await },
         )
         .await;
 
@@ -631,9 +660,10 @@ impl ItemPage {
             (!self.is_at_lower() || imp.selection.selected() != 0).then_some(0)
         });
 
+        let client = self.client();
         let items = slice
             .iter()
-            .map(|item| TuObject::from_simple(item.to_owned()))
+            .map(|item| TuObject::from_simple(item.to_owned(), &client))
             .collect::<Vec<_>>();
         store.splice(0, store.n_items(), &items);
         imp.episode_stack.set_visible_child_name("view");
@@ -666,19 +696,19 @@ impl ItemPage {
     }
 
     async fn set_shows_next_up(&self, id: &str) -> Option<TuItem> {
+        let client = self.client();
         let id = id.to_string();
-        let next_up =
-            match spawn_tokio(async move { JELLYFIN_CLIENT.get_shows_next_up(&id).await }).await {
-                Ok(next_up) => next_up,
-                Err(e) => {
-                    self.toast(e.to_user_facing());
-                    return None;
-                }
-            };
+        let next_up = match spawn_tokio(async move { client.get_shows_next_up(&id).await }).await {
+            Ok(next_up) => next_up,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return None;
+            }
+        };
 
         let next_up_item = next_up.items.into_iter().next()?;
 
-        let tu_item = TuItem::from_simple(next_up_item);
+        let tu_item = TuItem::from_simple(next_up_item, &self.client());
 
         self.set_now_item::<false>(&tu_item);
 
@@ -713,6 +743,7 @@ impl ItemPage {
 
     pub async fn set_dropdown(&self, playbackinfo: &Media) {
         let imp = self.imp();
+        let client = self.item().client();
         let namedropdown = imp.namedropdown.get();
         let subdropdown = imp.subdropdown.get();
 
@@ -770,7 +801,8 @@ impl ItemPage {
                             }
                         }
 
-                        subtitle_choice = make_subtitle_version_choice(lang_list);
+                        subtitle_choice =
+                            make_subtitle_version_choice(lang_list, &imp.obj().client());
                         break;
                     }
                 }
@@ -789,7 +821,7 @@ impl ItemPage {
                 .bit_rate
                 .map(|bit_rate| format!("{:.2} Kbps", bit_rate as f64 / 1_000.0))
                 .unwrap_or_default();
-            let play_url = media_source_stream_url(media).await;
+            let play_url = media_source_stream_url(&client, media).await;
             let Ok(dl) = DropdownListBuilder::default()
                 .line1(Some(media.name.to_owned()))
                 .line2(Some(line2))
@@ -806,13 +838,31 @@ impl ItemPage {
 
         vstore.extend_from_slice(&objects);
 
+        imp.version_reason_label.set_visible(false);
         if let Some(matcher) = matcher {
             if let Some(p) = make_video_version_choice_from_matcher(v_dl, &matcher) {
                 namedropdown.set_selected(p as u32);
             }
-        } else if let Some(p) = make_video_version_choice_from_filter(v_dl) {
-            namedropdown.set_selected(p as u32);
+        } else if let Some(choice) = make_video_version_choice(&playbackinfo.media_sources) {
+            namedropdown.set_selected(choice.index as u32);
+            self.explain_version_choice(&choice);
+        }
+    }
+
+    fn explain_version_choice(&self, choice: &VersionChoice) {
+        let label = &self.imp().version_reason_label;
+        if SETTINGS.preferred_version_descriptors().is_empty() {
+            return;
         }
+        let text = if choice.all_excluded {
+            gettext("Every version is excluded by a rule")
+        } else if choice.reasons.is_empty() {
+            gettext("No rule matched, using the first version")
+        } else {
+            gettext("Chosen by rules: {rules}").replace("{rules}", &choice.reasons.join(", "))
+        };
+        label.set_text(&text);
+        label.set_visible(true);
     }
 
     pub async fn setup_background(&self, source: PictureSource) {
@@ -841,12 +891,14 @@ impl ItemPage {
     pub async fn add_backdrops(&self, image_tags: Vec<String>, id: &str) {
         let imp = self.imp();
         let carousel = imp.carousel.imp().carousel.get();
+        let account = self.item().account_key();
         for (tag_num, tag) in image_tags.into_iter().enumerate().skip(1) {
             let source = PictureSource::Item {
                 id: id.to_string(),
                 tag,
                 image_type: BACKDROP,
                 image_index: Some(tag_num as u8),
+                account: account.to_owned(),
             };
             if let Ok(file) = resolve_picture_file(source).await {
                 let picture = gtk::Picture::builder()
@@ -862,6 +914,7 @@ impl ItemPage {
 
     pub async fn setup_seasons(&self, id: &str) {
         let imp = self.imp();
+        let client = self.client();
         let id = id.to_string();
 
         let Some(season_list_store) = imp.seasonlist.model().and_downcast::<gtk::StringList>()
@@ -872,7 +925,7 @@ impl ItemPage {
         let mut events = fetch_with_cache(
             &format!("season_{}", id),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_season_list(&id).await },
+            async move { client.get_season_list(&id).await },
         )
         .await;
 
@@ -889,7 +942,8 @@ impl ItemPage {
                         season_list_store.n_items().saturating_sub(1),
                         &names,
                     );
-                    imp.seasonshortu.set_items(season_list.to_owned());
+                    imp.seasonshortu
+                        .set_items(season_list.to_owned(), &self.client());
                     imp.season_list_vec.replace(season_list);
                     self.on_season_selected(None, imp.seasonlist.get()).await;
                 }
@@ -903,6 +957,9 @@ impl ItemPage {
 
     #[template_callback]
     async fn on_item_activated(&self, position: u32, view: &ListView) {
+        if self.imp().select_button.is_active() {
+            return;
+        }
         let Some(model) = view.model() else {
             return;
         };
@@ -914,7 +971,8 @@ impl ItemPage {
 
     pub async fn set_logo(&self, item: &SimpleListItem) {
         let logo_bin = self.imp().logo_bin.get();
-        let logo_source = select_logo_picture_source(item);
+        let logo_source = select_logo_picture_source(item)
+            .map(|source| source.for_account(self.item().account_key()));
 
         if let Some(logo_source) = logo_source {
             let logo = super::logo::set_logo(logo_source).await;
@@ -925,12 +983,13 @@ impl ItemPage {
     }
 
     pub async fn set_overview(&self, id: &str) {
+        let client = self.client();
         let id = id.to_string();
 
         let mut events = fetch_with_cache(
             &format!("item_{}", id),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_item_info(&id).await },
+            async move { client.get_item_info(&id).await },
         )
         .await;
 
@@ -1227,7 +1286,7 @@ impl ItemPage {
 
     pub async fn setactorscrolled(&self, actors: Vec<SimpleListItem>) {
         let hortu = self.imp().actorhortu.get();
-        hortu.set_items(actors);
+        hortu.set_items(actors, &self.client());
     }
 
     pub async fn set_lists(&self, id: &str) {
@@ -1242,6 +1301,7 @@ impl ItemPage {
             _ => return,
         };
 
+        let client = self.client();
         let id = id.to_string();
         let types = types.to_string();
 
@@ -1250,9 +1310,9 @@ impl ItemPage {
             CachePolicy::ReadCacheAndRefresh,
             async move {
                 match types.as_str() {
-                    "Recommend" => JELLYFIN_CLIENT.get_similar(&id).await,
-                    "Included In" => JELLYFIN_CLIENT.get_included(&id).await,
-                    "Additional Parts" => JELLYFIN_CLIENT.get_additional(&id).await,
+                    "Recommend" => client.get_similar(&id).await,
+                    "Included In" => client.get_included(&id).await,
+                    "Additional Parts" => client.get_additional(&id).await,
                     _ => Ok(List::default()),
                 }
             },
@@ -1262,7 +1322,7 @@ impl ItemPage {
         while let Some(event) = events.recv().await {
             match event {
                 CacheEvent::Data { data, .. } => {
-                    hortu.set_items(data.items);
+                    hortu.set_items(data.items, &self.client());
                 }
                 CacheEvent::Error(e) => {
                     self.toast(e.to_user_facing());
@@ -1280,7 +1340,7 @@ impl ItemPage {
             _ => return,
         };
 
-        horbu.set_items(&infos, type_);
+        horbu.set_items(&infos, type_, &self.client());
     }
 
     pub fn set_flowlinks(&self, links: Vec<Urls>) {
@@ -1339,10 +1399,11 @@ impl ItemPage {
         let item = self.current_item().unwrap_or(self.item());
         let start_seconds = item.playback_position_ticks() as f64 / 10_000_000.0;
 
+        let client = self.client();
         let episode_list = self.imp().episode_list_vec.borrow();
         let episode_list: Vec<TuItem> = episode_list
             .iter()
-            .map(|item| TuItem::from_simple(item.to_owned()))
+            .map(|item| TuItem::from_simple(item.to_owned(), &client))
             .collect();
 
         let matcher = self.imp().video_version_matcher.borrow().to_owned();
@@ -1351,6 +1412,65 @@ impl ItemPage {
             .play_media(Some(info), item, episode_list, matcher, start_seconds);
     }
 
+    #[template_callback]
+    fn on_play_on_clicked(&self) {
+        let video_dl = self
+            .imp()
+            .namedropdown
+            .selected_item()
+            .and_downcast::<glib::BoxedAnyObject>()
+            .map(|obj| obj.borrow::<DropdownList>().to_owned());
+        let sub_dl = self
+            .imp()
+            .subdropdown
+            .selected_item()
+            .and_downcast::<glib::BoxedAnyObject>()
+            .map(|obj| obj.borrow::<DropdownList>().to_owned());
+
+        let item = self.current_item().unwrap_or(self.item());
+
+        // Send the whole season so the device can move on to the next episode
+        let mut item_ids: Vec<String> = self
+            .imp()
+            .episode_list_vec
+            .borrow()
+            .iter()
+            .map(|episode| episode.id.to_owned())
+            .collect();
+        let start_index = match item_ids.iter().position(|id| *id == item.id()) {
+            Some(index) => index,
+            None => {
+                item_ids = vec![item.id()];
+                0
+            }
+        };
+
+        let request = PlayRequest {
+            item_ids,
+            start_index,
+            start_ticks: item.playback_position_ticks() as i64,
+            media_source_id: video_dl.and_then(|dl| dl.id),
+            subtitle_stream_index: sub_dl.and_then(|dl| dl.index),
+        };
+
+        RemoteControlDialog::new(item.client(), Some(request)).present(Some(self));
+    }
+
+    #[template_callback]
+    fn on_select_toggled(&self, button: &gtk::ToggleButton) {
+        let imp = self.imp();
+        let active = button.is_active();
+        if active {
+            imp.itemlist.set_model(imp.episode_selection.get());
+            imp.itemlist.add_css_class("selection-mode");
+        } else {
+            imp.itemlist.set_model(Some(&imp.selection));
+            imp.itemlist.remove_css_class("selection-mode");
+        }
+        imp.itemlist.set_single_click_activate(!active);
+        imp.selection_bar.reveal(active);
+    }
+
     #[template_callback]
     fn on_rightbutton_clicked(&self) {
         self.scroll_controls_anime::<true>();
@@ -1388,7 +1508,7 @@ impl ItemPage {
             return;
         };
 
-        let item = TuItem::from_simple(season.to_owned());
+        let item = TuItem::from_simple(season.to_owned(), &self.client());
         item.activate(self);
     }
 }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::subclass::prelude::*;
 use gettextrs::gettext;
 use gtk::{
@@ -15,7 +17,7 @@ use super::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
     },
     utils::{
         spawn,
@@ -26,6 +28,7 @@ use crate::{
 mod imp {
     use std::cell::{
         Cell,
+        OnceCell,
         RefCell,
     };
 
@@ -50,6 +53,7 @@ mod imp {
         pub is_playable: Cell<bool>,
         #[property(get, set, default = false)]
         pub played: Cell<bool>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
     }
 
     #[glib::object_subclass]
@@ -101,6 +105,20 @@ impl ItemActionsBox {
             .build()
     }
 
+    /// Sets the account of the item, which the actions go to. Set once, the
+    /// page showing the item sets it right away.
+    pub fn set_client(&self, client: Arc<JellyfinClient>) {
+        let _ = self.imp().client.set(client);
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ItemActionsBox has a client before its actions are used")
+            .clone()
+    }
+
     fn target_id(&self) -> Option<String> {
         self.id().or_else(|| self.series_id())
     }
@@ -110,10 +128,11 @@ impl ItemActionsBox {
         let id = self.target_id();
 
         if let Some(id) = id {
+            let client = self.client();
             let result = if btn.is_active() {
-                spawn_tokio(async move { JELLYFIN_CLIENT.like(&id).await }).await
+                spawn_tokio(async move { client.like(&id).await }).await
             } else {
-                spawn_tokio(async move { JELLYFIN_CLIENT.unlike(&id).await }).await
+                spawn_tokio(async move { client.unlike(&id).await }).await
             };
 
             match result {
@@ -140,7 +159,7 @@ impl ItemActionsBox {
                     };
                     let id = obj.target_id();
                     if let Some(id) = id {
-                        let dialog = MetadataDialog::new(&id);
+                        let dialog = MetadataDialog::new(&id, obj.client());
                         insert_editm_dialog!(obj, dialog);
                     }
                 }
@@ -157,7 +176,7 @@ impl ItemActionsBox {
                     };
                     let id = obj.target_id();
                     if let Some(id) = id {
-                        let dialog = ImageDialog::new(&id, &obj.item_type());
+                        let dialog = ImageDialog::new(&id, &obj.item_type(), obj.client());
                         insert_editm_dialog!(obj, dialog);
                     }
                 }
@@ -173,12 +192,13 @@ impl ItemActionsBox {
                             let id = obj.target_id();
                             let series_id = obj.series_id();
                             if let Some(id) = id {
+                                let client = obj.client();
                                 spawn(glib::clone!(
                                     #[weak]
                                     obj,
                                     async move {
                                         match spawn_tokio(async move {
-                                            JELLYFIN_CLIENT.set_as_unplayed(&id, series_id).await
+                                            client.set_as_unplayed(&id, series_id).await
                                         })
                                         .await
                                         {
@@ -206,12 +226,13 @@ impl ItemActionsBox {
                             let id = obj.target_id();
                             let series_id = obj.series_id();
                             if let Some(id) = id {
+                                let client = obj.client();
                                 spawn(glib::clone!(
                                     #[weak]
                                     obj,
                                     async move {
                                         match spawn_tokio(async move {
-                                            JELLYFIN_CLIENT.set_as_played(&id, series_id).await
+                                            client.set_as_played(&id, series_id).await
                                         })
                                         .await
                                         {
@@ -1,33 +1,22 @@
 use strsim::jaro_winkler;
 
-use crate::ui::{
-    models::SETTINGS,
-    provider::descriptor::DescriptorType,
+use crate::{
+    client::{
+        jellyfin_client::JellyfinClient,
+        structs::MediaSource,
+    },
+    ui::{
+        models::SETTINGS,
+        provider::descriptor::{
+            VersionChoice,
+            choose_version,
+        },
+    },
 };
 
-pub fn make_video_version_choice_from_filter(dl_list: Vec<String>) -> Option<usize> {
-    let descriptors = crate::ui::models::SETTINGS.preferred_version_descriptors();
-    let mut current_list: Vec<_> = dl_list.iter().collect();
-
-    for descriptor in descriptors {
-        let content = &descriptor.content.to_lowercase();
-        let previous_list = current_list.to_owned();
-
-        current_list.retain(|&name| match descriptor.type_ {
-            DescriptorType::String => name.to_lowercase().contains(content),
-            DescriptorType::Regex => {
-                regex::Regex::new(content).is_ok_and(|re| re.is_match(&name.to_lowercase()))
-            }
-        });
-
-        if current_list.is_empty() {
-            current_list = previous_list; // Revert to the previous list
-        }
-    }
-
-    current_list
-        .first()
-        .and_then(|first_item| dl_list.iter().position(|name| name == *first_item))
+/// Version picked by the preferred version rules
+pub fn make_video_version_choice(media_sources: &[MediaSource]) -> Option<VersionChoice> {
+    choose_version(&SETTINGS.preferred_version_descriptors(), media_sources)
 }
 
 pub fn make_video_version_choice_from_matcher(
@@ -46,8 +35,11 @@ pub fn make_video_version_choice_from_matcher(
     best_match_index
 }
 
-pub fn make_subtitle_version_choice(lang_list: Vec<(i64, String)>) -> Option<(i64, usize)> {
-    let lang = match SETTINGS.mpv_subtitle_preferred_lang() {
+/// Subtitle in the language preferred on the server of `client`
+pub fn make_subtitle_version_choice(
+    lang_list: Vec<(i64, String)>, client: &JellyfinClient,
+) -> Option<(i64, usize)> {
+    let lang = match SETTINGS.mpv_subtitle_preferred_lang(client) {
         1 => "English",
         2 => "Chinese Simplified",
         3 => "Japanese",
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use gettextrs::gettext;
 use glib::Object;
 use gtk::{
@@ -11,7 +13,7 @@ use super::utils::GlobalToast;
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
         structs::*,
     },
     fraction,
@@ -22,6 +24,11 @@ use crate::{
     },
 };
 mod imp {
+    use std::{
+        cell::OnceCell,
+        sync::Arc,
+    };
+
     use glib::subclass::InitializingObject;
     use gtk::{
         CompositeTemplate,
@@ -29,7 +36,13 @@ mod imp {
         subclass::prelude::*,
     };
 
-    use crate::ui::widgets::hortu_scrolled::HortuScrolled;
+    use crate::{
+        client::{
+            jellyfin_client::JellyfinClient,
+            registry::CLIENTS,
+        },
+        ui::widgets::hortu_scrolled::HortuScrolled,
+    };
 
     // Object holding the state
     #[derive(CompositeTemplate, Default)]
@@ -51,6 +64,8 @@ mod imp {
         pub tvhortu: TemplateChild<HortuScrolled>,
         #[template_child]
         pub stack: TemplateChild<gtk::Stack>,
+        /// Account the page shows, the page is rebuilt when switching accounts
+        pub client: OnceCell<Arc<JellyfinClient>>,
     }
 
     #[glib::object_subclass]
@@ -71,6 +86,7 @@ mod imp {
     impl ObjectImpl for LikedPage {
         fn constructed(&self) {
             self.parent_constructed();
+            let _ = self.client.set(CLIENTS.active());
             let obj = self.obj();
             obj.update();
         }
@@ -103,6 +119,14 @@ impl LikedPage {
         Object::builder().build()
     }
 
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("LikedPage is constructed")
+            .clone()
+    }
+
     pub fn update(&self) {
         spawn(glib::clone!(
             #[weak(rename_to = obj)]
@@ -158,8 +182,10 @@ impl LikedPage {
 
         let type_ = types.to_owned();
 
+        let client = self.client();
+        let fetch_client = client.clone();
         let results = match spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            fetch_client
                 .get_favourite(&types, 0, 12, "SortName", "Ascending", &Default::default())
                 .await
         })
@@ -177,36 +203,38 @@ impl LikedPage {
             return;
         }
 
-        hortu.set_items(results.items);
+        hortu.set_items(results.items, &client);
 
         hortu.connect_morebutton(glib::clone!(
             #[weak(rename_to = obj)]
             self,
             move |_| {
                 let tag = format!("{} {}", "Favourite", type_);
-                let page = crate::ui::widgets::single_grid::SingleGrid::new();
+                let page = crate::ui::widgets::single_grid::SingleGrid::new(client.to_owned());
                 let type_clone1 = type_.to_owned();
                 let type_clone2 = type_.to_owned();
-                page.connect_sort_changed_tokio(move |sort_by, sort_order, filters_list| {
-                    let type_clone1 = type_clone1.to_owned();
-                    async move {
-                        JELLYFIN_CLIENT
-                            .get_favourite(
-                                &type_clone1,
-                                0,
-                                50,
-                                &sort_by,
-                                &sort_order,
-                                &filters_list,
-                            )
-                            .await
-                    }
-                });
+                page.connect_sort_changed_tokio(
+                    move |client, sort_by, sort_order, filters_list| {
+                        let type_clone1 = type_clone1.to_owned();
+                        async move {
+                            client
+                                .get_favourite(
+                                    &type_clone1,
+                                    0,
+                                    50,
+                                    &sort_by,
+                                    &sort_order,
+                                    &filters_list,
+                                )
+                                .await
+                        }
+                    },
+                );
                 page.connect_end_edge_overshot_tokio(
-                    move |sort_by, sort_order, n_items, filters_list| {
+                    move |client, sort_by, sort_order, n_items, filters_list| {
                         let type_clone2 = type_clone2.to_owned();
                         async move {
-                            JELLYFIN_CLIENT
+                            client
                                 .get_favourite(
                                     &type_clone2,
                                     n_items,
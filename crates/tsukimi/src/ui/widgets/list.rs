@@ -16,10 +16,7 @@ use super::{
         CardShape,
     },
 };
-use crate::{
-    client::jellyfin_client::JELLYFIN_CLIENT,
-    ui::provider::tu_item::TuItem,
-};
+use crate::ui::provider::tu_item::TuItem;
 mod imp {
 
     use std::cell::OnceCell;
@@ -111,12 +108,12 @@ impl ListPage {
         let stack = imp.stack.get();
 
         if &collection_type == "livetv" {
-            let page = SingleGrid::new();
-            page.connect_sort_changed_tokio(move |_, _, _| async move {
-                JELLYFIN_CLIENT.get_channels_list(0).await
+            let page = SingleGrid::new(item.client());
+            page.connect_sort_changed_tokio(move |client, _, _, _| async move {
+                client.get_channels_list(0).await
             });
-            page.connect_end_edge_overshot_tokio(move |_, _, n_items, _| async move {
-                JELLYFIN_CLIENT.get_channels_list(n_items).await
+            page.connect_end_edge_overshot_tokio(move |client, _, _, n_items, _| async move {
+                client.get_channels_list(n_items).await
             });
             stack.add_titled(&page, Some("channels"), &gettext("Channels"));
             return;
@@ -135,7 +132,7 @@ impl ListPage {
         ];
 
         for (name, title, list_type) in pages {
-            let page = SingleGrid::new();
+            let page = SingleGrid::new(item.client());
             page.set_list_type(list_type);
             let is_resume = list_type == ListType::Resume;
             page.set_card_options(CardOptions {
@@ -150,16 +147,16 @@ impl ListPage {
             page.set_is_resume(is_resume);
             let id_clone1 = id.to_owned();
             let include_item_types_clone1 = include_item_types.to_owned();
-            page.connect_sort_changed_tokio(move |sort_by, sort_order, filters_list| {
+            page.connect_sort_changed_tokio(move |client, sort_by, sort_order, filters_list| {
                 let id_clone1 = id_clone1.to_owned();
                 let include_item_types_clone1 = include_item_types_clone1.to_owned();
                 async move {
                     if list_type == ListType::Folder {
-                        JELLYFIN_CLIENT
+                        client
                             .get_folder_include(&id_clone1, &sort_by, &sort_order, 0, &filters_list)
                             .await
                     } else {
-                        JELLYFIN_CLIENT
+                        client
                             .get_list(
                                 &id_clone1,
                                 0,
@@ -178,12 +175,12 @@ impl ListPage {
 
             if list_type != ListType::Resume {
                 page.connect_end_edge_overshot_tokio(
-                    move |sort_by, sort_order, n_items, filters_list| {
+                    move |client, sort_by, sort_order, n_items, filters_list| {
                         let id_clone2 = id_clone2.to_owned();
                         let include_item_types_clone2 = include_item_types_clone2.to_owned();
                         async move {
                             if list_type == ListType::Folder {
-                                JELLYFIN_CLIENT
+                                client
                                     .get_folder_include(
                                         &id_clone2,
                                         &sort_by,
@@ -193,7 +190,7 @@ impl ListPage {
                                     )
                                     .await
                             } else {
-                                JELLYFIN_CLIENT
+                                client
                                     .get_list(
                                         &id_clone2,
                                         n_items,
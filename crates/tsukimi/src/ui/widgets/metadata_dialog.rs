@@ -1,7 +1,14 @@
+use std::sync::Arc;
+
 use adw::{
     prelude::*,
     subclass::prelude::*,
 };
+use chrono::{
+    DateTime,
+    NaiveDate,
+    Utc,
+};
 use gettextrs::gettext;
 use gtk::{
     SpinButton,
@@ -13,14 +20,40 @@ use super::utils::GlobalToast;
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
-        structs::SimpleListItem,
+        jellyfin_client::JellyfinClient,
+        structs::{
+            ItemEditInfo,
+            NameIdPair,
+            PersonInfo,
+        },
     },
     utils::{
         spawn,
         spawn_tokio,
     },
 };
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Provider ids every video item gets an entry for, even when it has no value yet
+const DEFAULT_PROVIDERS: [&str; 3] = ["Tmdb", "Imdb", "Tvdb"];
+
+const PERSON_TYPES: [&str; 6] = [
+    "Actor",
+    "Director",
+    "Writer",
+    "Producer",
+    "GuestStar",
+    "Composer",
+];
+
+#[derive(Clone, Copy)]
+pub enum NameList {
+    Genres,
+    Tags,
+    Studios,
+}
+
 mod imp {
     use std::cell::{
         OnceCell,
@@ -32,11 +65,9 @@ mod imp {
         CompositeTemplate,
         glib,
     };
-    use serde_json::Value;
 
     use super::*;
     use crate::{
-        client::structs::SimpleListItem,
         ui::{
             provider::IS_ADMIN,
             widgets::item::dt,
@@ -44,12 +75,13 @@ mod imp {
         utils::spawn,
     };
 
-    #[derive(Debug, Default, CompositeTemplate, glib::Properties)]
+    #[derive(Default, CompositeTemplate, glib::Properties)]
     #[template(resource = "/moe/tsuna/tsukimi/ui/metadata_dialog.ui")]
     #[properties(wrapper_type = super::MetadataDialog)]
     pub struct MetadataDialog {
         #[property(get, set, construct_only)]
         pub id: OnceCell<String>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
         #[template_child]
         pub stack: TemplateChild<gtk::Stack>,
 
@@ -64,23 +96,57 @@ mod imp {
         #[template_child]
         pub title_entry: TemplateChild<adw::EntryRow>,
         #[template_child]
+        pub original_title_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
         pub sorttitle_entry: TemplateChild<adw::EntryRow>,
         #[template_child]
         pub date_entry: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub premiere_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub end_date_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub year_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
         pub overview_entry: TemplateChild<gtk::TextView>,
 
         #[template_child]
-        pub ids_group: TemplateChild<adw::PreferencesGroup>,
+        pub official_rating_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub community_rating_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub critic_rating_entry: TemplateChild<adw::EntryRow>,
+
+        #[template_child]
+        pub genres_list: TemplateChild<gtk::ListBox>,
         #[template_child]
-        pub moviedb_entry: TemplateChild<adw::EntryRow>,
+        pub tags_list: TemplateChild<gtk::ListBox>,
         #[template_child]
-        pub tvdb_entry: TemplateChild<adw::EntryRow>,
+        pub studios_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub people_list: TemplateChild<gtk::ListBox>,
+
+        #[template_child]
+        pub ids_group: TemplateChild<adw::PreferencesGroup>,
         #[template_child]
-        pub imdb_entry: TemplateChild<adw::EntryRow>,
+        pub ids_list: TemplateChild<gtk::ListBox>,
 
         #[template_child]
-        pub lock_check: TemplateChild<gtk::CheckButton>,
+        pub name_lock: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub overview_lock: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub rating_lock: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub genres_lock: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub tags_lock: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub studios_lock: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub people_lock: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub lock_row: TemplateChild<adw::SwitchRow>,
 
         #[template_child]
         pub hour_spin: TemplateChild<gtk::SpinButton>,
@@ -92,7 +158,13 @@ mod imp {
         pub apply_button: TemplateChild<adw::ButtonRow>,
 
         pub timezone: RefCell<Option<glib::DateTime>>,
-        pub value: OnceCell<Value>,
+        pub info: RefCell<Option<ItemEditInfo>>,
+
+        pub genres: RefCell<Vec<String>>,
+        pub tags: RefCell<Vec<String>>,
+        pub studios: RefCell<Vec<NameIdPair>>,
+        pub people: RefCell<Vec<PersonInfo>>,
+        pub provider_entries: RefCell<Vec<(String, adw::EntryRow)>>,
     }
 
     #[glib::object_subclass]
@@ -136,91 +208,201 @@ mod imp {
             ));
         }
 
-        pub fn load_data(&self, metadata: SimpleListItem) {
+        // `LockedFields` names paired with the toggle that controls them
+        pub fn lock_buttons(&self) -> [(&'static str, gtk::ToggleButton); 7] {
+            [
+                ("Name", self.name_lock.get()),
+                ("Overview", self.overview_lock.get()),
+                ("OfficialRating", self.rating_lock.get()),
+                ("Genres", self.genres_lock.get()),
+                ("Tags", self.tags_lock.get()),
+                ("Studios", self.studios_lock.get()),
+                ("Cast", self.people_lock.get()),
+            ]
+        }
+
+        pub fn load_data(&self, info: &ItemEditInfo) {
             self.path_entry
-                .set_subtitle(&metadata.path.unwrap_or("No Data".to_string()));
-            self.title_entry.set_text(&metadata.name);
+                .set_subtitle(info.path.as_deref().unwrap_or(&gettext("No Data")));
+            self.title_entry.set_text(&info.name);
+            self.original_title_entry
+                .set_text(info.original_title.as_deref().unwrap_or_default());
             self.sorttitle_entry
-                .set_text(&metadata.sort_name.unwrap_or_default());
-            self.timezone
-                .replace(metadata.date_created.as_ref().map(|x| {
-                    glib::DateTime::from_iso8601(&x.to_rfc3339(), None)
-                        .unwrap()
-                        .to_local()
-                        .unwrap()
-                }));
-            self.date_entry.set_subtitle(&dt(metadata.date_created));
+                .set_text(info.forced_sort_name.as_deref().unwrap_or_default());
+
+            self.timezone.replace(info.date_created.as_ref().map(|x| {
+                glib::DateTime::from_iso8601(&x.to_rfc3339(), None)
+                    .unwrap()
+                    .to_local()
+                    .unwrap()
+            }));
+            self.date_entry.set_subtitle(&dt(info.date_created));
+            self.premiere_entry
+                .set_text(&format_date(info.premiere_date));
+            self.end_date_entry.set_text(&format_date(info.end_date));
+            self.year_entry.set_text(
+                &info
+                    .production_year
+                    .map(|year| year.to_string())
+                    .unwrap_or_default(),
+            );
+
             self.overview_entry
                 .buffer()
-                .set_text(&metadata.overview.unwrap_or_default());
-            self.lock_check
-                .set_active(metadata.lock_data.unwrap_or_default());
-
-            if metadata.item_type == "MusicAlbum" || metadata.item_type == "Audio" {
-                return;
-            }
-
-            if let Some(provider_ids) = metadata.provider_ids {
-                self.moviedb_entry
-                    .set_text(&provider_ids.tmdb.unwrap_or_default());
-                self.tvdb_entry
-                    .set_text(&provider_ids.tvdb.unwrap_or_default());
-                self.imdb_entry
-                    .set_text(&provider_ids.imdb.unwrap_or_default());
-                self.ids_group.set_visible(true);
+                .set_text(info.overview.as_deref().unwrap_or_default());
+
+            self.official_rating_entry
+                .set_text(info.official_rating.as_deref().unwrap_or_default());
+            self.community_rating_entry.set_text(
+                &info
+                    .community_rating
+                    .map(|rating| rating.to_string())
+                    .unwrap_or_default(),
+            );
+            self.critic_rating_entry.set_text(
+                &info
+                    .critic_rating
+                    .map(|rating| rating.to_string())
+                    .unwrap_or_default(),
+            );
+
+            self.genres.replace(info.genres.to_owned());
+            self.tags.replace(info.tags.to_owned());
+            self.studios.replace(info.studios.to_owned());
+            self.people.replace(info.people.to_owned());
+
+            for (field, button) in self.lock_buttons() {
+                button.set_active(info.is_locked(field));
             }
+            self.lock_row.set_active(info.lock_data);
         }
 
-        pub fn get_edit_data(&self) -> Option<Value> {
-            let value = self.value.get()?;
-
-            let mut value = value.to_owned();
-
-            let title = self.title_entry.text().to_string();
-            if !title.is_empty() {
-                value["Name"] = Value::String(title);
+        pub fn get_edit_data(&self) -> Result<ItemEditInfo, (gtk::Widget, String)> {
+            let Some(mut info) = self.info.borrow().to_owned() else {
+                return Err((self.apply_button.get().upcast(), gettext("No Data")));
+            };
+
+            let title = self.title_entry.text();
+            if title.trim().is_empty() {
+                return Err((
+                    self.title_entry.get().upcast(),
+                    gettext("Title cannot be empty"),
+                ));
             }
-
-            let sort_title = self.sorttitle_entry.text().to_string();
-            if !sort_title.is_empty() {
-                value["SortName"] = Value::String(sort_title);
+            if title.as_str() != info.name {
+                info.name = title.trim().to_string();
             }
-
-            let buffer = self.overview_entry.buffer();
-            let overview = buffer
-                .text(&buffer.start_iter(), &buffer.end_iter(), false)
-                .to_string();
-            if !overview.is_empty() {
-                value["Overview"] = Value::String(overview);
-            }
-
-            let lock_data = self.lock_check.is_active();
-            value["LockData"] = Value::Bool(lock_data);
+            info.original_title = edited(&self.original_title_entry.text(), &info.original_title);
+            info.forced_sort_name = edited(&self.sorttitle_entry.text(), &info.forced_sort_name);
 
             if let Some(date) = self.timezone.borrow().as_ref() {
-                let Ok(date) = date.to_utc().and_then(|x| x.format_iso8601()) else {
-                    println!("Error converting date");
-                    return None;
+                let date = date
+                    .to_utc()
+                    .and_then(|x| x.format_iso8601())
+                    .ok()
+                    .and_then(|x| DateTime::parse_from_rfc3339(&x).ok());
+                let Some(date) = date else {
+                    return Err((
+                        self.date_entry.get().upcast(),
+                        gettext("Invalid date created"),
+                    ));
                 };
-                value["DateCreated"] = Value::String(date.to_string());
+                info.date_created = Some(date.with_timezone(&Utc));
             }
 
-            let tmdb = self.moviedb_entry.text().to_string();
-            if !tmdb.is_empty() {
-                value["ProviderIds"]["Tmdb"] = Value::String(tmdb);
+            let Some(premiere_date) = parse_date(&self.premiere_entry.text(), info.premiere_date)
+            else {
+                return Err((
+                    self.premiere_entry.get().upcast(),
+                    gettext("Release date must look like 2024-01-31"),
+                ));
+            };
+            let Some(end_date) = parse_date(&self.end_date_entry.text(), info.end_date) else {
+                return Err((
+                    self.end_date_entry.get().upcast(),
+                    gettext("End date must look like 2024-01-31"),
+                ));
+            };
+            if let (Some(premiere_date), Some(end_date)) = (premiere_date, end_date)
+                && end_date < premiere_date
+            {
+                return Err((
+                    self.end_date_entry.get().upcast(),
+                    gettext("End date cannot be before the release date"),
+                ));
             }
+            info.premiere_date = premiere_date;
+            info.end_date = end_date;
+
+            let Some(production_year) =
+                parse_number::<i32>(&self.year_entry.text(), 1800.0, 2200.0)
+            else {
+                return Err((
+                    self.year_entry.get().upcast(),
+                    gettext("Production year must be between 1800 and 2200"),
+                ));
+            };
+            info.production_year = production_year;
 
-            let tvdb = self.tvdb_entry.text().to_string();
-            if !tvdb.is_empty() {
-                value["ProviderIds"]["Tvdb"] = Value::String(tvdb);
+            let buffer = self.overview_entry.buffer();
+            info.overview = edited(
+                &buffer.text(&buffer.start_iter(), &buffer.end_iter(), false),
+                &info.overview,
+            );
+
+            info.official_rating =
+                edited(&self.official_rating_entry.text(), &info.official_rating);
+            let Some(community_rating) =
+                parse_number::<f32>(&self.community_rating_entry.text(), 0.0, 10.0)
+            else {
+                return Err((
+                    self.community_rating_entry.get().upcast(),
+                    gettext("Community rating must be between 0 and 10"),
+                ));
+            };
+            info.community_rating = community_rating;
+            let Some(critic_rating) =
+                parse_number::<f32>(&self.critic_rating_entry.text(), 0.0, 100.0)
+            else {
+                return Err((
+                    self.critic_rating_entry.get().upcast(),
+                    gettext("Critic rating must be between 0 and 100"),
+                ));
+            };
+            info.critic_rating = critic_rating;
+
+            info.genres = self.genres.borrow().to_owned();
+            info.tags = self.tags.borrow().to_owned();
+            info.studios = self.studios.borrow().to_owned();
+            info.people = self.people.borrow().to_owned();
+
+            for (provider, entry) in self.provider_entries.borrow().iter() {
+                // Ids the user didn't touch are kept as they are, even if they don't match
+                let text = entry.text();
+                let loaded = info.provider_ids.get(provider).map(String::as_str);
+                if text.as_str() == loaded.unwrap_or_default() {
+                    continue;
+                }
+                let value = text.trim().to_string();
+                if value.is_empty() {
+                    info.provider_ids.remove(provider);
+                    continue;
+                }
+                if !is_valid_provider_id(provider, &value) {
+                    return Err((
+                        entry.to_owned().upcast(),
+                        format!("{}: {}", gettext("Invalid id"), provider_label(provider)),
+                    ));
+                }
+                info.provider_ids.insert(provider.to_owned(), value);
             }
 
-            let imdb = self.imdb_entry.text().to_string();
-            if !imdb.is_empty() {
-                value["ProviderIds"]["IMDB"] = Value::String(imdb);
+            for (field, button) in self.lock_buttons() {
+                info.set_locked(field, button.is_active());
             }
+            info.lock_data = self.lock_row.is_active();
 
-            Some(value)
+            Ok(info)
         }
     }
 }
@@ -233,20 +415,34 @@ glib::wrapper! {
 
 #[template_callbacks]
 impl MetadataDialog {
-    pub fn new(id: &str) -> Self {
-        glib::Object::builder().property("id", id).build()
+    pub fn new(id: &str, client: Arc<JellyfinClient>) -> Self {
+        let dialog: Self = glib::Object::builder().property("id", id).build();
+        let _ = dialog.imp().client.set(client);
+        dialog
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("MetadataDialog is created with a client")
+            .clone()
     }
 
     async fn get_data(&self) {
         let id = self.id();
-        match spawn_tokio(async move { JELLYFIN_CLIENT.get_edit_info(&id).await }).await {
-            Ok(metadata) => {
-                self.imp().stack.set_visible_child_name("page");
-                let _ = self.imp().value.set(metadata.to_owned());
-                let Ok(item) = serde_json::from_value::<SimpleListItem>(metadata) else {
-                    return;
-                };
-                self.imp().load_data(item);
+        let client = self.client();
+        match spawn_tokio(async move { client.get_edit_info(&id).await }).await {
+            Ok(info) => {
+                let imp = self.imp();
+                imp.stack.set_visible_child_name("page");
+                imp.load_data(&info);
+                self.set_provider_ids(&info);
+                imp.info.replace(Some(info));
+                for list in [NameList::Genres, NameList::Tags, NameList::Studios] {
+                    self.refresh_names(list);
+                }
+                self.refresh_people();
             }
             Err(e) => {
                 self.toast(e.to_user_facing());
@@ -254,6 +450,288 @@ impl MetadataDialog {
         }
     }
 
+    fn set_provider_ids(&self, info: &ItemEditInfo) {
+        let imp = self.imp();
+
+        let mut providers: Vec<String> = info.provider_ids.keys().cloned().collect();
+        if !matches!(
+            info.item_type.as_str(),
+            "MusicAlbum" | "Audio" | "MusicArtist"
+        ) {
+            for provider in DEFAULT_PROVIDERS {
+                if !providers.iter().any(|p| p.eq_ignore_ascii_case(provider)) {
+                    providers.push(provider.to_string());
+                }
+            }
+        }
+
+        let mut entries = Vec::new();
+        for provider in providers {
+            let entry = adw::EntryRow::builder()
+                .title(provider_label(&provider))
+                .text(
+                    info.provider_ids
+                        .get(&provider)
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                )
+                .build();
+            imp.ids_list.append(&entry);
+            entries.push((provider, entry));
+        }
+
+        imp.ids_group.set_visible(!entries.is_empty());
+        imp.provider_entries.replace(entries);
+    }
+
+    fn names(&self, list: NameList) -> Vec<String> {
+        let imp = self.imp();
+        match list {
+            NameList::Genres => imp.genres.borrow().to_owned(),
+            NameList::Tags => imp.tags.borrow().to_owned(),
+            NameList::Studios => imp
+                .studios
+                .borrow()
+                .iter()
+                .map(|studio| studio.name.to_owned())
+                .collect(),
+        }
+    }
+
+    fn set_names(&self, list: NameList, names: Vec<String>) {
+        let imp = self.imp();
+        match list {
+            NameList::Genres => {
+                imp.genres.replace(names);
+            }
+            NameList::Tags => {
+                imp.tags.replace(names);
+            }
+            NameList::Studios => {
+                // Studios that stay keep their server id
+                let old = imp.studios.take();
+                let studios = names
+                    .into_iter()
+                    .map(|name| {
+                        old.iter()
+                            .find(|studio| studio.name == name)
+                            .cloned()
+                            .unwrap_or(NameIdPair { name, id: None })
+                    })
+                    .collect();
+                imp.studios.replace(studios);
+            }
+        }
+        self.refresh_names(list);
+    }
+
+    fn refresh_names(&self, list: NameList) {
+        let imp = self.imp();
+        let (list_box, add_title) = match list {
+            NameList::Genres => (imp.genres_list.get(), gettext("Add Genre")),
+            NameList::Tags => (imp.tags_list.get(), gettext("Add Tag")),
+            NameList::Studios => (imp.studios_list.get(), gettext("Add Studio")),
+        };
+        list_box.remove_all();
+
+        for (index, name) in self.names(list).into_iter().enumerate() {
+            let row = adw::ActionRow::builder()
+                .use_markup(false)
+                .title(name)
+                .build();
+            let remove_button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(gettext("Remove"))
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            remove_button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    let mut names = obj.names(list);
+                    names.remove(index);
+                    obj.set_names(list, names);
+                }
+            ));
+            row.add_suffix(&remove_button);
+            list_box.append(&row);
+        }
+
+        let add_row = adw::EntryRow::builder()
+            .title(add_title)
+            .show_apply_button(true)
+            .build();
+        add_row.connect_apply(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |entry| {
+                let name = entry.text().trim().to_string();
+                let mut names = obj.names(list);
+                if name.is_empty() || names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                    return;
+                }
+                names.push(name);
+                obj.set_names(list, names);
+            }
+        ));
+        list_box.append(&add_row);
+    }
+
+    fn refresh_people(&self) {
+        let imp = self.imp();
+        imp.people_list.remove_all();
+
+        for (index, person) in imp.people.borrow().iter().enumerate() {
+            let subtitle = [
+                person.person_type.as_deref().map(person_type_label),
+                person.role.to_owned().filter(|role| !role.is_empty()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+            let row = adw::ActionRow::builder()
+                .use_markup(false)
+                .title(&person.name)
+                .subtitle(subtitle)
+                .activatable(true)
+                .build();
+            row.connect_activated(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.edit_person(Some(index));
+                }
+            ));
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(gettext("Remove"))
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            remove_button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.imp().people.borrow_mut().remove(index);
+                    obj.refresh_people();
+                }
+            ));
+            row.add_suffix(&remove_button);
+            imp.people_list.append(&row);
+        }
+    }
+
+    #[template_callback]
+    fn on_add_person(&self) {
+        self.edit_person(None);
+    }
+
+    fn edit_person(&self, index: Option<usize>) {
+        let person = index
+            .and_then(|index| self.imp().people.borrow().get(index).cloned())
+            .unwrap_or_else(|| PersonInfo {
+                person_type: Some(PERSON_TYPES[0].to_string()),
+                ..Default::default()
+            });
+
+        let name_entry = adw::EntryRow::builder()
+            .title(gettext("Name"))
+            .text(&person.name)
+            .build();
+        let role_entry = adw::EntryRow::builder()
+            .title(gettext("Role"))
+            .text(person.role.as_deref().unwrap_or_default())
+            .build();
+
+        let mut types: Vec<String> = PERSON_TYPES.iter().map(|t| t.to_string()).collect();
+        if let Some(person_type) = &person.person_type
+            && !types.contains(person_type)
+        {
+            types.push(person_type.to_owned());
+        }
+        let type_labels: Vec<String> = types.iter().map(|t| person_type_label(t)).collect();
+        let type_row = adw::ComboRow::builder()
+            .title(gettext("Type"))
+            .model(&gtk::StringList::new(
+                &type_labels.iter().map(String::as_str).collect::<Vec<_>>(),
+            ))
+            .selected(
+                person
+                    .person_type
+                    .as_ref()
+                    .and_then(|t| types.iter().position(|x| x == t))
+                    .unwrap_or(0) as u32,
+            )
+            .build();
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&name_entry);
+        list.append(&type_row);
+        list.append(&role_entry);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(if index.is_some() {
+                gettext("Edit Person")
+            } else {
+                gettext("Add Person")
+            })
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("save", &gettext("Save"))]);
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_response_enabled("save", !person.name.is_empty());
+        dialog.set_close_response("cancel");
+
+        name_entry.connect_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                dialog.set_response_enabled("save", !entry.text().trim().is_empty());
+            }
+        ));
+
+        dialog.connect_response(
+            Some("save"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                name_entry,
+                #[weak]
+                role_entry,
+                #[weak]
+                type_row,
+                move |_, _| {
+                    let mut person = person.to_owned();
+                    let name = name_entry.text();
+                    if name.as_str() != person.name {
+                        person.name = name.trim().to_string();
+                    }
+                    person.role = edited(&role_entry.text(), &person.role);
+                    person.person_type = types.get(type_row.selected() as usize).cloned();
+
+                    {
+                        let mut people = obj.imp().people.borrow_mut();
+                        match index {
+                            Some(index) => people[index] = person,
+                            None => people.push(person),
+                        }
+                    }
+                    obj.refresh_people();
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
     #[template_callback]
     fn on_day_selected(&self, calender: gtk::Calendar) {
         let date_time = self.get_time();
@@ -274,8 +752,33 @@ impl MetadataDialog {
 
     #[template_callback]
     fn on_apply_button_clicked(&self) {
-        let Some(value) = self.imp().get_edit_data() else {
-            return;
+        let imp = self.imp();
+        for entry in [
+            imp.title_entry.get(),
+            imp.premiere_entry.get(),
+            imp.end_date_entry.get(),
+            imp.year_entry.get(),
+            imp.community_rating_entry.get(),
+            imp.critic_rating_entry.get(),
+        ]
+        .into_iter()
+        .chain(
+            imp.provider_entries
+                .borrow()
+                .iter()
+                .map(|(_, entry)| entry.to_owned()),
+        ) {
+            entry.remove_css_class("error");
+        }
+
+        let info = match imp.get_edit_data() {
+            Ok(info) => info,
+            Err((widget, message)) => {
+                widget.add_css_class("error");
+                widget.grab_focus();
+                self.toast(message);
+                return;
+            }
         };
 
         let id = self.id();
@@ -302,12 +805,11 @@ impl MetadataDialog {
                         #[strong]
                         id,
                         #[strong]
-                        value,
+                        info,
                         async move {
-                            match spawn_tokio(
-                                async move { JELLYFIN_CLIENT.post_item(&id, value).await },
-                            )
-                            .await
+                            let client = obj.client();
+                            match spawn_tokio(async move { client.post_item(&id, &info).await })
+                                .await
                             {
                                 Ok(_) => {
                                     obj.toast(gettext("Success"));
@@ -365,3 +867,81 @@ impl MetadataDialog {
         }
     }
 }
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+// Fields the user didn't touch keep the value the server sent
+fn edited(text: &str, loaded: &Option<String>) -> Option<String> {
+    if text == loaded.as_deref().unwrap_or_default() {
+        return loaded.to_owned();
+    }
+    non_empty(text)
+}
+
+// Release dates are stored as UTC midnight, so show them in UTC to avoid shifting a day
+fn format_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(|date| date.format(DATE_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+// `None` when the text is not a date, `Some(None)` when it was cleared.
+// An unchanged day keeps the original time of day.
+fn parse_date(text: &str, original: Option<DateTime<Utc>>) -> Option<Option<DateTime<Utc>>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(None);
+    }
+    let date = NaiveDate::parse_from_str(text, DATE_FORMAT).ok()?;
+    if let Some(original) = original
+        && original.date_naive() == date
+    {
+        return Some(Some(original));
+    }
+    Some(Some(date.and_hms_opt(0, 0, 0)?.and_utc()))
+}
+
+// `None` when the text is not a number in `min..=max`, `Some(None)` when it was cleared
+fn parse_number<T>(text: &str, min: f64, max: f64) -> Option<Option<T>>
+where
+    T: std::str::FromStr + Into<f64> + Copy,
+{
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(None);
+    }
+    let value = text.parse::<T>().ok()?;
+    (min..=max).contains(&value.into()).then_some(Some(value))
+}
+
+fn is_valid_provider_id(provider: &str, value: &str) -> bool {
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    match provider.to_lowercase().as_str() {
+        "imdb" => value.strip_prefix("tt").is_some_and(digits),
+        "tmdb" | "tvdb" => digits(value),
+        _ => true,
+    }
+}
+
+fn provider_label(provider: &str) -> String {
+    match provider.to_lowercase().as_str() {
+        "tmdb" => gettext("TheMovieDb Id"),
+        "tvdb" => gettext("TheTVDb Id"),
+        "imdb" => gettext("IMDb Id"),
+        _ => provider.to_string(),
+    }
+}
+
+fn person_type_label(person_type: &str) -> String {
+    match person_type {
+        "Actor" => gettext("Actor"),
+        "Director" => gettext("Director"),
+        "Writer" => gettext("Writer"),
+        "Producer" => gettext("Producer"),
+        "GuestStar" => gettext("Guest Star"),
+        "Composer" => gettext("Composer"),
+        other => other.to_string(),
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::sync::Arc;
 
 use adw::subclass::prelude::*;
 use gettextrs::gettext;
@@ -12,7 +12,7 @@ use super::utils::GlobalToast;
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
     },
     utils::spawn_tokio,
 };
@@ -28,12 +28,13 @@ mod imp {
     use super::*;
     use crate::utils::spawn;
 
-    #[derive(Debug, Default, CompositeTemplate, glib::Properties)]
+    #[derive(Default, CompositeTemplate, glib::Properties)]
     #[template(resource = "/moe/tsuna/tsukimi/ui/missing_episodes.ui")]
     #[properties(wrapper_type = super::MissingEpisodesDialog)]
     pub struct MissingEpisodesDialog {
         #[property(get, set, construct_only)]
         pub id: OnceCell<String>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
 
         #[template_child]
         pub stack: TemplateChild<gtk::Stack>,
@@ -98,8 +99,18 @@ impl MissingEpisodesDialog {
     const LOADING_STACK_PAGE: &'static str = "loading";
     const VIEW_STACK_PAGE: &'static str = "view";
 
-    pub fn new(id: &str) -> Self {
-        glib::Object::builder().property("id", id).build()
+    pub fn new(id: &str, client: Arc<JellyfinClient>) -> Self {
+        let dialog: Self = glib::Object::builder().property("id", id).build();
+        let _ = dialog.imp().client.set(client);
+        dialog
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("MissingEpisodesDialog is created with a client")
+            .clone()
     }
 
     pub fn loading_page(&self) {
@@ -122,8 +133,9 @@ impl MissingEpisodesDialog {
         let special_checked = self.imp().specials_check.is_active();
         let upcoming_checked = self.imp().upcoming_check.is_active();
 
+        let client = self.client();
         let items = match spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            client
                 .get_show_missing(&id, special_checked, upcoming_checked)
                 .await
         })
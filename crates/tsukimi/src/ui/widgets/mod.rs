@@ -10,6 +10,7 @@ pub mod filter_panel;
 pub mod fix;
 pub mod fixed_bin;
 pub mod home;
+pub mod home_layout_dialog;
 mod hor_controls;
 pub mod horbu_scrolled;
 pub mod hortu_scrolled;
@@ -31,14 +32,18 @@ pub mod menu_info;
 pub mod metadata_dialog;
 pub mod missing_episodes_dialog;
 pub mod music_album;
+pub mod music_queue;
 pub mod other;
 pub mod picture_loader;
 pub mod player_toolbar;
 pub mod refresh_dialog;
+pub mod remote_control_dialog;
 pub mod scale_revealer;
 pub mod search;
+pub mod selection_bar;
 pub mod server_action_row;
 pub mod server_panel;
+pub mod server_settings_page;
 pub mod single_grid;
 pub mod smooth_scale;
 pub mod song_widget;
@@ -49,6 +54,7 @@ pub mod tu_list_item;
 pub mod tu_overview_item;
 pub mod tuview_scrolled;
 pub mod utils;
+pub mod watch_stats_dialog;
 pub mod window;
 
 pub use episode_switcher::EpisodeSwitcher;
@@ -24,7 +24,6 @@ use crate::{
     bing_song_model,
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
         structs::{
             List,
             SongWidgetView,
@@ -172,6 +171,7 @@ impl AlbumPage {
 
         let imp = self.imp();
 
+        imp.actionbox.set_client(item.client());
         imp.actionbox.set_id(Some(item.id()));
         imp.actionbox.set_item_type(item.item_type());
 
@@ -221,11 +221,13 @@ impl AlbumPage {
         let item = self.item();
         let view_type = self.view_type();
         let id = item.id();
+        let client = item.client();
+        let fetch_client = client.clone();
 
         let mut events = fetch_with_cache(
             &format!("audio_{}", item.id()),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_songs(&id).await },
+            async move { fetch_client.get_songs(&id).await },
         )
         .await;
 
@@ -253,7 +255,7 @@ impl AlbumPage {
                         songs.items.sort_by_key(|song| song.index_number);
                     }
                     for song in songs.items {
-                        let item = TuItem::from_simple(song);
+                        let item = TuItem::from_simple(song, &client);
                         let parent_index_number = if view_type == SongWidgetView::MusicAlbumItem {
                             item.parent_index_number()
                         } else {
@@ -350,14 +352,16 @@ impl AlbumPage {
         let id = self.item().id();
         let artist_id = self.item().albumartist_id();
         let types = types.to_string();
+        let client = self.item().client();
+        let fetch_client = client.clone();
 
         let mut events = fetch_with_cache(
             &format!("item_{types}_{id}"),
             CachePolicy::ReadCacheAndRefresh,
             async move {
                 match types.as_str() {
-                    "Recommend" => JELLYFIN_CLIENT.get_similar(&id).await,
-                    "More From" => JELLYFIN_CLIENT.get_artist_albums(&id, &artist_id).await,
+                    "Recommend" => fetch_client.get_similar(&id).await,
+                    "More From" => fetch_client.get_artist_albums(&id, &artist_id).await,
                     _ => Ok(List::default()),
                 }
             },
@@ -373,7 +377,7 @@ impl AlbumPage {
                     }
 
                     hortu.set_visible(true);
-                    hortu.set_items(data.items);
+                    hortu.set_items(data.items, &client);
                 }
                 CacheEvent::Error(e) => {
                     self.toast(e.to_user_facing());
@@ -0,0 +1,372 @@
+use std::cell::{
+    OnceCell,
+    RefCell,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    gdk::DragAction,
+    gio,
+    glib,
+    template_callbacks,
+};
+
+use crate::{
+    alert_dialog,
+    client::error::UserFacingError,
+    gstl::player::MusicPlayer,
+    ui::{
+        GlobalToast,
+        provider::core_song::CoreSong,
+        widgets::song_widget::State,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/music_queue.ui")]
+    pub struct MusicQueue {
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub listbox: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub save_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub clear_button: TemplateChild<gtk::Button>,
+
+        pub player: OnceCell<MusicPlayer>,
+        pub model_handler: RefCell<Option<(gio::ListStore, glib::SignalHandlerId)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MusicQueue {
+        const NAME: &'static str = "MusicQueue";
+        type Type = super::MusicQueue;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for MusicQueue {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().update_state(0);
+        }
+
+        fn dispose(&self) {
+            if let Some((model, handler)) = self.model_handler.take() {
+                model.disconnect(handler);
+            }
+        }
+    }
+
+    impl WidgetImpl for MusicQueue {}
+    impl BinImpl for MusicQueue {}
+}
+
+glib::wrapper! {
+    /// Tracks the music player goes through, in the order it plays them.
+    pub struct MusicQueue(ObjectSubclass<imp::MusicQueue>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for MusicQueue {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+#[template_callbacks]
+impl MusicQueue {
+    pub fn set_player(&self, player: &MusicPlayer) {
+        if self.imp().player.set(player.to_owned()).is_err() {
+            return;
+        }
+        player.connect_active_model_notify(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |player| {
+                obj.bind_model(player.active_model());
+            }
+        ));
+        self.bind_model(player.active_model());
+    }
+
+    fn player(&self) -> Option<&MusicPlayer> {
+        self.imp().player.get()
+    }
+
+    fn bind_model(&self, model: Option<gio::ListStore>) {
+        let imp = self.imp();
+        if let Some((model, handler)) = imp.model_handler.take() {
+            model.disconnect(handler);
+        }
+
+        imp.listbox.bind_model(
+            model.as_ref(),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[upgrade_or_panic]
+                move |object| {
+                    let song = object
+                        .downcast_ref::<CoreSong>()
+                        .expect("Queue items are songs");
+                    obj.create_row(song).upcast()
+                }
+            ),
+        );
+
+        let Some(model) = model else {
+            self.update_state(0);
+            return;
+        };
+        self.update_state(model.n_items());
+        let handler = model.connect_items_changed(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |model, _, _, _| {
+                obj.update_state(model.n_items());
+            }
+        ));
+        imp.model_handler.replace(Some((model, handler)));
+    }
+
+    fn update_state(&self, n_items: u32) {
+        let imp = self.imp();
+        imp.stack
+            .set_visible_child_name(if n_items == 0 { "empty" } else { "list" });
+        imp.save_button.set_sensitive(n_items > 0);
+        imp.clear_button.set_sensitive(n_items > 1);
+    }
+
+    fn create_row(&self, song: &CoreSong) -> adw::ActionRow {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&song.name()))
+            .subtitle(glib::markup_escape_text(&song.artist()))
+            .activatable(true)
+            .build();
+
+        let drag_handle = gtk::Image::builder()
+            .icon_name("list-drag-handle-symbolic")
+            .build();
+        row.add_prefix(&drag_handle);
+
+        let playing_image = gtk::Image::builder()
+            .icon_name("media-playback-start-symbolic")
+            .tooltip_text(gettext("Playing"))
+            .build();
+        song.bind_property("state", &playing_image, "visible")
+            .transform_to(|_, state: State| Some(state == State::Playing))
+            .sync_create()
+            .build();
+        row.add_suffix(&playing_image);
+
+        let remove_button = gtk::Button::builder()
+            .icon_name("list-remove-symbolic")
+            .tooltip_text(gettext("Remove From Queue"))
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build();
+        song.bind_property("state", &remove_button, "visible")
+            .transform_to(|_, state: State| Some(state != State::Playing))
+            .sync_create()
+            .build();
+        remove_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            #[weak]
+            row,
+            move |_| {
+                if let Some(player) = obj.player() {
+                    player.remove_song(row.index() as u32);
+                }
+            }
+        ));
+        row.add_suffix(&remove_button);
+
+        let drag_source = gtk::DragSource::builder()
+            .name("queue-drag-format")
+            .actions(DragAction::MOVE)
+            .build();
+
+        drag_source.connect_prepare(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            #[weak]
+            row,
+            #[strong]
+            song,
+            #[upgrade_or]
+            None,
+            move |drag_context, _x, _y| {
+                obj.imp().listbox.drag_highlight_row(&row);
+                let icon = gtk::WidgetPaintable::new(Some(&row));
+                drag_context.set_icon(Some(&icon), 0, 0);
+                Some(gtk::gdk::ContentProvider::for_value(&song.to_value()))
+            }
+        ));
+
+        drag_source.connect_drag_end(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_, _, _| {
+                obj.imp().listbox.drag_unhighlight_row();
+            }
+        ));
+
+        let drop_target = gtk::DropTarget::builder()
+            .name("queue-drag-format")
+            .propagation_phase(gtk::PropagationPhase::Capture)
+            .actions(DragAction::MOVE)
+            .build();
+
+        drop_target.set_types(&[CoreSong::static_type()]);
+
+        drop_target.connect_drop(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            #[weak]
+            row,
+            #[upgrade_or]
+            false,
+            move |_drop_target, value, _x, _y| {
+                let Ok(dragged) = value.get::<CoreSong>() else {
+                    return false;
+                };
+                let Some(player) = obj.player() else {
+                    return false;
+                };
+                let Some(from) = player.active_model().and_then(|model| model.find(&dragged))
+                else {
+                    return false;
+                };
+                player.move_song(from, row.index() as u32);
+                true
+            }
+        ));
+
+        row.add_controller(drag_source);
+        row.add_controller(drop_target);
+
+        row
+    }
+
+    #[template_callback]
+    fn on_row_activated(&self, row: &gtk::ListBoxRow) {
+        let position = row.index() as u32;
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                if let Some(player) = obj.player() {
+                    player.play_queue_position(position).await;
+                }
+            }
+        ));
+    }
+
+    #[template_callback]
+    fn on_clear_clicked(&self) {
+        if let Some(player) = self.player() {
+            player.clear_queue();
+        }
+    }
+
+    #[template_callback]
+    fn on_save_clicked(&self) {
+        let Some(model) = self.player().and_then(|player| player.active_model()) else {
+            return;
+        };
+        let songs = model.iter::<CoreSong>().flatten().collect::<Vec<_>>();
+        let Some(client) = songs.first().map(|song| song.client()) else {
+            return;
+        };
+        let ids = songs.iter().map(|song| song.id()).collect::<Vec<_>>();
+
+        let name_entry = adw::EntryRow::builder()
+            .title(gettext("Playlist Name"))
+            .build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&name_entry);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Save as Playlist"))
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("save", &gettext("Save"))]);
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_response_enabled("save", false);
+        dialog.set_close_response("cancel");
+
+        name_entry.connect_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                dialog.set_response_enabled("save", !entry.text().trim().is_empty());
+            }
+        ));
+
+        dialog.connect_response(
+            Some("save"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                name_entry,
+                move |_, _| {
+                    let name = name_entry.text().trim().to_string();
+                    let ids = ids.to_owned();
+                    let client = client.clone();
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            match spawn_tokio(async move {
+                                client.create_playlist(&name, &ids, "Audio").await
+                            })
+                            .await
+                            {
+                                Ok(()) => obj.toast(gettext("Playlist saved")),
+                                Err(e) => obj.toast(e.to_user_facing()),
+                            }
+                        }
+                    ));
+                }
+            ),
+        );
+
+        if let Some(popover) = self
+            .ancestor(gtk::Popover::static_type())
+            .and_downcast::<gtk::Popover>()
+        {
+            popover.popdown();
+        }
+        alert_dialog!(self, dialog);
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use super::{
     horbu_scrolled::HorbuScrolled,
     item::dt,
@@ -12,7 +14,7 @@ use super::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
         structs::*,
     },
     fraction,
@@ -164,6 +166,7 @@ pub(crate) mod imp {
                 }
             ));
 
+            self.actionbox.set_client(obj.client());
             self.actionbox.set_id(Some(obj.item().id()));
             self.actionbox.set_item_type(obj.item().item_type());
             self.selection.set_model(Some(&store));
@@ -197,6 +200,10 @@ impl OtherPage {
         Object::builder().property("item", item).build()
     }
 
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.item().client()
+    }
+
     pub fn setup_pic(&self) {
         let imp = self.imp();
         let Some(source) = select_picture_source(
@@ -217,12 +224,13 @@ impl OtherPage {
 
     pub async fn get_item(&self) {
         let id = self.item().id();
+        let client = self.client();
 
         fraction_reset!(self);
         let mut events = fetch_with_cache(
             &format!("list_{id}"),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_item_info(&id).await },
+            async move { client.get_item_info(&id).await },
         )
         .await;
 
@@ -338,10 +346,12 @@ impl OtherPage {
         let Some(series_id) = self.item().series_id() else {
             return;
         };
+        let client = self.client();
+        let fetch_client = client.clone();
         let mut events = fetch_with_cache(
             &format!("season_{id}"),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_episodes_all(&series_id, &id).await },
+            async move { fetch_client.get_episodes_all(&series_id, &id).await },
         )
         .await;
 
@@ -360,7 +370,7 @@ impl OtherPage {
                         .items
                         .into_iter()
                         .map(|item| {
-                            let tu_item = TuItem::from_simple(item);
+                            let tu_item = TuItem::from_simple(item, &client);
                             tu_item.set_is_resume(true);
                             TuObject::new(tu_item)
                         })
@@ -393,7 +403,7 @@ impl OtherPage {
     }
 
     pub fn add_sgt_item_horbu(&self, horbu: &HorbuScrolled, items: &[SGTitem], type_: &str) {
-        horbu.set_items(items, type_);
+        horbu.set_items(items, type_, &self.client());
     }
 
     #[template_callback]
@@ -405,15 +415,16 @@ impl OtherPage {
 
     pub fn add_actor_item_hortu(&self, items: Vec<SimpleListItem>) {
         let hortu = self.imp().actorhortu.get();
-        hortu.set_items(items);
+        hortu.set_items(items, &self.client());
     }
 
     async fn hortu_set_boxset_list(&self) {
         let id = self.item().id();
+        let client = self.client();
         let mut events = fetch_with_cache(
             &format!("boxset_{id}"),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_includedby(&id).await },
+            async move { client.get_includedby(&id).await },
         )
         .await;
 
@@ -444,9 +455,10 @@ impl OtherPage {
                 _ => {}
             });
 
-        imp.moviehortu.set_items(movies);
-        imp.serieshortu.set_items(series);
-        imp.episodehortu.set_items(episodes);
+        let client = self.client();
+        imp.moviehortu.set_items(movies, &client);
+        imp.serieshortu.set_items(series, &client);
+        imp.episodehortu.set_items(episodes, &client);
     }
 
     async fn hortu_set_actor_list(&self, type_: &str) {
@@ -467,33 +479,35 @@ impl OtherPage {
             move |_| {
                 let id = obj.item().id();
                 let tag = format!("{} of {}", type1_, obj.item().name());
-                let page = crate::ui::widgets::single_grid::SingleGrid::new();
+                let page = crate::ui::widgets::single_grid::SingleGrid::new(obj.client());
                 let type_clone1 = type1_.to_owned();
                 let type_clone2 = type1_.to_owned();
                 let id_clone1 = id.to_owned();
                 let id_clone2 = id.to_owned();
-                page.connect_sort_changed_tokio(move |sort_by, sort_order, filters_list| {
-                    let id_clone1 = id_clone1.to_owned();
-                    let type_clone1 = type_clone1.to_owned();
-                    async move {
-                        JELLYFIN_CLIENT
-                            .get_person_large_list(
-                                &id_clone1,
-                                &type_clone1,
-                                &sort_by,
-                                &sort_order,
-                                0,
-                                &filters_list,
-                            )
-                            .await
-                    }
-                });
+                page.connect_sort_changed_tokio(
+                    move |client, sort_by, sort_order, filters_list| {
+                        let id_clone1 = id_clone1.to_owned();
+                        let type_clone1 = type_clone1.to_owned();
+                        async move {
+                            client
+                                .get_person_large_list(
+                                    &id_clone1,
+                                    &type_clone1,
+                                    &sort_by,
+                                    &sort_order,
+                                    0,
+                                    &filters_list,
+                                )
+                                .await
+                        }
+                    },
+                );
                 page.connect_end_edge_overshot_tokio(
-                    move |sort_by, sort_order, n_items, filters_list| {
+                    move |client, sort_by, sort_order, n_items, filters_list| {
                         let id_clone2 = id_clone2.to_owned();
                         let type_clone2 = type_clone2.to_owned();
                         async move {
-                            JELLYFIN_CLIENT
+                            client
                                 .get_person_large_list(
                                     &id_clone2,
                                     &type_clone2,
@@ -513,18 +527,20 @@ impl OtherPage {
         let type_ = type2_.to_owned();
 
         let id = self.item().id();
+        let client = self.client();
+        let fetch_client = client.clone();
 
         let mut events = fetch_with_cache(
             &format!("other_{}_{}", type_, id),
             CachePolicy::ReadCacheAndRefresh,
-            async move { JELLYFIN_CLIENT.get_actor_item_list(&id, &type_).await },
+            async move { fetch_client.get_actor_item_list(&id, &type_).await },
         )
         .await;
 
         while let Some(event) = events.recv().await {
             match event {
                 CacheEvent::Data { data, .. } => {
-                    hortu.set_items(data.items);
+                    hortu.set_items(data.items, &client);
                 }
                 CacheEvent::Error(e) => {
                     self.toast(e.to_user_facing());
@@ -68,6 +68,7 @@ pub mod imp {
         #[property(get, set)]
         pub tag: RefCell<String>,
         pub image_index: Cell<Option<u8>>,
+        pub account: RefCell<Option<String>>,
         #[property(get, set, nullable)]
         pub url: RefCell<Option<String>>,
         #[template_child]
@@ -140,6 +141,7 @@ impl PictureLoader {
                 tag,
                 image_type,
                 image_index,
+                account,
             } => {
                 let obj: Self = glib::Object::builder()
                     .property("id", id)
@@ -147,6 +149,7 @@ impl PictureLoader {
                     .build();
                 obj.imp().imagetype.set(image_type);
                 obj.imp().image_index.replace(image_index);
+                obj.imp().account.replace(account);
                 obj
             }
             PictureSource::Url { url } => glib::Object::builder()
@@ -172,16 +175,19 @@ impl PictureLoader {
                 tag,
                 image_type,
                 image_index,
+                account,
             } => {
                 self.set_id(id.as_str());
                 self.imp().imagetype.set(image_type);
                 self.set_tag(tag.as_str());
                 self.imp().image_index.replace(*image_index);
+                self.imp().account.replace(account.to_owned());
                 self.set_url(None::<String>);
             }
             PictureSource::Url { url } => {
                 self.set_id("");
                 self.imp().image_index.replace(None);
+                self.imp().account.replace(None);
                 self.set_url(Some(url.as_str()));
             }
             PictureSource::User { .. } => unreachable!(),
@@ -288,6 +294,7 @@ impl PictureLoader {
                 tag: self.tag(),
                 image_type: self.imp().imagetype.get(),
                 image_index: self.imp().image_index.get(),
+                account: self.imp().account.borrow().to_owned(),
             }
         }
     }
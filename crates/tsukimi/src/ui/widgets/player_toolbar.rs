@@ -1,3 +1,6 @@
+use std::sync::Arc;
+
+use gettextrs::gettext;
 use gst::ClockTime;
 use gtk::{
     gio,
@@ -12,9 +15,17 @@ use super::{
     song_widget::format_duration,
 };
 use crate::{
+    client::{
+        jellyfin_client::JellyfinClient,
+        registry::CLIENTS,
+    },
     gstl::player::imp::ListRepeatMode,
     ui::{
-        models::SETTINGS,
+        GlobalToast,
+        models::{
+            SETTINGS,
+            sleep_timer::SleepTimerMode,
+        },
         provider::core_song::CoreSong,
     },
     utils::{
@@ -38,7 +49,10 @@ mod imp {
             MusicPlayer,
             imp::ListRepeatMode,
         },
-        ui::widgets::smooth_scale::SmoothScale,
+        ui::widgets::{
+            music_queue::MusicQueue,
+            smooth_scale::SmoothScale,
+        },
     };
 
     #[derive(CompositeTemplate, Default)]
@@ -63,6 +77,10 @@ mod imp {
         pub duration_label: TemplateChild<gtk::Label>,
         #[template_child]
         pub repeat_image: TemplateChild<gtk::Image>,
+        #[template_child]
+        pub queue: TemplateChild<MusicQueue>,
+        #[template_child]
+        pub shuffle_button: TemplateChild<gtk::ToggleButton>,
     }
 
     #[glib::object_subclass]
@@ -98,6 +116,16 @@ mod imp {
                     player_toolbar_box.set_repeat_mode(ListRepeatMode::Repeat);
                 },
             );
+
+            klass.install_action(
+                "sleep-timer.set",
+                Some(glib::VariantTy::STRING),
+                move |player_toolbar_box, _action, target| {
+                    if let Some(target) = target.and_then(|t| t.str()) {
+                        player_toolbar_box.set_sleep_timer(SleepTimerMode::from_target(target));
+                    }
+                },
+            );
         }
 
         fn instance_init(obj: &InitializingObject<Self>) {
@@ -109,6 +137,12 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
             self.progress_scale.set_player(Some(&self.player));
+            self.queue.set_player(&self.player);
+            self.player
+                .bind_property("shuffle", &*self.shuffle_button, "active")
+                .sync_create()
+                .bidirectional()
+                .build();
             let obj = self.obj().to_owned();
             self.player.connect_closure(
                 "stream-start",
@@ -117,8 +151,18 @@ mod imp {
                     obj.change_view(duration);
                 }),
             );
-            self.obj()
-                .set_repeat_mode(ListRepeatMode::from_string(&SETTINGS.music_repeat_mode()));
+            let obj = self.obj().to_owned();
+            self.player.connect_closure(
+                "sleep-timer-finished",
+                false,
+                closure_local!(move |_player: MusicPlayer| {
+                    obj.on_stop_button_clicked();
+                }),
+            );
+            let obj = self.obj();
+            obj.set_repeat_mode(ListRepeatMode::from_string(
+                &SETTINGS.music_repeat_mode(&obj.client()),
+            ));
         }
     }
 
@@ -151,7 +195,9 @@ impl PlayerToolbarBox {
     pub fn set_repeat_mode(&self, mode: ListRepeatMode) {
         let player = &self.imp().player;
         player.set_repeat_mode(mode);
-        SETTINGS.set_music_repeat_mode(mode.to_string()).unwrap();
+        SETTINGS
+            .set_music_repeat_mode(&self.client(), mode.to_string())
+            .unwrap();
         let i = &self.imp().repeat_image;
         match mode {
             ListRepeatMode::None => {
@@ -166,6 +212,24 @@ impl PlayerToolbarBox {
         }
     }
 
+    /// Client of the account the playing track belongs to.
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .player
+            .active_core_song()
+            .map(|song| song.client())
+            .unwrap_or_else(|| CLIENTS.active())
+    }
+
+    pub fn set_sleep_timer(&self, mode: SleepTimerMode) {
+        let player = &self.imp().player;
+        player.set_sleep_timer(mode);
+        match player.sleep_timer_description() {
+            Some(description) => self.toast(description),
+            None => self.toast(gettext("Sleep timer cancelled")),
+        }
+    }
+
     pub fn update_play_state(&self) {
         self.imp().progress_scale.update_timeout();
         let play_pause_image = &self.imp().play_pause_image.get();
@@ -213,6 +277,7 @@ impl PlayerToolbarBox {
     pub fn on_stop_button_clicked(&self) {
         let imp = self.imp();
         imp.player.imp().stop();
+        imp.player.stop_sleep_timer();
         imp.progress_scale.remove_timeout();
         imp.toolbar.set_revealed(false);
     }
@@ -238,9 +303,39 @@ impl PlayerToolbarBox {
             .imp()
             .load_model(active_model, active_core_song)
             .await;
+        // The server of the new track may override the repeat mode
+        self.set_repeat_mode(ListRepeatMode::from_string(
+            &SETTINGS.music_repeat_mode(&self.client()),
+        ));
         self.update_play_state();
     }
 
+    /// Adds `songs` to the queue, or starts playing them when nothing plays.
+    pub async fn queue_songs(&self, songs: Vec<CoreSong>, play_next: bool) {
+        let player = &self.imp().player;
+        let queued = if play_next {
+            player.play_next(&songs)
+        } else {
+            player.add_to_queue(&songs)
+        };
+
+        if queued {
+            self.toast(if play_next {
+                gettext("Playing next")
+            } else {
+                gettext("Added to queue")
+            });
+            return;
+        }
+
+        let Some(first) = songs.first().cloned() else {
+            return;
+        };
+        let model = gio::ListStore::new::<CoreSong>();
+        model.extend_from_slice(&songs);
+        self.bind_song_model(model, first).await;
+    }
+
     #[template_callback]
     async fn on_next_button_clicked(&self) {
         self.imp().player.imp().next().await;
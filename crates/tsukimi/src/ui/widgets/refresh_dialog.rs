@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::{
     prelude::*,
     subclass::prelude::*,
@@ -11,7 +13,7 @@ use gtk::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
     },
     utils::spawn_tokio,
 };
@@ -29,12 +31,13 @@ mod imp {
 
     use super::*;
 
-    #[derive(Debug, Default, CompositeTemplate, glib::Properties)]
+    #[derive(Default, CompositeTemplate, glib::Properties)]
     #[template(resource = "/moe/tsuna/tsukimi/ui/refresh_dialog.ui")]
     #[properties(wrapper_type = super::RefreshDialog)]
     pub struct RefreshDialog {
         #[property(get, set, construct_only)]
         pub id: OnceCell<String>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
         #[template_child]
         pub metadata_check: TemplateChild<gtk::CheckButton>,
         #[template_child]
@@ -72,8 +75,18 @@ glib::wrapper! {
 
 #[template_callbacks]
 impl RefreshDialog {
-    pub fn new(id: &str) -> Self {
-        glib::Object::builder().property("id", id).build()
+    pub fn new(id: &str, client: Arc<JellyfinClient>) -> Self {
+        let dialog: Self = glib::Object::builder().property("id", id).build();
+        let _ = dialog.imp().client.set(client);
+        dialog
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("RefreshDialog is created with a client")
+            .clone()
     }
 
     #[template_callback]
@@ -83,8 +96,9 @@ impl RefreshDialog {
         let metadata = imp.metadata_check.is_active();
         let image = imp.image_check.is_active();
 
+        let client = self.client();
         match spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            client
                 .fullscan(&id, &metadata.to_string(), &image.to_string())
                 .await
         })
@@ -0,0 +1,631 @@
+use std::{
+    cell::{
+        Cell,
+        OnceCell,
+        RefCell,
+    },
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use super::{
+    song_widget::format_duration,
+    utils::GlobalToast,
+};
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        structs::SessionInfo,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+const POLL_SECONDS: u32 = 2;
+const TICKS_PER_SECOND: f64 = 10_000_000.0;
+/// Dragging the position or volume sends one command once the scale rests
+/// this long.
+const SEEK_DELAY: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// What to start on the target device once it is picked.
+#[derive(Clone, Default)]
+pub struct PlayRequest {
+    pub item_ids: Vec<String>,
+    pub start_index: usize,
+    pub start_ticks: i64,
+    pub media_source_id: Option<String>,
+    pub subtitle_stream_index: Option<i64>,
+}
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/remote_control_dialog.ui")]
+    pub struct RemoteControlDialog {
+        #[template_child]
+        pub view: TemplateChild<adw::NavigationView>,
+        #[template_child]
+        pub device_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub remote_page: TemplateChild<adw::NavigationPage>,
+        #[template_child]
+        pub title_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub device_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub position_scale: TemplateChild<gtk::Scale>,
+        #[template_child]
+        pub position_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub duration_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub play_pause_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub volume_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub mute_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub volume_scale: TemplateChild<gtk::Scale>,
+        #[template_child]
+        pub audio_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub audio_list: TemplateChild<gtk::StringList>,
+        #[template_child]
+        pub subtitle_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub subtitle_list: TemplateChild<gtk::StringList>,
+
+        pub request: RefCell<Option<PlayRequest>>,
+        pub sessions: RefCell<Vec<SessionInfo>>,
+        pub session: RefCell<Option<SessionInfo>>,
+        // Stream indexes in the same order as `audio_list` and `subtitle_list`
+        pub audio_indexes: RefCell<Vec<i64>>,
+        pub subtitle_indexes: RefCell<Vec<i64>>,
+        // Tracks are rebuilt only when the playing item changes
+        pub streams_item_id: RefCell<Option<String>>,
+        // Set while mirroring the session, so the combo rows don't send commands back
+        pub updating: Cell<bool>,
+        pub poll_timeout: RefCell<Option<glib::SourceId>>,
+        pub seek_timeout: RefCell<Option<glib::SourceId>>,
+        pub volume_timeout: RefCell<Option<glib::SourceId>>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RemoteControlDialog {
+        const NAME: &'static str = "RemoteControlDialog";
+        type Type = super::RemoteControlDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RemoteControlDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.device_list.set_placeholder(Some(
+                &adw::StatusPage::builder()
+                    .icon_name("video-display-symbolic")
+                    .title(gettext("No Devices Found"))
+                    .description(gettext(
+                        "Open a Jellyfin client on another device with the same account",
+                    ))
+                    .css_classes(["compact"])
+                    .build(),
+            ));
+            self.obj().on_refresh_devices();
+        }
+
+        fn dispose(&self) {
+            if let Some(timeout) = self.poll_timeout.take() {
+                timeout.remove();
+            }
+            if let Some(timeout) = self.seek_timeout.take() {
+                timeout.remove();
+            }
+            if let Some(timeout) = self.volume_timeout.take() {
+                timeout.remove();
+            }
+        }
+    }
+
+    impl WidgetImpl for RemoteControlDialog {}
+    impl AdwDialogImpl for RemoteControlDialog {}
+}
+
+glib::wrapper! {
+    pub struct RemoteControlDialog(ObjectSubclass<imp::RemoteControlDialog>)
+        @extends gtk::Widget, adw::Dialog, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl RemoteControlDialog {
+    /// Picking a device starts `request` on it, or only opens the remote view when there is none.
+    /// Controls the sessions of the account of `client`.
+    pub fn new(client: Arc<JellyfinClient>, request: Option<PlayRequest>) -> Self {
+        let obj: Self = glib::Object::new();
+        let _ = obj.imp().client.set(client);
+        if request.is_none() {
+            obj.set_title(&gettext("Remote Control"));
+        }
+        obj.imp().request.replace(request);
+        obj
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("RemoteControlDialog is created with a client")
+            .clone()
+    }
+
+    #[template_callback]
+    fn on_refresh_devices(&self) {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let client = obj.client();
+                let sessions = match spawn_tokio(
+                    async move { client.get_controllable_sessions().await },
+                )
+                .await
+                {
+                    Ok(sessions) => sessions,
+                    Err(e) => {
+                        obj.toast(e.to_user_facing());
+                        return;
+                    }
+                };
+                obj.set_devices(sessions);
+            }
+        ));
+    }
+
+    fn set_devices(&self, sessions: Vec<SessionInfo>) {
+        let device_list = &self.imp().device_list;
+        device_list.remove_all();
+
+        for session in &sessions {
+            let mut subtitle = session.client.to_owned().unwrap_or_default();
+            if let Some(item) = &session.now_playing_item {
+                subtitle = format!("{subtitle} · {}", item.name);
+            }
+
+            let row = adw::ActionRow::builder()
+                .use_markup(false)
+                .title(session.device_name.to_owned().unwrap_or_default())
+                .subtitle(subtitle)
+                .activatable(true)
+                .build();
+            row.add_prefix(&gtk::Image::from_icon_name("video-display-symbolic"));
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            device_list.append(&row);
+        }
+
+        self.imp().sessions.replace(sessions);
+    }
+
+    #[template_callback]
+    async fn on_device_activated(&self, row: gtk::ListBoxRow) {
+        let imp = self.imp();
+        let Some(session) = imp.sessions.borrow().get(row.index() as usize).cloned() else {
+            return;
+        };
+
+        let request = imp.request.borrow().to_owned();
+        if let Some(request) = request {
+            let session_id = session.id.to_owned();
+            let client = self.client();
+            if let Err(e) = spawn_tokio(async move {
+                client
+                    .play_on_session(
+                        &session_id,
+                        &request.item_ids,
+                        request.start_index,
+                        request.start_ticks,
+                        request.media_source_id.as_deref(),
+                        request.subtitle_stream_index,
+                    )
+                    .await
+            })
+            .await
+            {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        }
+
+        imp.device_label
+            .set_text(&session.device_name.to_owned().unwrap_or_default());
+        imp.streams_item_id.replace(None);
+        self.mirror_session(session);
+        imp.view.push(&imp.remote_page.get());
+        self.watch_session();
+    }
+
+    fn watch_session(&self) {
+        let imp = self.imp();
+        if let Some(timeout) = imp.poll_timeout.take() {
+            timeout.remove();
+        }
+
+        let timeout = glib::timeout_add_seconds_local(
+            POLL_SECONDS,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    if obj.imp().view.visible_page_tag().as_deref() == Some("remote") {
+                        spawn(glib::clone!(
+                            #[weak]
+                            obj,
+                            async move {
+                                obj.poll_session().await;
+                            }
+                        ));
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+        imp.poll_timeout.replace(Some(timeout));
+    }
+
+    async fn poll_session(&self) {
+        let Some(id) = self.session_id() else {
+            return;
+        };
+
+        let client = self.client();
+        let sessions =
+            match spawn_tokio(async move { client.get_controllable_sessions().await }).await {
+                Ok(sessions) => sessions,
+                Err(e) => {
+                    self.toast(e.to_user_facing());
+                    return;
+                }
+            };
+
+        match sessions.into_iter().find(|session| session.id == id) {
+            Some(session) => self.mirror_session(session),
+            None => {
+                self.toast(gettext("The device is no longer connected"));
+                self.imp().view.pop();
+                self.on_refresh_devices();
+            }
+        }
+    }
+
+    fn session_id(&self) -> Option<String> {
+        self.imp()
+            .session
+            .borrow()
+            .as_ref()
+            .map(|session| session.id.to_owned())
+    }
+
+    fn mirror_session(&self, session: SessionInfo) {
+        let imp = self.imp();
+        let play_state = &session.play_state;
+        let supports = |command: &str| session.supported_commands.iter().any(|c| c == command);
+
+        imp.updating.set(true);
+
+        match &session.now_playing_item {
+            Some(item) => {
+                let title = match &item.series_name {
+                    Some(series_name) => format!("{series_name} - {}", item.name),
+                    None => item.name.to_owned(),
+                };
+                imp.title_label.set_text(&title);
+
+                let runtime = item.run_time_ticks.unwrap_or_default() as f64 / TICKS_PER_SECOND;
+                let position =
+                    play_state.position_ticks.unwrap_or_default() as f64 / TICKS_PER_SECOND;
+                imp.position_scale.set_range(0.0, runtime.max(1.0));
+                // Don't move the scale away while a seek is waiting to be sent
+                if imp.seek_timeout.borrow().is_none() {
+                    imp.position_scale.set_value(position);
+                    imp.position_label
+                        .set_text(&format_duration(position as i64));
+                }
+                imp.duration_label
+                    .set_text(&format_duration(runtime as i64));
+
+                if imp.streams_item_id.borrow().as_deref() != Some(item.id.as_str()) {
+                    self.set_streams(&session);
+                    imp.streams_item_id.replace(Some(item.id.to_owned()));
+                }
+            }
+            None => {
+                imp.title_label.set_text(&gettext("Nothing Playing"));
+                imp.position_scale.set_value(0.0);
+                imp.position_label.set_text(&format_duration(0));
+                imp.duration_label.set_text(&format_duration(0));
+                imp.audio_list
+                    .splice(0, imp.audio_list.n_items(), &[] as &[&str]);
+                imp.subtitle_list
+                    .splice(0, imp.subtitle_list.n_items(), &[] as &[&str]);
+                imp.streams_item_id.replace(None);
+            }
+        }
+
+        let playing = session.now_playing_item.is_some();
+        imp.position_scale.set_sensitive(playing);
+        imp.play_pause_button
+            .set_icon_name(if playing && !play_state.is_paused {
+                "media-playback-pause-symbolic"
+            } else {
+                "media-playback-start-symbolic"
+            });
+
+        imp.volume_row
+            .set_sensitive(supports("SetVolume") || supports("ToggleMute"));
+        // Same for the volume
+        if imp.volume_timeout.borrow().is_none() {
+            imp.volume_scale
+                .set_value(play_state.volume_level.unwrap_or(100) as f64);
+        }
+        imp.mute_button.set_active(play_state.is_muted);
+        imp.mute_button.set_icon_name(if play_state.is_muted {
+            "audio-volume-muted-symbolic"
+        } else {
+            "audio-volume-high-symbolic"
+        });
+
+        if let Some(index) = play_state.audio_stream_index {
+            let position = imp
+                .audio_indexes
+                .borrow()
+                .iter()
+                .position(|i| *i == index as i64);
+            if let Some(position) = position {
+                imp.audio_row.set_selected(position as u32);
+            }
+        }
+        let subtitle_index = play_state.subtitle_stream_index.unwrap_or(-1) as i64;
+        let position = imp
+            .subtitle_indexes
+            .borrow()
+            .iter()
+            .position(|i| *i == subtitle_index);
+        if let Some(position) = position {
+            imp.subtitle_row.set_selected(position as u32);
+        }
+        imp.audio_row
+            .set_sensitive(playing && supports("SetAudioStreamIndex"));
+        imp.subtitle_row
+            .set_sensitive(playing && supports("SetSubtitleStreamIndex"));
+
+        imp.updating.set(false);
+        imp.session.replace(Some(session));
+    }
+
+    fn set_streams(&self, session: &SessionInfo) {
+        let imp = self.imp();
+        let streams = session
+            .now_playing_item
+            .as_ref()
+            .and_then(|item| item.media_streams.to_owned())
+            .unwrap_or_default();
+
+        let mut audio_names = Vec::new();
+        let mut audio_indexes = Vec::new();
+        let mut subtitle_names = vec![gettext("None")];
+        let mut subtitle_indexes = vec![-1];
+        for stream in streams {
+            let name = stream
+                .display_title
+                .to_owned()
+                .unwrap_or_else(|| gettext("Unknown"));
+            match stream.stream_type.as_str() {
+                "Audio" => {
+                    audio_names.push(name);
+                    audio_indexes.push(stream.index);
+                }
+                "Subtitle" => {
+                    subtitle_names.push(name);
+                    subtitle_indexes.push(stream.index);
+                }
+                _ => {}
+            }
+        }
+
+        imp.audio_list.splice(
+            0,
+            imp.audio_list.n_items(),
+            &audio_names.iter().map(String::as_str).collect::<Vec<_>>(),
+        );
+        imp.subtitle_list.splice(
+            0,
+            imp.subtitle_list.n_items(),
+            &subtitle_names
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+        );
+        imp.audio_indexes.replace(audio_indexes);
+        imp.subtitle_indexes.replace(subtitle_indexes);
+    }
+
+    fn playstate_command(&self, command: &'static str, seek_ticks: Option<i64>) {
+        let Some(id) = self.session_id() else {
+            return;
+        };
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let client = obj.client();
+                if let Err(e) = spawn_tokio(async move {
+                    client
+                        .send_playstate_command(&id, command, seek_ticks)
+                        .await
+                })
+                .await
+                {
+                    obj.toast(e.to_user_facing());
+                }
+            }
+        ));
+    }
+
+    fn general_command(&self, name: &'static str, arguments: Vec<(&'static str, String)>) {
+        let Some(id) = self.session_id() else {
+            return;
+        };
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let client = obj.client();
+                if let Err(e) =
+                    spawn_tokio(
+                        async move { client.send_general_command(&id, name, &arguments).await },
+                    )
+                    .await
+                {
+                    obj.toast(e.to_user_facing());
+                }
+            }
+        ));
+    }
+
+    #[template_callback]
+    fn on_play_pause(&self) {
+        self.playstate_command("PlayPause", None);
+    }
+
+    #[template_callback]
+    fn on_previous(&self) {
+        self.playstate_command("PreviousTrack", None);
+    }
+
+    #[template_callback]
+    fn on_next(&self) {
+        self.playstate_command("NextTrack", None);
+    }
+
+    #[template_callback]
+    fn on_stop(&self) {
+        self.playstate_command("Stop", None);
+    }
+
+    #[template_callback]
+    fn on_position_changed(&self, _scroll: gtk::ScrollType, value: f64) -> bool {
+        let imp = self.imp();
+        imp.position_label.set_text(&format_duration(value as i64));
+
+        if let Some(timeout) = imp.seek_timeout.take() {
+            timeout.remove();
+        }
+        let ticks = (value * TICKS_PER_SECOND) as i64;
+        imp.seek_timeout.replace(Some(glib::timeout_add_local_once(
+            SEEK_DELAY,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move || {
+                    obj.imp().seek_timeout.take();
+                    obj.playstate_command("Seek", Some(ticks));
+                }
+            ),
+        )));
+        false
+    }
+
+    #[template_callback]
+    fn on_volume_changed(&self, _scroll: gtk::ScrollType, value: f64) -> bool {
+        let imp = self.imp();
+        if let Some(timeout) = imp.volume_timeout.take() {
+            timeout.remove();
+        }
+        let volume = value.clamp(0.0, 100.0).round() as i64;
+        imp.volume_timeout
+            .replace(Some(glib::timeout_add_local_once(
+                SEEK_DELAY,
+                glib::clone!(
+                    #[weak(rename_to = obj)]
+                    self,
+                    move || {
+                        obj.imp().volume_timeout.take();
+                        obj.general_command("SetVolume", vec![("Volume", volume.to_string())]);
+                    }
+                ),
+            )));
+        false
+    }
+
+    #[template_callback]
+    fn on_mute_clicked(&self) {
+        let command = if self.imp().mute_button.is_active() {
+            "Mute"
+        } else {
+            "Unmute"
+        };
+        self.general_command(command, Vec::new());
+    }
+
+    #[template_callback]
+    fn on_audio_selected(&self) {
+        let imp = self.imp();
+        if imp.updating.get() {
+            return;
+        }
+        let Some(index) = imp
+            .audio_indexes
+            .borrow()
+            .get(imp.audio_row.selected() as usize)
+            .copied()
+        else {
+            return;
+        };
+        self.general_command("SetAudioStreamIndex", vec![("Index", index.to_string())]);
+    }
+
+    #[template_callback]
+    fn on_subtitle_selected(&self) {
+        let imp = self.imp();
+        if imp.updating.get() {
+            return;
+        }
+        let Some(index) = imp
+            .subtitle_indexes
+            .borrow()
+            .get(imp.subtitle_row.selected() as usize)
+            .copied()
+        else {
+            return;
+        };
+        self.general_command("SetSubtitleStreamIndex", vec![("Index", index.to_string())]);
+    }
+}
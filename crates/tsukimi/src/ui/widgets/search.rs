@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use glib::Object;
 use gtk::{
     gio,
@@ -8,14 +10,20 @@ use gtk::{
 };
 
 use adw::prelude::*;
+use gettextrs::gettext;
 
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
+        runtime::runtime,
         structs::*,
     },
-    ui::provider::tu_item::TuItem,
+    ui::{
+        models::search_history::SearchHistory,
+        provider::tu_item::TuItem,
+        widgets::tuview_scrolled::TuViewScrolled,
+    },
     utils::{
         spawn,
         spawn_tokio,
@@ -27,11 +35,21 @@ use super::{
     utils::GlobalToast,
 };
 
+/// Shorter queries match too much to be worth sending
+const MIN_QUERY_CHARS: usize = 2;
+
 mod imp {
 
     use std::{
-        cell::OnceCell,
-        sync::atomic::Ordering,
+        cell::{
+            Cell,
+            OnceCell,
+            RefCell,
+        },
+        sync::{
+            Arc,
+            atomic::Ordering,
+        },
     };
 
     use glib::subclass::InitializingObject;
@@ -42,6 +60,10 @@ mod imp {
     };
 
     use crate::{
+        client::{
+            jellyfin_client::JellyfinClient,
+            registry::CLIENTS,
+        },
         ui::widgets::{
             filter_panel::FilterPanelDialog,
             tuview_scrolled::TuViewScrolled,
@@ -58,8 +80,24 @@ mod imp {
         #[template_child]
         pub searchscrolled: TemplateChild<TuViewScrolled>,
         #[template_child]
+        pub people_scrolled: TemplateChild<TuViewScrolled>,
+        #[template_child]
+        pub artists_scrolled: TemplateChild<TuViewScrolled>,
+        #[template_child]
+        pub studios_scrolled: TemplateChild<TuViewScrolled>,
+        #[template_child]
+        pub genres_scrolled: TemplateChild<TuViewScrolled>,
+        #[template_child]
+        pub result_stack: TemplateChild<adw::ViewStack>,
+        #[template_child]
         pub recommend_group: TemplateChild<gtk::ListBox>,
         #[template_child]
+        pub history_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub history_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub suggestion_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
         pub movie: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub series: TemplateChild<adw::SwitchRow>,
@@ -83,6 +121,13 @@ mod imp {
 
         pub filter_panel: OnceCell<FilterPanelDialog>,
         pub selection: gtk::SingleSelection,
+
+        /// Bumped on every keystroke so that stale suggestion replies are dropped
+        pub suggest_generation: Cell<u64>,
+        pub suggest_handle: RefCell<Option<tokio::task::AbortHandle>>,
+        pub last_query: RefCell<String>,
+        /// Account the page searches, the page is rebuilt when switching accounts
+        pub client: OnceCell<Arc<JellyfinClient>>,
     }
 
     #[glib::object_subclass]
@@ -105,6 +150,7 @@ mod imp {
         fn constructed(&self) {
             let obj = self.obj();
             self.parent_constructed();
+            let _ = self.client.set(CLIENTS.active());
             self.searchscrolled.connect_end_edge_reached(glib::clone!(
                 #[weak]
                 obj,
@@ -119,7 +165,7 @@ mod imp {
 
                             let search_results = obj.get_search_results::<true>().await;
 
-                            scrolled.set_store::<false>(search_results.items);
+                            scrolled.set_store::<false>(search_results.items, &obj.client());
 
                             scrolled.reveal_spinner(false);
 
@@ -160,6 +206,14 @@ impl SearchPage {
         Object::builder().build()
     }
 
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("SearchPage is constructed")
+            .clone()
+    }
+
     pub fn update(&self) {
         spawn(glib::clone!(
             #[weak(rename_to = obj)]
@@ -171,8 +225,12 @@ impl SearchPage {
     }
 
     pub async fn setup_recommend(&self) {
+        self.update_history();
+
+        let client = self.client();
+        let fetch_client = client.clone();
         let recommend =
-            match spawn_tokio(async { JELLYFIN_CLIENT.get_search_recommend().await }).await {
+            match spawn_tokio(async move { fetch_client.get_search_recommend().await }).await {
                 Ok(list) => list,
                 Err(e) => {
                     self.toast(e.to_user_facing());
@@ -212,8 +270,10 @@ impl SearchPage {
             action_row.connect_activated(glib::clone!(
                 #[weak(rename_to = obj)]
                 self,
+                #[strong]
+                client,
                 move |_| {
-                    let tu_item = TuItem::from_simple(item.to_owned());
+                    let tu_item = TuItem::from_simple(item.to_owned(), &client);
                     tu_item.activate(&obj);
                 }
             ));
@@ -224,27 +284,264 @@ impl SearchPage {
         imp.stack.set_visible_child_name("recommend");
     }
 
+    fn update_history(&self) {
+        let imp = self.imp();
+        imp.history_list.remove_all();
+
+        let queries = SearchHistory::load(&self.client()).queries();
+        imp.history_group.set_visible(!queries.is_empty());
+
+        for query in queries {
+            let action_row = adw::ActionRow::builder()
+                .title(&query)
+                .use_markup(false)
+                .activatable(true)
+                .build();
+            action_row.add_prefix(&gtk::Image::from_icon_name("document-open-recent-symbolic"));
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("window-close-symbolic")
+                .tooltip_text(gettext("Remove from History"))
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            remove_button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[strong]
+                query,
+                move |_| {
+                    if let Err(e) = SearchHistory::load(&obj.client()).remove(&query) {
+                        obj.toast(e.to_string());
+                    }
+                    obj.update_history();
+                }
+            ));
+            action_row.add_suffix(&remove_button);
+
+            action_row.connect_activated(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.imp().searchentry.set_text(&query);
+                    obj.search();
+                }
+            ));
+
+            imp.history_list.append(&action_row);
+        }
+    }
+
+    #[template_callback]
+    fn on_clear_history(&self) {
+        if let Err(e) = SearchHistory::load(&self.client()).clear() {
+            self.toast(e.to_string());
+        }
+        self.update_history();
+    }
+
+    fn search(&self) {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                obj.on_search_activate().await;
+            }
+        ));
+    }
+
+    fn cancel_suggestions(&self) {
+        let imp = self.imp();
+        imp.suggest_generation.set(imp.suggest_generation.get() + 1);
+        if let Some(handle) = imp.suggest_handle.take() {
+            handle.abort();
+        }
+    }
+
+    #[template_callback]
+    async fn on_search_changed(&self) {
+        let imp = self.imp();
+
+        let query = imp.searchentry.text().trim().to_string();
+        if *imp.last_query.borrow() == query {
+            return;
+        }
+        imp.last_query.replace(query.to_owned());
+
+        self.cancel_suggestions();
+        let generation = imp.suggest_generation.get();
+
+        if !is_searchable(&query) {
+            imp.stack.set_visible_child_name("recommend");
+            return;
+        }
+
+        let client = self.client();
+        let handle = runtime().spawn(async move { client.search_hints(&query).await });
+        imp.suggest_handle.replace(Some(handle.abort_handle()));
+
+        let Ok(result) = handle.await else {
+            return;
+        };
+
+        // The query changed or a full search started while we were waiting
+        if imp.suggest_generation.get() != generation {
+            return;
+        }
+
+        let hints = match result {
+            Ok(result) => result.search_hints,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        imp.suggestion_list.remove_all();
+
+        if hints.is_empty() {
+            imp.stack.set_visible_child_name("recommend");
+            return;
+        }
+
+        for hint in hints {
+            let Some(id) = hint.item_id().map(str::to_owned) else {
+                continue;
+            };
+
+            let mut subtitle = vec![hint.item_type.to_owned()];
+            if let Some(year) = hint.production_year {
+                subtitle.push(year.to_string());
+            }
+            if let Some(series) = &hint.series {
+                subtitle.push(series.to_owned());
+            }
+
+            let action_row = adw::ActionRow::builder()
+                .title(&hint.name)
+                .subtitle(subtitle.join(" · "))
+                .use_markup(false)
+                .activatable(true)
+                .build();
+
+            action_row.connect_activated(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    let id = id.to_owned();
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            let client = obj.client();
+                            let fetch_client = client.clone();
+                            match spawn_tokio(async move { fetch_client.get_item_info(&id).await })
+                                .await
+                            {
+                                Ok(item) => TuItem::from_simple(item, &client).activate(&obj),
+                                Err(e) => obj.toast(e.to_user_facing()),
+                            }
+                        }
+                    ));
+                }
+            ));
+
+            imp.suggestion_list.append(&action_row);
+        }
+
+        imp.stack.set_visible_child_name("suggestions");
+    }
+
     #[template_callback]
     async fn on_search_activate(&self) {
         let imp = self.imp();
 
-        let search_results = self.get_search_results::<false>().await;
+        self.cancel_suggestions();
+
+        let query = imp.searchentry.text().trim().to_string();
+        imp.last_query.replace(query.to_owned());
+
+        if is_searchable(&query) {
+            if let Err(e) = SearchHistory::load(&self.client()).push(&query) {
+                self.toast(e.to_string());
+            }
+            self.update_history();
+        }
 
-        if search_results.items.is_empty() {
+        let include_people = imp.person.is_active();
+        let include_artists = imp.music.is_active();
+
+        let (search_results, people, artists, studios, genres) = futures_util::join!(
+            self.get_search_results::<false>(),
+            self.search_names(include_people, "Persons", &query),
+            self.search_names(include_artists, "Artists", &query),
+            self.search_names(true, "Studios", &query),
+            self.search_names(true, "Genres", &query),
+        );
+
+        let mut has_results = false;
+        for (scrolled, items) in [
+            (&*imp.searchscrolled, search_results.items),
+            (&*imp.people_scrolled, people),
+            (&*imp.artists_scrolled, artists),
+            (&*imp.studios_scrolled, studios),
+            (&*imp.genres_scrolled, genres),
+        ] {
+            has_results |= !items.is_empty();
+            imp.result_stack
+                .page(scrolled)
+                .set_visible(!items.is_empty());
+            scrolled.set_store::<true>(items, &self.client());
+        }
+
+        if !has_results {
             imp.stack.set_visible_child_name("fallback");
             return;
-        };
+        }
 
-        imp.searchscrolled.set_store::<true>(search_results.items);
+        // Keep the current tab if it still has results
+        let current_visible = imp
+            .result_stack
+            .visible_child()
+            .is_some_and(|child| imp.result_stack.page(&child).is_visible());
+        if !current_visible {
+            let first = imp
+                .result_stack
+                .pages()
+                .iter::<adw::ViewStackPage>()
+                .flatten()
+                .find(|page| page.is_visible());
+            if let Some(page) = first {
+                imp.result_stack.set_visible_child(&page.child());
+            }
+        }
 
         imp.stack.set_visible_child_name("result");
     }
 
+    async fn search_names(
+        &self, enabled: bool, endpoint: &'static str, query: &str,
+    ) -> Vec<SimpleListItem> {
+        if !enabled || !is_searchable(query) {
+            return Vec::new();
+        }
+
+        let query = query.to_owned();
+        let client = self.client();
+        match spawn_tokio(async move { client.search_names(endpoint, &query).await }).await {
+            Ok(list) => list.items,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                Vec::new()
+            }
+        }
+    }
+
     pub async fn get_search_results<const F: bool>(&self) -> List {
         let imp = self.imp();
 
-        let search_content = imp.searchentry.text().to_string();
-        if search_content.len() < 2 {
+        let search_content = imp.searchentry.text().trim().to_string();
+        if !is_searchable(&search_content) {
             return List::default();
         }
         let search_filter = {
@@ -258,9 +555,6 @@ impl SearchPage {
             if imp.boxset.is_active() {
                 filter.push("BoxSet");
             }
-            if imp.person.is_active() {
-                filter.push("Person");
-            }
             if imp.music.is_active() {
                 filter.push("MusicAlbum");
             }
@@ -297,8 +591,9 @@ impl SearchPage {
             imp.filter.remove_css_class("accent");
         }
 
+        let client = self.client();
         match spawn_tokio(async move {
-            JELLYFIN_CLIENT
+            client
                 .search(
                     &search_content,
                     &search_filter,
@@ -320,7 +615,7 @@ impl SearchPage {
     #[template_callback]
     fn filter_panel_cb(&self, _btn: &gtk::Button) {
         let panel = self.imp().filter_panel.get_or_init(|| {
-            let dialog = FilterPanelDialog::new();
+            let dialog = FilterPanelDialog::new(self.client());
             dialog.connect_applied(glib::clone!(
                 #[weak(rename_to = obj)]
                 self,
@@ -337,12 +632,10 @@ impl SearchPage {
     }
 
     pub fn on_filter_applied(&self) {
-        spawn(glib::clone!(
-            #[weak(rename_to = obj)]
-            self,
-            async move {
-                obj.on_search_activate().await;
-            }
-        ));
+        self.search();
     }
 }
+
+fn is_searchable(query: &str) -> bool {
+    query.trim().chars().count() >= MIN_QUERY_CHARS
+}
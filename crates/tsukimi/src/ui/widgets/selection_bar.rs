@@ -0,0 +1,681 @@
+use std::{
+    collections::HashSet,
+    future::Future,
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use anyhow::Result;
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    gio,
+    glib,
+    template_callbacks,
+};
+
+use super::{
+    identify::IdentifyDialog,
+    tu_item::{
+        Action,
+        perform_item_action,
+    },
+    utils::GlobalToast,
+};
+use crate::{
+    alert_dialog,
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+    },
+    ui::provider::{
+        IS_ADMIN,
+        tu_item::{
+            AUDIO,
+            MUSIC_ALBUM,
+            MUSIC_ARTIST,
+            MUSIC_GENRE,
+            TuItem,
+        },
+        tu_object::TuObject,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+/// Number of requests sent to the server at once while running a bulk action.
+const BATCH_SIZE: usize = 4;
+
+pub(crate) mod imp {
+    use std::{
+        cell::{
+            Cell,
+            RefCell,
+        },
+        collections::VecDeque,
+        sync::OnceLock,
+    };
+
+    use glib::subclass::{
+        InitializingObject,
+        Signal,
+    };
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/selection_bar.ui")]
+    pub struct SelectionBar {
+        #[template_child]
+        pub revealer: TemplateChild<gtk::Revealer>,
+        #[template_child]
+        pub count_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub progress_bar: TemplateChild<gtk::ProgressBar>,
+        #[template_child]
+        pub actions_button: TemplateChild<gtk::MenuButton>,
+
+        pub model: RefCell<Option<gtk::MultiSelection>>,
+        pub handlers: RefCell<Vec<glib::SignalHandlerId>>,
+        pub running: Cell<bool>,
+        pub identify_queue: RefCell<VecDeque<TuItem>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SelectionBar {
+        const NAME: &'static str = "SelectionBar";
+        type Type = super::SelectionBar;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+            klass.install_action_async("bulk.played", None, |bar, _, _| async move {
+                bar.run_action(Action::Played).await;
+            });
+            klass.install_action_async("bulk.unplayed", None, |bar, _, _| async move {
+                bar.run_action(Action::Unplayed).await;
+            });
+            klass.install_action_async("bulk.like", None, |bar, _, _| async move {
+                bar.run_action(Action::Like).await;
+            });
+            klass.install_action_async("bulk.unlike", None, |bar, _, _| async move {
+                bar.run_action(Action::Unlike).await;
+            });
+            klass.install_action_async("bulk.remove", None, |bar, _, _| async move {
+                bar.run_action(Action::Remove).await;
+            });
+            klass.install_action_async("bulk.add-to-playlist", None, |bar, _, _| async move {
+                bar.add_to_container("Playlist").await;
+            });
+            klass.install_action_async("bulk.add-to-collection", None, |bar, _, _| async move {
+                bar.add_to_container("BoxSet").await;
+            });
+            klass.install_action("bulk.identify", None, |bar, _, _| {
+                bar.identify();
+            });
+            klass.install_action("bulk.refresh", None, |bar, _, _| {
+                bar.refresh();
+            });
+            klass.install_action("bulk.delete", None, |bar, _, _| {
+                bar.delete();
+            });
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SelectionBar {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("done").build(),
+                    Signal::builder("items-removed").build(),
+                ]
+            })
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().update_state();
+        }
+    }
+
+    impl WidgetImpl for SelectionBar {}
+    impl BinImpl for SelectionBar {}
+}
+
+glib::wrapper! {
+    /// Action bar shown while a grid or list is in selection mode.
+    ///
+    /// Runs the item actions on every selected `TuObject` of the attached
+    /// [`gtk::MultiSelection`].
+    pub struct SelectionBar(ObjectSubclass<imp::SelectionBar>)
+        @extends gtk::Widget, adw::Bin, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for SelectionBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[template_callbacks]
+impl SelectionBar {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn set_model(&self, model: Option<&gtk::MultiSelection>) {
+        let imp = self.imp();
+        if let Some(old) = imp.model.take() {
+            for handler in imp.handlers.take() {
+                old.disconnect(handler);
+            }
+        }
+
+        if let Some(model) = model {
+            let selection_handler = model.connect_selection_changed(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _, _| obj.update_state()
+            ));
+            let items_handler = model.connect_items_changed(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _, _, _| obj.update_state()
+            ));
+            imp.handlers.replace(vec![selection_handler, items_handler]);
+        }
+
+        imp.model.replace(model.cloned());
+        self.update_state();
+    }
+
+    pub fn reveal(&self, reveal: bool) {
+        if !reveal && let Some(model) = self.imp().model.borrow().as_ref() {
+            model.unselect_all();
+        }
+        self.imp().revealer.set_reveal_child(reveal);
+    }
+
+    pub fn connect_done<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "done",
+            true,
+            glib::closure_local!(move |obj: Self| {
+                f(&obj);
+            }),
+        )
+    }
+
+    /// Emitted after deleted or hidden items were taken out of the model.
+    pub fn connect_items_removed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "items-removed",
+            true,
+            glib::closure_local!(move |obj: Self| {
+                f(&obj);
+            }),
+        )
+    }
+
+    fn update_state(&self) {
+        let imp = self.imp();
+        let count = imp
+            .model
+            .borrow()
+            .as_ref()
+            .map(|model| model.selection().size())
+            .unwrap_or_default();
+
+        imp.count_label
+            .set_label(&gettext("{count} selected").replace("{count}", &count.to_string()));
+        imp.actions_button
+            .set_sensitive(count > 0 && !imp.running.get());
+
+        let is_admin = IS_ADMIN.load(std::sync::atomic::Ordering::Relaxed);
+        self.action_set_enabled("bulk.identify", is_admin);
+        self.action_set_enabled("bulk.refresh", is_admin);
+        self.action_set_enabled("bulk.delete", is_admin);
+    }
+
+    fn selected_items(&self) -> Vec<TuItem> {
+        let Some(model) = self.imp().model.borrow().clone() else {
+            return Vec::new();
+        };
+        (0..model.n_items())
+            .filter(|position| model.is_selected(*position))
+            .filter_map(|position| model.item(position).and_downcast::<TuObject>())
+            .map(|object| object.item())
+            .collect()
+    }
+
+    #[template_callback]
+    fn on_select_all(&self) {
+        if let Some(model) = self.imp().model.borrow().as_ref() {
+            model.select_all();
+        }
+    }
+
+    #[template_callback]
+    fn on_select_none(&self) {
+        if let Some(model) = self.imp().model.borrow().as_ref() {
+            model.unselect_all();
+        }
+    }
+
+    #[template_callback]
+    fn on_done(&self) {
+        self.emit_by_name::<()>("done", &[]);
+    }
+
+    /// Runs `op` for every item, `BATCH_SIZE` requests at a time, and reports
+    /// the outcome in a toast. Returns the items the request succeeded for.
+    async fn run_batch<F, Fut>(&self, items: Vec<TuItem>, op: F) -> Vec<TuItem>
+    where
+        F: Fn(Arc<JellyfinClient>, String, Option<String>) -> Fut,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let imp = self.imp();
+        let total = items.len();
+        if total == 0 {
+            return Vec::new();
+        }
+
+        imp.running.set(true);
+        self.update_state();
+        imp.progress_bar.set_fraction(0.0);
+        imp.progress_bar.set_text(Some(&format!("0 / {total}")));
+        imp.progress_bar.set_visible(true);
+
+        let mut succeeded = Vec::new();
+        let mut last_error = None;
+        let mut done = 0;
+        for chunk in items.chunks(BATCH_SIZE) {
+            let results = futures_util::future::join_all(
+                chunk
+                    .iter()
+                    .map(|item| spawn_tokio(op(item.client(), item.id(), item.series_id()))),
+            )
+            .await;
+
+            for (item, result) in chunk.iter().zip(results) {
+                match result {
+                    Ok(()) => succeeded.push(item.to_owned()),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+
+            done += chunk.len();
+            imp.progress_bar.set_fraction(done as f64 / total as f64);
+            imp.progress_bar
+                .set_text(Some(&format!("{done} / {total}")));
+        }
+
+        imp.progress_bar.set_visible(false);
+        imp.running.set(false);
+        self.update_state();
+
+        let failed = total - succeeded.len();
+        match last_error {
+            None => {
+                self.toast(gettext("{count} items updated").replace("{count}", &total.to_string()))
+            }
+            Some(e) => self.toast(format!(
+                "{}: {}",
+                gettext("{done} of {total} items updated, {failed} failed")
+                    .replace("{done}", &succeeded.len().to_string())
+                    .replace("{total}", &total.to_string())
+                    .replace("{failed}", &failed.to_string()),
+                e.to_user_facing()
+            )),
+        }
+
+        succeeded
+    }
+
+    async fn run_action(&self, action: Action) {
+        let items = self.selected_items();
+        let succeeded = self
+            .run_batch(items, move |client, id, series_id| async move {
+                perform_item_action(client, id, series_id, &action).await
+            })
+            .await;
+
+        for item in &succeeded {
+            match action {
+                Action::Like => item.set_is_favorite(true),
+                Action::Unlike => item.set_is_favorite(false),
+                Action::Played => item.set_played(true),
+                Action::Unplayed => item.set_played(false),
+                Action::Remove => {}
+            }
+        }
+
+        if matches!(action, Action::Remove) {
+            self.remove_items(&succeeded);
+        }
+    }
+
+    /// Takes `items` out of the underlying `gio::ListStore`.
+    fn remove_items(&self, items: &[TuItem]) {
+        if items.is_empty() {
+            return;
+        }
+        let Some(store) = self
+            .imp()
+            .model
+            .borrow()
+            .as_ref()
+            .and_then(|model| model.model())
+            .and_downcast::<gio::ListStore>()
+        else {
+            return;
+        };
+
+        let ids = items.iter().map(|item| item.id()).collect::<HashSet<_>>();
+        for position in (0..store.n_items()).rev() {
+            if store
+                .item(position)
+                .and_downcast::<TuObject>()
+                .is_some_and(|object| ids.contains(&object.item().id()))
+            {
+                store.remove(position);
+            }
+        }
+        self.emit_by_name::<()>("items-removed", &[]);
+    }
+
+    /// Adds the selection to an existing playlist or collection, or to a new
+    /// one. `item_type` is either `Playlist` or `BoxSet`.
+    async fn add_to_container(&self, item_type: &'static str) {
+        let is_playlist = item_type == "Playlist";
+        let Some(client) = self.container_client(&self.selected_items()) else {
+            return;
+        };
+        let containers =
+            match spawn_tokio(async move { client.get_user_containers(item_type).await }).await {
+                Ok(list) => list.items,
+                Err(e) => {
+                    self.toast(e.to_user_facing());
+                    return;
+                }
+            };
+
+        let names = containers
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>();
+        let container_row = adw::ComboRow::builder()
+            .title(if is_playlist {
+                gettext("Playlist")
+            } else {
+                gettext("Collection")
+            })
+            .model(&gtk::StringList::new(&names))
+            .visible(!containers.is_empty())
+            .build();
+        let name_entry = adw::EntryRow::builder()
+            .title(if is_playlist {
+                gettext("New Playlist")
+            } else {
+                gettext("New Collection")
+            })
+            .build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&container_row);
+        list.append(&name_entry);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(if is_playlist {
+                gettext("Add to Playlist")
+            } else {
+                gettext("Add to Collection")
+            })
+            .body(gettext("Enter a name to create a new one instead"))
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("add", &gettext("Add"))]);
+        dialog.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+        let has_containers = !containers.is_empty();
+        dialog.set_response_enabled("add", has_containers);
+        dialog.set_close_response("cancel");
+
+        name_entry.connect_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                dialog
+                    .set_response_enabled("add", has_containers || !entry.text().trim().is_empty());
+            }
+        ));
+
+        dialog.connect_response(
+            Some("add"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                name_entry,
+                #[weak]
+                container_row,
+                move |_, _| {
+                    let name = name_entry.text().trim().to_string();
+                    let target = containers
+                        .get(container_row.selected() as usize)
+                        .map(|item| item.id.to_owned());
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            obj.add_to_container_inner(is_playlist, name, target).await;
+                        }
+                    ));
+                }
+            ),
+        );
+
+        alert_dialog!(self, dialog);
+    }
+
+    async fn add_to_container_inner(
+        &self, is_playlist: bool, name: String, target: Option<String>,
+    ) {
+        let items = self.selected_items();
+        let Some(client) = self.container_client(&items) else {
+            return;
+        };
+        if !name.is_empty() {
+            let ids = items.iter().map(|item| item.id()).collect::<Vec<_>>();
+            let media_type = playlist_media_type(&items);
+            let result = spawn_tokio(async move {
+                if is_playlist {
+                    client.create_playlist(&name, &ids, media_type).await
+                } else {
+                    client.create_collection(&name, &ids).await
+                }
+            })
+            .await;
+            match result {
+                Ok(()) => self.toast(
+                    gettext("{count} items updated").replace("{count}", &items.len().to_string()),
+                ),
+                Err(e) => self.toast(e.to_user_facing()),
+            }
+            return;
+        }
+
+        let Some(target) = target else {
+            return;
+        };
+        self.run_batch(items, move |client, id, _| {
+            let target = target.to_owned();
+            async move {
+                if is_playlist {
+                    client.add_to_playlist(&target, &[id]).await
+                } else {
+                    client.add_to_collection(&target, &[id]).await
+                }
+            }
+        })
+        .await;
+    }
+
+    /// Client of the server the playlist or collection is on. Items of other
+    /// servers can't go in it, so a selection spanning servers gets `None`.
+    fn container_client(&self, items: &[TuItem]) -> Option<Arc<JellyfinClient>> {
+        let client = items.first()?.client();
+        let key = client.session().account_key();
+        if items
+            .iter()
+            .any(|item| item.client().session().account_key() != key)
+        {
+            self.toast(gettext("Select items from a single server to add them"));
+            return None;
+        }
+        Some(client)
+    }
+
+    /// Opens the identify dialog for each selected item, one after another.
+    fn identify(&self) {
+        let queue = self.selected_items().into_iter().collect();
+        self.imp().identify_queue.replace(queue);
+        self.identify_next();
+    }
+
+    fn identify_next(&self) {
+        let Some(item) = self.imp().identify_queue.borrow_mut().pop_front() else {
+            return;
+        };
+        let dialog = IdentifyDialog::new(&item.id(), &item.item_type(), item.client());
+        dialog.connect_closed(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| obj.identify_next()
+        ));
+        crate::insert_editm_dialog!(self, dialog);
+    }
+
+    fn refresh(&self) {
+        let count = self.selected_items().len();
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Refresh Metadata"))
+            .body(
+                gettext("Refresh metadata of {count} items?")
+                    .replace("{count}", &count.to_string()),
+            )
+            .build();
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("missing", &gettext("Search for Missing Metadata")),
+            ("replace", &gettext("Replace All Metadata")),
+        ]);
+        dialog.set_response_appearance("replace", adw::ResponseAppearance::Destructive);
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, response| {
+                    let replace_metadata = match response {
+                        "missing" => false,
+                        "replace" => true,
+                        _ => return,
+                    };
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            let items = obj.selected_items();
+                            obj.run_batch(items, move |client, id, _| async move {
+                                client
+                                    .fullscan(&id, "false", &replace_metadata.to_string())
+                                    .await?
+                                    .error_for_status()?;
+                                Ok(())
+                            })
+                            .await;
+                        }
+                    ));
+                }
+            ),
+        );
+
+        alert_dialog!(self, dialog);
+    }
+
+    fn delete(&self) {
+        let count = self.selected_items().len();
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Delete Items"))
+            .body(format!(
+                "{}\n{}",
+                gettext("Deleting {count} items will delete them from both the file system and your media library.")
+                    .replace("{count}", &count.to_string()),
+                gettext("Are you sure you wish to continue?")
+            ))
+            .build();
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("delete", &gettext("Delete")),
+        ]);
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("delete"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _| {
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            let items = obj.selected_items();
+                            let deleted = obj
+                                .run_batch(items, |client, id, _| async move {
+                                    client.delete_item(&id).await?.error_for_status()?;
+                                    Ok(())
+                                })
+                                .await;
+                            obj.remove_items(&deleted);
+                        }
+                    ));
+                }
+            ),
+        );
+
+        alert_dialog!(self, dialog);
+    }
+}
+
+/// Music selections make an audio playlist, everything else a video one.
+fn playlist_media_type(items: &[TuItem]) -> &'static str {
+    let is_music = |item: &TuItem| {
+        matches!(
+            item.item_type().as_str(),
+            AUDIO | MUSIC_ALBUM | MUSIC_ARTIST | MUSIC_GENRE
+        )
+    };
+    if !items.is_empty() && items.iter().all(is_music) {
+        "Audio"
+    } else {
+        "Video"
+    }
+}
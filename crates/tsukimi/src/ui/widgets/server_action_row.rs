@@ -16,6 +16,7 @@ use crate::{
     client::{
         Account,
         account::ServerType,
+        registry::CLIENTS,
     },
     ui::{
         models::SETTINGS,
@@ -29,14 +30,9 @@ mod imp {
     use glib::subclass::InitializingObject;
 
     use super::*;
-    use crate::{
-        client::jellyfin_client::JELLYFIN_CLIENT,
-        ui::{
-            models::SETTINGS,
-            provider::account_item::AccountItem,
-            widgets::window::Window,
-        },
-        utils::spawn,
+    use crate::ui::{
+        provider::account_item::AccountItem,
+        widgets::window::Window,
     };
 
     #[derive(Debug, Default, CompositeTemplate, glib::Properties)]
@@ -88,19 +84,9 @@ mod imp {
     impl ActionRowImpl for ServerActionRow {
         fn activate(&self) {
             let obj = self.obj();
-
-            spawn(glib::clone!(
-                #[weak]
-                obj,
-                async move {
-                    let account = obj.item().account();
-                    SETTINGS.set_preferred_server(&account.servername).unwrap();
-                    let _ = JELLYFIN_CLIENT.init(&account).await;
-                    if let Some(w) = obj.root().and_downcast::<Window>() {
-                        w.reset()
-                    }
-                }
-            ));
+            if let Some(w) = obj.root().and_downcast::<Window>() {
+                w.switch_account(&obj.item().account());
+            }
         }
     }
 }
@@ -156,6 +142,7 @@ impl ServerActionRow {
     #[template_callback]
     async fn on_delete_clicked(&self) {
         let account = self.item().account();
+        CLIENTS.remove(&account);
         SETTINGS
             .remove_account(account)
             .expect("Failed to remove server");
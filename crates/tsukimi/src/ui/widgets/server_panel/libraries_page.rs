@@ -0,0 +1,618 @@
+use std::{
+    cell::{
+        Cell,
+        OnceCell,
+        RefCell,
+    },
+    collections::HashMap,
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use super::ServerLibraryOptionsDialog;
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        structs::VirtualFolder,
+    },
+    fraction,
+    fraction_reset,
+    ui::{
+        provider::IS_ADMIN,
+        widgets::utils::GlobalToast,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+const COLLECTION_TYPES: [Option<&str>; 8] = [
+    Some("movies"),
+    Some("tvshows"),
+    Some("music"),
+    Some("musicvideos"),
+    Some("homevideos"),
+    Some("boxsets"),
+    Some("books"),
+    None,
+];
+
+const PROGRESS_POLL_SECONDS: u32 = 3;
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_libraries_page.ui")]
+    pub struct ServerLibrariesPage {
+        #[template_child]
+        pub libraries_list: TemplateChild<gtk::ListBox>,
+
+        // Scan progress bars keyed by library item id
+        pub progress_bars: RefCell<HashMap<String, gtk::ProgressBar>>,
+        pub refreshing: Cell<bool>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerLibrariesPage {
+        const NAME: &'static str = "ServerLibrariesPage";
+        type Type = super::ServerLibrariesPage;
+        type ParentType = adw::NavigationPage;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerLibrariesPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.refresh();
+            obj.watch_progress();
+        }
+    }
+
+    impl WidgetImpl for ServerLibrariesPage {}
+    impl NavigationPageImpl for ServerLibrariesPage {}
+}
+
+glib::wrapper! {
+    pub struct ServerLibrariesPage(ObjectSubclass<imp::ServerLibrariesPage>)
+        @extends gtk::Widget, adw::NavigationPage, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl ServerLibrariesPage {
+    pub fn new(client: Arc<JellyfinClient>) -> Self {
+        let page: Self = glib::Object::new();
+        let _ = page.imp().client.set(client);
+        page
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerLibrariesPage is created with a client")
+            .clone()
+    }
+
+    pub fn refresh(&self) {
+        if !IS_ADMIN.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                fraction_reset!(obj);
+                obj.set_libraries().await;
+                fraction!(obj);
+            }
+        ));
+    }
+
+    fn watch_progress(&self) {
+        glib::timeout_add_seconds_local(
+            PROGRESS_POLL_SECONDS,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    if obj.imp().refreshing.get() && obj.is_mapped() {
+                        spawn(glib::clone!(
+                            #[weak]
+                            obj,
+                            async move {
+                                obj.update_progress().await;
+                            }
+                        ));
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    async fn set_libraries(&self) {
+        let client = self.client();
+        let folders = match spawn_tokio(async move { client.get_virtual_folders().await }).await {
+            Ok(folders) => folders,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        let imp = self.imp();
+        imp.libraries_list.remove_all();
+        imp.progress_bars.borrow_mut().clear();
+
+        for folder in &folders {
+            let row = self.library_row(folder);
+            imp.libraries_list.append(&row);
+        }
+
+        self.set_progress(&folders);
+    }
+
+    async fn update_progress(&self) {
+        let client = self.client();
+        match spawn_tokio(async move { client.get_virtual_folders().await }).await {
+            Ok(folders) => self.set_progress(&folders),
+            Err(e) => {
+                self.imp().refreshing.set(false);
+                self.toast(e.to_user_facing());
+            }
+        }
+    }
+
+    fn set_progress(&self, folders: &[VirtualFolder]) {
+        let imp = self.imp();
+        let progress_bars = imp.progress_bars.borrow();
+
+        for folder in folders {
+            let Some(progress_bar) = progress_bars.get(&folder.item_id) else {
+                continue;
+            };
+            progress_bar.set_visible(folder.is_refreshing());
+            progress_bar.set_fraction(folder.refresh_progress.unwrap_or_default() / 100.0);
+        }
+
+        imp.refreshing
+            .set(folders.iter().any(VirtualFolder::is_refreshing));
+    }
+
+    fn library_row(&self, folder: &VirtualFolder) -> adw::ExpanderRow {
+        let row = adw::ExpanderRow::builder()
+            .title(&folder.name)
+            .subtitle(collection_type_label(folder.collection_type.as_deref()))
+            .build();
+
+        let progress_bar = gtk::ProgressBar::builder()
+            .valign(gtk::Align::Center)
+            .width_request(80)
+            .visible(false)
+            .build();
+        row.add_suffix(&progress_bar);
+        self.imp()
+            .progress_bars
+            .borrow_mut()
+            .insert(folder.item_id.to_owned(), progress_bar);
+
+        for location in &folder.locations {
+            let path_row = adw::ActionRow::builder()
+                .title(location)
+                .title_selectable(true)
+                .build();
+            path_row.add_prefix(&gtk::Image::from_icon_name("folder-symbolic"));
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(gettext("Remove Path"))
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            let name = folder.name.to_owned();
+            let path = location.to_owned();
+            remove_button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.on_remove_media_path(name.to_owned(), path.to_owned());
+                }
+            ));
+            path_row.add_suffix(&remove_button);
+
+            row.add_row(&path_row);
+        }
+
+        let add_path_row = adw::ButtonRow::builder()
+            .title(gettext("Add Path"))
+            .start_icon_name("list-add-symbolic")
+            .build();
+        let name = folder.name.to_owned();
+        add_path_row.connect_activated(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                obj.on_add_path(name.to_owned());
+            }
+        ));
+        row.add_row(&add_path_row);
+
+        let scan_row = adw::ButtonRow::builder()
+            .title(gettext("Scan Library"))
+            .start_icon_name("view-refresh-symbolic")
+            .build();
+        let id = folder.item_id.to_owned();
+        scan_row.connect_activated(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                obj.scan_library(id.to_owned());
+            }
+        ));
+        row.add_row(&scan_row);
+
+        let options_row = adw::ButtonRow::builder()
+            .title(gettext("Library Options"))
+            .start_icon_name("emblem-system-symbolic")
+            .build();
+        let options_folder = folder.to_owned();
+        options_row.connect_activated(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                let dialog =
+                    ServerLibraryOptionsDialog::new(options_folder.to_owned(), obj.client());
+                dialog.connect_closed(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_| {
+                        obj.refresh();
+                    }
+                ));
+                dialog.present(Some(&obj));
+            }
+        ));
+        row.add_row(&options_row);
+
+        let rename_row = adw::ButtonRow::builder()
+            .title(gettext("Rename"))
+            .start_icon_name("document-edit-symbolic")
+            .build();
+        let name = folder.name.to_owned();
+        rename_row.connect_activated(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                obj.on_rename_library(name.to_owned());
+            }
+        ));
+        row.add_row(&rename_row);
+
+        let remove_row = adw::ButtonRow::builder()
+            .title(gettext("Remove Library"))
+            .start_icon_name("user-trash-symbolic")
+            .css_classes(["destructive-action"])
+            .build();
+        let name = folder.name.to_owned();
+        remove_row.connect_activated(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                obj.on_remove_library(name.to_owned());
+            }
+        ));
+        row.add_row(&remove_row);
+
+        row
+    }
+
+    fn run_and_refresh<F, Fut>(&self, op: F, message: String)
+    where
+        F: FnOnce(Arc<JellyfinClient>) -> Fut + 'static,
+        Fut: std::future::Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                match spawn_tokio(op(obj.client())).await {
+                    Ok(_) => {
+                        obj.toast(message);
+                        obj.refresh();
+                    }
+                    Err(e) => obj.toast(e.to_user_facing()),
+                }
+            }
+        ));
+    }
+
+    fn scan_library(&self, id: String) {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let client = obj.client();
+                match spawn_tokio(async move { client.scan(&id).await }).await {
+                    Ok(_) => {
+                        obj.toast(gettext("Scanning..."));
+                        obj.imp().refreshing.set(true);
+                    }
+                    Err(e) => obj.toast(e.to_user_facing()),
+                }
+            }
+        ));
+    }
+
+    #[template_callback]
+    fn on_scan_all(&self) {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let client = obj.client();
+                match spawn_tokio(async move { client.refresh_libraries().await }).await {
+                    Ok(_) => {
+                        obj.toast(gettext("Scanning..."));
+                        obj.imp().refreshing.set(true);
+                    }
+                    Err(e) => obj.toast(e.to_user_facing()),
+                }
+            }
+        ));
+    }
+
+    fn on_remove_media_path(&self, name: String, path: String) {
+        let dialog = adw::AlertDialog::new(
+            Some(&gettext("Remove Path")),
+            Some(
+                &gettext("Are you sure you want to remove {path} from {name}?")
+                    .replace("{path}", &path)
+                    .replace("{name}", &name),
+            ),
+        );
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("remove", &gettext("Remove")),
+        ]);
+        dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("remove"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _| {
+                    let name = name.to_owned();
+                    let path = path.to_owned();
+                    obj.run_and_refresh(
+                        move |client| async move { client.remove_media_path(&name, &path).await },
+                        gettext("Path removed"),
+                    );
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    fn on_add_path(&self, name: String) {
+        let path_entry = adw::EntryRow::builder()
+            .title(gettext("Folder Path on Server"))
+            .build();
+        let dialog = entry_dialog(&gettext("Add Path"), &gettext("Add"), &path_entry);
+
+        dialog.connect_response(
+            Some("confirm"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                path_entry,
+                move |_, _| {
+                    let name = name.to_owned();
+                    let path = path_entry.text().trim().to_string();
+                    obj.run_and_refresh(
+                        move |client| async move { client.add_media_path(&name, &path).await },
+                        gettext("Path added"),
+                    );
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    fn on_rename_library(&self, name: String) {
+        let name_entry = adw::EntryRow::builder()
+            .title(gettext("Name"))
+            .text(&name)
+            .build();
+        let dialog = entry_dialog(&gettext("Rename Library"), &gettext("Rename"), &name_entry);
+
+        dialog.connect_response(
+            Some("confirm"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                name_entry,
+                move |_, _| {
+                    let name = name.to_owned();
+                    let new_name = name_entry.text().trim().to_string();
+                    if new_name == name {
+                        return;
+                    }
+                    obj.run_and_refresh(
+                        move |client| async move {
+                            client.rename_virtual_folder(&name, &new_name).await
+                        },
+                        gettext("Library renamed"),
+                    );
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    fn on_remove_library(&self, name: String) {
+        let dialog = adw::AlertDialog::new(
+            Some(&gettext("Remove Library")),
+            Some(&gettext("Are you sure you want to remove {name}?").replace("{name}", &name)),
+        );
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("remove", &gettext("Remove")),
+        ]);
+        dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("remove"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _| {
+                    let name = name.to_owned();
+                    obj.run_and_refresh(
+                        move |client| async move { client.remove_virtual_folder(&name).await },
+                        gettext("Library removed"),
+                    );
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    #[template_callback]
+    fn on_add_library(&self) {
+        let name_entry = adw::EntryRow::builder().title(gettext("Name")).build();
+
+        let type_names = COLLECTION_TYPES
+            .iter()
+            .map(|collection_type| collection_type_label(*collection_type))
+            .collect::<Vec<_>>();
+        let type_model =
+            gtk::StringList::new(&type_names.iter().map(String::as_str).collect::<Vec<_>>());
+        let type_row = adw::ComboRow::builder()
+            .title(gettext("Content Type"))
+            .model(&type_model)
+            .build();
+
+        let path_entry = adw::EntryRow::builder()
+            .title(gettext("Folder Path on Server"))
+            .build();
+
+        let dialog = entry_dialog(&gettext("Add Library"), &gettext("Add"), &name_entry);
+        if let Some(list) = dialog.extra_child().and_downcast::<gtk::ListBox>() {
+            list.append(&type_row);
+            list.append(&path_entry);
+        }
+
+        dialog.connect_response(
+            Some("confirm"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                name_entry,
+                #[weak]
+                type_row,
+                #[weak]
+                path_entry,
+                move |_, _| {
+                    let name = name_entry.text().trim().to_string();
+                    let path = path_entry.text().trim().to_string();
+                    let collection_type = COLLECTION_TYPES
+                        .get(type_row.selected() as usize)
+                        .copied()
+                        .flatten();
+                    obj.run_and_refresh(
+                        move |client| async move {
+                            client
+                                .add_virtual_folder(&name, collection_type, &path)
+                                .await
+                        },
+                        gettext("Library added"),
+                    );
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+}
+
+// An alert dialog with a boxed list holding `entry`, confirming is only possible with some text
+fn entry_dialog(heading: &str, confirm: &str, entry: &adw::EntryRow) -> adw::AlertDialog {
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+    list.append(entry);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(heading)
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[("cancel", &gettext("Cancel")), ("confirm", confirm)]);
+    dialog.set_response_appearance("confirm", adw::ResponseAppearance::Suggested);
+    dialog.set_response_enabled("confirm", !entry.text().trim().is_empty());
+    dialog.set_close_response("cancel");
+
+    entry.connect_changed(glib::clone!(
+        #[weak]
+        dialog,
+        move |entry| {
+            dialog.set_response_enabled("confirm", !entry.text().trim().is_empty());
+        }
+    ));
+
+    dialog
+}
+
+fn collection_type_label(collection_type: Option<&str>) -> String {
+    match collection_type {
+        Some("movies") => gettext("Movies"),
+        Some("tvshows") => gettext("Shows"),
+        Some("music") => gettext("Music"),
+        Some("musicvideos") => gettext("Music Videos"),
+        Some("homevideos") => gettext("Home Videos and Photos"),
+        Some("boxsets") => gettext("Collections"),
+        Some("books") => gettext("Books"),
+        Some(other) => other.to_string(),
+        None => gettext("Mixed Content"),
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    cell::{
+        OnceCell,
+        RefCell,
+    },
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        structs::VirtualFolder,
+    },
+    ui::widgets::utils::GlobalToast,
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_library_options_dialog.ui")]
+    pub struct ServerLibraryOptionsDialog {
+        #[template_child]
+        pub realtime_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub language_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub language_list: TemplateChild<gtk::StringList>,
+        #[template_child]
+        pub trickplay_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub chapter_images_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub chapter_scan_row: TemplateChild<adw::SwitchRow>,
+
+        pub folder: RefCell<Option<VirtualFolder>>,
+        // Language codes in the same order as `language_list`, `None` for the server default
+        pub language_codes: RefCell<Vec<Option<String>>>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerLibraryOptionsDialog {
+        const NAME: &'static str = "ServerLibraryOptionsDialog";
+        type Type = super::ServerLibraryOptionsDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerLibraryOptionsDialog {}
+    impl WidgetImpl for ServerLibraryOptionsDialog {}
+    impl AdwDialogImpl for ServerLibraryOptionsDialog {}
+}
+
+glib::wrapper! {
+    pub struct ServerLibraryOptionsDialog(ObjectSubclass<imp::ServerLibraryOptionsDialog>)
+        @extends gtk::Widget, adw::Dialog, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl ServerLibraryOptionsDialog {
+    pub fn new(folder: VirtualFolder, client: Arc<JellyfinClient>) -> Self {
+        let obj: Self = glib::Object::new();
+        let _ = obj.imp().client.set(client);
+        obj.set_folder(folder);
+        obj
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerLibraryOptionsDialog is created with a client")
+            .clone()
+    }
+
+    fn set_folder(&self, folder: VirtualFolder) {
+        let imp = self.imp();
+        let options = &folder.library_options;
+
+        self.set_title(&folder.name);
+        imp.realtime_row.set_active(options.enable_realtime_monitor);
+        imp.trickplay_row
+            .set_active(options.enable_trickplay_image_extraction);
+        imp.trickplay_row.set_visible(self.client().is_jellyfin());
+        imp.chapter_images_row
+            .set_active(options.enable_chapter_image_extraction);
+        imp.chapter_scan_row
+            .set_active(options.extract_chapter_images_during_library_scan);
+
+        imp.folder.replace(Some(folder));
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                obj.set_languages().await;
+            }
+        ));
+    }
+
+    async fn set_languages(&self) {
+        let client = self.client();
+        let cultures = match spawn_tokio(async move { client.get_cultures().await }).await {
+            Ok(cultures) => cultures,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        let imp = self.imp();
+        let current = imp.folder.borrow().as_ref().and_then(|folder| {
+            folder
+                .library_options
+                .preferred_metadata_language
+                .to_owned()
+        });
+
+        let mut names = vec![gettext("Server Default")];
+        let mut codes = vec![None];
+        for culture in cultures {
+            names.push(culture.display_name);
+            codes.push(Some(culture.two_letter_iso_language_name));
+        }
+
+        imp.language_list
+            .splice(0, 0, &names.iter().map(String::as_str).collect::<Vec<_>>());
+        let selected = codes
+            .iter()
+            .position(|code| {
+                code.as_deref()
+                    .zip(current.as_deref())
+                    .is_some_and(|(a, b)| a.eq_ignore_ascii_case(b))
+            })
+            .unwrap_or(0);
+        imp.language_row.set_selected(selected as u32);
+        imp.language_codes.replace(codes);
+    }
+
+    #[template_callback]
+    async fn on_save(&self) {
+        let imp = self.imp();
+        let Some(folder) = imp.folder.borrow().to_owned() else {
+            return;
+        };
+
+        let mut options = folder.library_options.to_owned();
+        options.enable_realtime_monitor = imp.realtime_row.is_active();
+        options.enable_trickplay_image_extraction = imp.trickplay_row.is_active();
+        options.enable_chapter_image_extraction = imp.chapter_images_row.is_active();
+        options.extract_chapter_images_during_library_scan = imp.chapter_scan_row.is_active();
+        if let Some(code) = imp
+            .language_codes
+            .borrow()
+            .get(imp.language_row.selected() as usize)
+        {
+            options.preferred_metadata_language = code.to_owned();
+        }
+
+        let id = folder.item_id.to_owned();
+        let new_options = options.to_owned();
+        let client = self.client();
+        match spawn_tokio(async move { client.update_library_options(&id, &new_options).await })
+            .await
+        {
+            Ok(_) => {
+                let mut folder = folder;
+                folder.library_options = options;
+                imp.folder.replace(Some(folder));
+                self.toast(gettext("Library options saved"));
+            }
+            Err(e) => self.toast(e.to_user_facing()),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    cell::{
+        OnceCell,
+        RefCell,
+    },
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    gio,
+    glib,
+    template_callbacks,
+};
+
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+    },
+    fraction,
+    fraction_reset,
+    ui::{
+        provider::IS_ADMIN,
+        widgets::utils::GlobalToast,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+const LIVE_TAIL_SECONDS: u32 = 5;
+
+// One log message, including the continuation lines of stack traces
+pub struct LogEntry {
+    level: u32,
+    text: String,
+}
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_logs_page.ui")]
+    pub struct ServerLogsPage {
+        #[template_child]
+        pub file_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub file_list: TemplateChild<gtk::StringList>,
+        #[template_child]
+        pub search_entry: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
+        pub level_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub live_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub scrolled: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub text_view: TemplateChild<gtk::TextView>,
+
+        pub content: RefCell<String>,
+        pub entries: RefCell<Vec<LogEntry>>,
+        pub live_timeout: RefCell<Option<glib::SourceId>>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerLogsPage {
+        const NAME: &'static str = "ServerLogsPage";
+        type Type = super::ServerLogsPage;
+        type ParentType = adw::NavigationPage;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerLogsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_up();
+        }
+
+        fn dispose(&self) {
+            if let Some(timeout) = self.live_timeout.take() {
+                timeout.remove();
+            }
+        }
+    }
+
+    impl WidgetImpl for ServerLogsPage {}
+    impl NavigationPageImpl for ServerLogsPage {}
+}
+
+glib::wrapper! {
+    pub struct ServerLogsPage(ObjectSubclass<imp::ServerLogsPage>)
+        @extends gtk::Widget, adw::NavigationPage, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl ServerLogsPage {
+    pub fn new(client: Arc<JellyfinClient>) -> Self {
+        let page: Self = glib::Object::new();
+        let _ = page.imp().client.set(client);
+        page
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerLogsPage is created with a client")
+            .clone()
+    }
+
+    fn set_up(&self) {
+        if !IS_ADMIN.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let client = obj.client();
+                let files = match spawn_tokio(async move { client.get_log_files().await }).await {
+                    Ok(files) => files,
+                    Err(e) => {
+                        obj.toast(e.to_user_facing());
+                        return;
+                    }
+                };
+
+                // Selecting the first file loads it
+                let names = files
+                    .iter()
+                    .map(|file| file.name.as_str())
+                    .collect::<Vec<_>>();
+                obj.imp().file_list.splice(0, 0, &names);
+            }
+        ));
+    }
+
+    fn selected_file(&self) -> Option<String> {
+        let imp = self.imp();
+        imp.file_list
+            .string(imp.file_dropdown.selected())
+            .map(|name| name.to_string())
+    }
+
+    #[template_callback]
+    fn on_file_selected(&self) {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                fraction_reset!(obj);
+                obj.load_log().await;
+                fraction!(obj);
+            }
+        ));
+    }
+
+    async fn load_log(&self) {
+        let Some(name) = self.selected_file() else {
+            return;
+        };
+
+        let client = self.client();
+        let content = match spawn_tokio(async move { client.get_log_file(&name).await }).await {
+            Ok(content) => content,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        let imp = self.imp();
+        // Skip re-rendering when tailing a log that hasn't changed
+        if *imp.content.borrow() == content {
+            return;
+        }
+
+        imp.entries.replace(parse_log(&content));
+        imp.content.replace(content);
+        self.render();
+    }
+
+    #[template_callback]
+    fn on_filter_changed(&self) {
+        self.render();
+    }
+
+    fn render(&self) {
+        let imp = self.imp();
+        let query = imp.search_entry.text().to_lowercase();
+        let min_level = imp.level_dropdown.selected();
+
+        let text = imp
+            .entries
+            .borrow()
+            .iter()
+            .filter(|entry| entry.level >= min_level)
+            .filter(|entry| query.is_empty() || entry.text.to_lowercase().contains(&query))
+            .map(|entry| entry.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let buffer = imp.text_view.buffer();
+        buffer.set_text(&text);
+
+        if imp.live_button.is_active() {
+            let mark = buffer.create_mark(None, &buffer.end_iter(), false);
+            imp.text_view.scroll_to_mark(&mark, 0.0, false, 0.0, 1.0);
+            buffer.delete_mark(&mark);
+        }
+    }
+
+    #[template_callback]
+    fn on_live_toggled(&self) {
+        let imp = self.imp();
+
+        if let Some(timeout) = imp.live_timeout.take() {
+            timeout.remove();
+        }
+
+        if !imp.live_button.is_active() {
+            return;
+        }
+
+        self.on_file_selected();
+
+        let timeout = glib::timeout_add_seconds_local(
+            LIVE_TAIL_SECONDS,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    if obj.is_mapped() {
+                        spawn(glib::clone!(
+                            #[weak]
+                            obj,
+                            async move {
+                                obj.load_log().await;
+                            }
+                        ));
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+        imp.live_timeout.replace(Some(timeout));
+    }
+
+    #[template_callback]
+    async fn on_save(&self) {
+        let Some(name) = self.selected_file() else {
+            return;
+        };
+
+        let filedialog = gtk::FileDialog::builder()
+            .modal(true)
+            .title(gettext("Save Log File"))
+            .initial_name(&name)
+            .build();
+
+        let root = self.root().and_downcast::<gtk::Window>();
+        let Ok(file) = filedialog.save_future(root.as_ref()).await else {
+            return;
+        };
+
+        let content = self.imp().content.borrow().to_owned();
+        match file
+            .replace_contents_future(
+                content.into_bytes(),
+                None,
+                false,
+                gio::FileCreateFlags::REPLACE_DESTINATION,
+            )
+            .await
+        {
+            Ok(_) => self.toast(gettext("Log file saved")),
+            Err((_, e)) => self.toast(e.to_string()),
+        }
+    }
+}
+
+fn parse_log(content: &str) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+
+    for line in content.lines() {
+        match line_level(line) {
+            Some(level) => entries.push(LogEntry {
+                level,
+                text: line.to_string(),
+            }),
+            None => match entries.last_mut() {
+                Some(entry) => {
+                    entry.text.push('\n');
+                    entry.text.push_str(line);
+                }
+                None => entries.push(LogEntry {
+                    level: 0,
+                    text: line.to_string(),
+                }),
+            },
+        }
+    }
+
+    entries
+}
+
+// Ranks match the rows of the level dropdown, 0 is shown at every level.
+// Jellyfin writes `[date] [INF] [thread] message`, Emby writes `date time Info source: message`
+fn line_level(line: &str) -> Option<u32> {
+    let token = if line.starts_with('[') {
+        line.split("] [").nth(1)?.split(']').next()?
+    } else if line.starts_with(|c: char| c.is_ascii_digit()) {
+        line.split_whitespace().nth(2)?
+    } else {
+        return None;
+    };
+
+    match token {
+        "VRB" | "Trace" => Some(0),
+        "DBG" | "Debug" => Some(1),
+        "INF" | "Info" => Some(2),
+        "WRN" | "Warn" => Some(3),
+        "ERR" | "Error" => Some(4),
+        "FTL" | "Fatal" => Some(5),
+        _ => None,
+    }
+}
@@ -0,0 +1,20 @@
+mod libraries_page;
+mod library_options_dialog;
+mod logs_page;
+mod panel;
+mod plugins_page;
+mod sessions_page;
+mod user_dialog;
+mod users_page;
+
+pub use libraries_page::ServerLibrariesPage;
+pub use library_options_dialog::ServerLibraryOptionsDialog;
+pub use logs_page::ServerLogsPage;
+pub use panel::{
+    ServerPanel,
+    utc_to_localstring,
+};
+pub use plugins_page::ServerPluginsPage;
+pub use sessions_page::ServerSessionsPage;
+pub use user_dialog::ServerUserDialog;
+pub use users_page::ServerUsersPage;
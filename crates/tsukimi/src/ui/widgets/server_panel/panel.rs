@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use adw::{
     prelude::*,
     subclass::prelude::*,
@@ -20,7 +22,8 @@ use gtk::{
 use crate::{
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
+        registry::CLIENTS,
     },
     fraction,
     fraction_reset,
@@ -30,9 +33,21 @@ use crate::{
     },
 };
 
-use super::utils::GlobalToast;
+use super::{
+    ServerLibrariesPage,
+    ServerLogsPage,
+    ServerPluginsPage,
+    ServerSessionsPage,
+    ServerUsersPage,
+};
+use crate::ui::widgets::{
+    utils::GlobalToast,
+    window::Window,
+};
 
 pub(crate) mod imp {
+    use std::cell::OnceCell;
+
     use glib::subclass::InitializingObject;
 
     use super::*;
@@ -50,6 +65,10 @@ pub(crate) mod imp {
         pub activity_log_group: TemplateChild<adw::PreferencesGroup>,
         #[template_child]
         pub task_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub plugins_row: TemplateChild<adw::ActionRow>,
+        /// Server the panel manages, the panel is rebuilt when switching accounts
+        pub client: OnceCell<Arc<JellyfinClient>>,
     }
 
     #[glib::object_subclass]
@@ -71,6 +90,7 @@ pub(crate) mod imp {
     impl ObjectImpl for ServerPanel {
         fn constructed(&self) {
             self.parent_constructed();
+            let _ = self.client.set(CLIENTS.active());
             let obj = self.obj();
             obj.set_up();
         }
@@ -98,7 +118,20 @@ impl ServerPanel {
         glib::Object::new()
     }
 
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerPanel is constructed")
+            .clone()
+    }
+
     pub fn set_up(&self) {
+        // Plugin management uses the Jellyfin API
+        self.imp()
+            .plugins_row
+            .set_visible(self.client().is_jellyfin());
+
         spawn(glib::clone!(
             #[weak(rename_to = obj)]
             self,
@@ -113,6 +146,47 @@ impl ServerPanel {
         ));
     }
 
+    #[template_callback]
+    fn on_users_activated(&self) {
+        self.push_manage_page(&ServerUsersPage::new(self.client()), &gettext("Users"));
+    }
+
+    #[template_callback]
+    fn on_libraries_activated(&self) {
+        self.push_manage_page(
+            &ServerLibrariesPage::new(self.client()),
+            &gettext("Libraries"),
+        );
+    }
+
+    #[template_callback]
+    fn on_plugins_activated(&self) {
+        self.push_manage_page(&ServerPluginsPage::new(self.client()), &gettext("Plugins"));
+    }
+
+    #[template_callback]
+    fn on_logs_activated(&self) {
+        self.push_manage_page(&ServerLogsPage::new(self.client()), &gettext("Log Files"));
+    }
+
+    #[template_callback]
+    fn on_sessions_activated(&self) {
+        self.push_manage_page(
+            &ServerSessionsPage::new(self.client()),
+            &gettext("Sessions"),
+        );
+    }
+
+    fn push_manage_page<T>(&self, page: &T, name: &str)
+    where
+        T: NavigationPageExt,
+    {
+        let Some(window) = self.root().and_downcast::<Window>() else {
+            return;
+        };
+        window.push_page(page, name, name);
+    }
+
     #[template_callback]
     fn on_shutdown(&self) {
         let dialog = adw::AlertDialog::new(
@@ -147,41 +221,12 @@ impl ServerPanel {
 
     #[template_callback]
     fn on_restart(&self) {
-        let dialog = adw::AlertDialog::new(
-            Some(&gettext("Restart server")),
-            Some(&gettext("Are you sure you want to restart the server?")),
-        );
-        dialog.add_responses(&[
-            ("revert", &gettext("Revert")),
-            ("confirm", &gettext("Confirm")),
-        ]);
-        dialog.set_response_appearance("revert", adw::ResponseAppearance::Destructive);
-        dialog.set_default_response(Some("revert"));
-        dialog.set_close_response("revert");
-        dialog.connect_response(
-            Some("confirm"),
-            glib::clone!(
-                #[weak(rename_to = obj)]
-                self,
-                move |dialog, _| {
-                    spawn(glib::clone!(
-                        #[weak]
-                        obj,
-                        #[weak]
-                        dialog,
-                        async move {
-                            obj.restart().await;
-                            dialog.close();
-                        }
-                    ));
-                }
-            ),
-        );
-        dialog.present(Some(self));
+        present_restart_dialog(self, self.client());
     }
 
     async fn shot_down(&self) {
-        match spawn_tokio(JELLYFIN_CLIENT.shut_down()).await {
+        let client = self.client();
+        match spawn_tokio(async move { client.shut_down().await }).await {
             Ok(_) => (),
             Err(e) => {
                 self.toast(e.to_user_facing());
@@ -192,20 +237,9 @@ impl ServerPanel {
         self.toast(gettext("Server is shutting down"));
     }
 
-    async fn restart(&self) {
-        match spawn_tokio(JELLYFIN_CLIENT.restart()).await {
-            Ok(_) => (),
-            Err(e) => {
-                self.toast(e.to_user_facing());
-                return;
-            }
-        };
-
-        self.toast(gettext("Server is restarting"));
-    }
-
     async fn set_server_info(&self) {
-        let server_info = match spawn_tokio(JELLYFIN_CLIENT.get_server_info()).await {
+        let client = self.client();
+        let server_info = match spawn_tokio(async move { client.get_server_info().await }).await {
             Ok(server_info) => server_info,
             Err(e) => {
                 self.toast(e.to_user_facing());
@@ -220,7 +254,8 @@ impl ServerPanel {
     }
 
     async fn set_server_logs(&self) {
-        let logs = match spawn_tokio(JELLYFIN_CLIENT.get_activity_log(false)).await {
+        let client = self.client();
+        let logs = match spawn_tokio(async move { client.get_activity_log(false).await }).await {
             Ok(logs) => logs,
             Err(e) => {
                 self.toast(e.to_user_facing());
@@ -236,8 +271,14 @@ impl ServerPanel {
                 .subtitle(utc_to_localstring(&log.date))
                 .build();
 
+            let icon_name = match log.severity.as_deref() {
+                Some("Error" | "Critical") => "dialog-error-symbolic",
+                Some("Warning") => "dialog-warning-symbolic",
+                _ => "dialog-information-symbolic",
+            };
+
             let icon = Image::builder()
-                .icon_name("dialog-error-symbolic")
+                .icon_name(icon_name)
                 .icon_size(gtk::IconSize::Large)
                 .build();
 
@@ -248,7 +289,8 @@ impl ServerPanel {
     }
 
     async fn set_activity_logs(&self) {
-        let logs = match spawn_tokio(JELLYFIN_CLIENT.get_activity_log(true)).await {
+        let client = self.client();
+        let logs = match spawn_tokio(async move { client.get_activity_log(true).await }).await {
             Ok(logs) => logs,
             Err(e) => {
                 self.toast(e.to_user_facing());
@@ -273,7 +315,8 @@ impl ServerPanel {
     }
 
     async fn set_tasks(&self) {
-        let tasks = match spawn_tokio(JELLYFIN_CLIENT.get_scheduled_tasks()).await {
+        let client = self.client();
+        let tasks = match spawn_tokio(async move { client.get_scheduled_tasks().await }).await {
             Ok(tasks) => tasks,
             Err(e) => {
                 self.toast(e.to_user_facing());
@@ -336,7 +379,8 @@ impl ServerPanel {
 
     pub async fn run_task(&self, id: &str) {
         let id = id.to_string();
-        match spawn_tokio(JELLYFIN_CLIENT.run_scheduled_task(id)).await {
+        let client = self.client();
+        match spawn_tokio(async move { client.run_scheduled_task(id).await }).await {
             Ok(result) => result,
             Err(e) => {
                 self.toast(e.to_user_facing());
@@ -348,6 +392,55 @@ impl ServerPanel {
     }
 }
 
+pub fn present_restart_dialog(widget: &impl IsA<gtk::Widget>, client: Arc<JellyfinClient>) {
+    let widget = widget.upcast_ref::<gtk::Widget>().to_owned();
+    let dialog = adw::AlertDialog::new(
+        Some(&gettext("Restart server")),
+        Some(&gettext("Are you sure you want to restart the server?")),
+    );
+    dialog.add_responses(&[
+        ("revert", &gettext("Revert")),
+        ("confirm", &gettext("Confirm")),
+    ]);
+    dialog.set_response_appearance("revert", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("revert"));
+    dialog.set_close_response("revert");
+    dialog.connect_response(
+        Some("confirm"),
+        glib::clone!(
+            #[weak]
+            widget,
+            move |dialog, _| {
+                spawn(glib::clone!(
+                    #[weak]
+                    widget,
+                    #[weak]
+                    dialog,
+                    #[strong]
+                    client,
+                    async move {
+                        restart_server(&widget, client).await;
+                        dialog.close();
+                    }
+                ));
+            }
+        ),
+    );
+    dialog.present(Some(&widget));
+}
+
+async fn restart_server(widget: &gtk::Widget, client: Arc<JellyfinClient>) {
+    match spawn_tokio(async move { client.restart().await }).await {
+        Ok(_) => (),
+        Err(e) => {
+            widget.toast(e.to_user_facing());
+            return;
+        }
+    };
+
+    widget.toast(gettext("Server is restarting"));
+}
+
 pub fn utc_to_localstring(utc: &DateTime<Utc>) -> String {
     let utc = utc.with_timezone(&chrono::Local);
     format!(
@@ -0,0 +1,429 @@
+use std::{
+    cell::OnceCell,
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use super::panel::present_restart_dialog;
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        structs::{
+            PackageInfo,
+            PluginInfo,
+        },
+    },
+    fraction,
+    fraction_reset,
+    ui::{
+        provider::IS_ADMIN,
+        widgets::utils::GlobalToast,
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_plugins_page.ui")]
+    pub struct ServerPluginsPage {
+        #[template_child]
+        pub restart_banner: TemplateChild<adw::Banner>,
+        #[template_child]
+        pub installed_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub catalog_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub catalog_search: TemplateChild<gtk::SearchEntry>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerPluginsPage {
+        const NAME: &'static str = "ServerPluginsPage";
+        type Type = super::ServerPluginsPage;
+        type ParentType = adw::NavigationPage;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerPluginsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_catalog_filter();
+            obj.refresh();
+        }
+    }
+
+    impl WidgetImpl for ServerPluginsPage {}
+    impl NavigationPageImpl for ServerPluginsPage {}
+}
+
+glib::wrapper! {
+    pub struct ServerPluginsPage(ObjectSubclass<imp::ServerPluginsPage>)
+        @extends gtk::Widget, adw::NavigationPage, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl ServerPluginsPage {
+    pub fn new(client: Arc<JellyfinClient>) -> Self {
+        let page: Self = glib::Object::new();
+        let _ = page.imp().client.set(client);
+        page
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerPluginsPage is created with a client")
+            .clone()
+    }
+
+    pub fn refresh(&self) {
+        if !IS_ADMIN.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                fraction_reset!(obj);
+                obj.set_plugins().await;
+                fraction!(obj);
+            }
+        ));
+    }
+
+    fn set_catalog_filter(&self) {
+        let search = self.imp().catalog_search.get();
+        self.imp().catalog_list.set_filter_func(glib::clone!(
+            #[weak]
+            search,
+            #[upgrade_or]
+            true,
+            move |row| {
+                let query = search.text().to_lowercase();
+                if query.is_empty() {
+                    return true;
+                }
+                row.downcast_ref::<adw::ActionRow>().is_some_and(|row| {
+                    row.title().to_lowercase().contains(&query)
+                        || row
+                            .subtitle()
+                            .is_some_and(|s| s.to_lowercase().contains(&query))
+                })
+            }
+        ));
+    }
+
+    #[template_callback]
+    fn on_search_changed(&self) {
+        self.imp().catalog_list.invalidate_filter();
+    }
+
+    #[template_callback]
+    fn on_restart(&self) {
+        present_restart_dialog(self, self.client());
+    }
+
+    async fn set_plugins(&self) {
+        let client = self.client();
+        let plugins = match spawn_tokio(async move { client.get_plugins().await }).await {
+            Ok(plugins) => plugins,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        let imp = self.imp();
+
+        if plugins
+            .iter()
+            .any(|plugin| matches!(plugin.status.as_str(), "Restart" | "Deleted"))
+        {
+            imp.restart_banner.set_revealed(true);
+        }
+
+        imp.installed_list.remove_all();
+        for plugin in &plugins {
+            imp.installed_list.append(&self.plugin_row(plugin));
+        }
+
+        let client = self.client();
+        let packages = match spawn_tokio(async move { client.get_packages().await }).await {
+            Ok(packages) => packages,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        imp.catalog_list.remove_all();
+        for package in packages {
+            let installed = plugins
+                .iter()
+                .find(|plugin| same_guid(&plugin.id, &package.guid));
+            imp.catalog_list
+                .append(&self.package_row(package, installed));
+        }
+    }
+
+    fn plugin_row(&self, plugin: &PluginInfo) -> adw::ActionRow {
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(&plugin.name)
+            .subtitle(format!(
+                "{} · {}",
+                plugin.version,
+                plugin_status_label(&plugin.status)
+            ))
+            .tooltip_text(plugin.description.to_owned().unwrap_or_default())
+            .build();
+
+        let enabled = plugin.status != "Disabled";
+        let switch = gtk::Switch::builder()
+            .valign(gtk::Align::Center)
+            .active(enabled)
+            .sensitive(matches!(plugin.status.as_str(), "Active" | "Disabled"))
+            .tooltip_text(gettext("Enabled"))
+            .build();
+        let id = plugin.id.to_owned();
+        let version = plugin.version.to_owned();
+        switch.connect_active_notify(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |switch| {
+                // Also reached when a failed request puts the switch back
+                if switch.is_active() == enabled {
+                    return;
+                }
+                // The rows are rebuilt once the request went through
+                switch.set_sensitive(false);
+                let id = id.to_owned();
+                let version = version.to_owned();
+                let client = obj.client();
+                obj.run_and_refresh_or(
+                    async move { client.set_plugin_enabled(&id, &version, !enabled).await },
+                    glib::clone!(
+                        #[weak]
+                        switch,
+                        move || {
+                            switch.set_active(enabled);
+                            switch.set_sensitive(true);
+                        }
+                    ),
+                );
+            }
+        ));
+        row.add_suffix(&switch);
+
+        if plugin.can_uninstall && plugin.status != "Deleted" {
+            let uninstall_button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(gettext("Uninstall"))
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            let plugin = plugin.to_owned();
+            uninstall_button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.on_uninstall(plugin.to_owned());
+                }
+            ));
+            row.add_suffix(&uninstall_button);
+        }
+
+        row
+    }
+
+    fn package_row(&self, package: PackageInfo, installed: Option<&PluginInfo>) -> adw::ActionRow {
+        let latest = package
+            .versions
+            .first()
+            .map(|version| version.version.to_owned());
+
+        let mut subtitle = package.overview.to_owned().unwrap_or_default();
+        if let Some(category) = &package.category {
+            subtitle = format!("{category} · {subtitle}");
+        }
+
+        let row = adw::ActionRow::builder()
+            .use_markup(false)
+            .title(&package.name)
+            .subtitle(subtitle)
+            .subtitle_lines(2)
+            .tooltip_text(package.owner.to_owned().unwrap_or_default())
+            .build();
+
+        let update_available = match (installed, &latest) {
+            (Some(plugin), Some(latest)) => is_newer_version(latest, &plugin.version),
+            _ => false,
+        };
+
+        if installed.is_some() && !update_available {
+            let label = gtk::Label::builder()
+                .label(gettext("Installed"))
+                .valign(gtk::Align::Center)
+                .css_classes(["dimmed"])
+                .build();
+            row.add_suffix(&label);
+            return row;
+        }
+
+        let label = if update_available {
+            gettext("Update")
+        } else {
+            gettext("Install")
+        };
+        let button = gtk::Button::builder()
+            .label(label)
+            .valign(gtk::Align::Center)
+            .css_classes(["suggested-action"])
+            .tooltip_text(latest.unwrap_or_default())
+            .build();
+        button.connect_clicked(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |button| {
+                button.set_sensitive(false);
+                let package = package.to_owned();
+                obj.toast(gettext("Installing {name}").replace("{name}", &package.name));
+                let client = obj.client();
+                obj.run_and_refresh(async move { client.install_package(&package).await });
+            }
+        ));
+        row.add_suffix(&button);
+
+        row
+    }
+
+    fn on_uninstall(&self, plugin: PluginInfo) {
+        let dialog = adw::AlertDialog::new(
+            Some(&gettext("Uninstall Plugin")),
+            Some(
+                &gettext("Are you sure you want to uninstall {name}?")
+                    .replace("{name}", &plugin.name),
+            ),
+        );
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("uninstall", &gettext("Uninstall")),
+        ]);
+        dialog.set_response_appearance("uninstall", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("uninstall"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _| {
+                    let id = plugin.id.to_owned();
+                    let version = plugin.version.to_owned();
+                    let client = obj.client();
+                    obj.run_and_refresh(
+                        async move { client.uninstall_plugin(&id, &version).await },
+                    );
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    // Every plugin change only takes effect after a restart
+    fn run_and_refresh<F>(&self, future: F)
+    where
+        F: std::future::Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.run_and_refresh_or(future, || {});
+    }
+
+    /// Like [`Self::run_and_refresh`], `on_error` undoes what the UI already
+    /// shows when the request fails.
+    fn run_and_refresh_or<F, E>(&self, future: F, on_error: E)
+    where
+        F: std::future::Future<Output = anyhow::Result<()>> + Send + 'static,
+        E: FnOnce() + 'static,
+    {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                match spawn_tokio(future).await {
+                    Ok(_) => {
+                        obj.imp().restart_banner.set_revealed(true);
+                        obj.refresh();
+                    }
+                    Err(e) => {
+                        on_error();
+                        obj.toast(e.to_user_facing());
+                    }
+                }
+            }
+        ));
+    }
+}
+
+fn same_guid(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| s.replace('-', "").to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+fn is_newer_version(a: &str, b: &str) -> bool {
+    let parse = |s: &str| {
+        s.split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    let (mut a, mut b) = (parse(a), parse(b));
+    // "1.2" and "1.2.0" are the same version
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    a > b
+}
+
+fn plugin_status_label(status: &str) -> String {
+    match status {
+        "Active" => gettext("Active"),
+        "Restart" => gettext("Restart Required"),
+        "Disabled" => gettext("Disabled"),
+        "Deleted" => gettext("Removed After Restart"),
+        "Superceded" => gettext("Superseded"),
+        "Malfunctioned" => gettext("Malfunctioned"),
+        "NotSupported" => gettext("Not Supported"),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,513 @@
+use std::{
+    cell::{
+        OnceCell,
+        RefCell,
+    },
+    collections::HashSet,
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        structs::{
+            SessionInfo,
+            TranscodingInfo,
+        },
+    },
+    ui::{
+        provider::IS_ADMIN,
+        widgets::{
+            song_widget::format_duration,
+            utils::GlobalToast,
+        },
+    },
+    utils::{
+        spawn,
+        spawn_tokio,
+    },
+};
+
+const REFRESH_SECONDS: u32 = 5;
+const TICKS_PER_SECOND: i64 = 10_000_000;
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_sessions_page.ui")]
+    pub struct ServerSessionsPage {
+        #[template_child]
+        pub sessions_list: TemplateChild<gtk::ListBox>,
+
+        // Keep rows open across refreshes
+        pub expanded: RefCell<HashSet<String>>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerSessionsPage {
+        const NAME: &'static str = "ServerSessionsPage";
+        type Type = super::ServerSessionsPage;
+        type ParentType = adw::NavigationPage;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerSessionsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            self.sessions_list.set_placeholder(Some(
+                &gtk::Label::builder()
+                    .label(gettext("No active sessions"))
+                    .margin_top(12)
+                    .margin_bottom(12)
+                    .css_classes(["dimmed"])
+                    .build(),
+            ));
+
+            obj.refresh();
+            obj.watch_sessions();
+        }
+    }
+
+    impl WidgetImpl for ServerSessionsPage {}
+    impl NavigationPageImpl for ServerSessionsPage {}
+}
+
+glib::wrapper! {
+    pub struct ServerSessionsPage(ObjectSubclass<imp::ServerSessionsPage>)
+        @extends gtk::Widget, adw::NavigationPage, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl ServerSessionsPage {
+    pub fn new(client: Arc<JellyfinClient>) -> Self {
+        let page: Self = glib::Object::new();
+        let _ = page.imp().client.set(client);
+        page
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerSessionsPage is created with a client")
+            .clone()
+    }
+
+    pub fn refresh(&self) {
+        if !IS_ADMIN.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                obj.set_sessions().await;
+            }
+        ));
+    }
+
+    fn watch_sessions(&self) {
+        glib::timeout_add_seconds_local(
+            REFRESH_SECONDS,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    if obj.is_mapped() {
+                        obj.refresh();
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    async fn set_sessions(&self) {
+        let client = self.client();
+        let mut sessions = match spawn_tokio(async move { client.get_sessions().await }).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        // Playing sessions first, then the most recently active
+        sessions.sort_by(|a, b| {
+            b.now_playing_item
+                .is_some()
+                .cmp(&a.now_playing_item.is_some())
+                .then(b.last_activity_date.cmp(&a.last_activity_date))
+        });
+
+        let sessions_list = &self.imp().sessions_list;
+        sessions_list.remove_all();
+        for session in sessions {
+            sessions_list.append(&self.session_row(session));
+        }
+    }
+
+    fn session_row(&self, session: SessionInfo) -> adw::ExpanderRow {
+        let title = [session.user_name.as_deref(), session.client.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        let row = adw::ExpanderRow::builder()
+            .use_markup(false)
+            .title(title)
+            .subtitle(session.device_name.to_owned().unwrap_or_default())
+            .expanded(self.imp().expanded.borrow().contains(&session.id))
+            .build();
+
+        let id = session.id.to_owned();
+        row.connect_expanded_notify(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |row| {
+                let mut expanded = obj.imp().expanded.borrow_mut();
+                if row.is_expanded() {
+                    expanded.insert(id.to_owned());
+                } else {
+                    expanded.remove(&id);
+                }
+            }
+        ));
+
+        let play_state = &session.play_state;
+        let icon_name = match &session.now_playing_item {
+            Some(_) if play_state.is_paused => "media-playback-pause-symbolic",
+            Some(_) => "media-playback-start-symbolic",
+            None => "computer-symbolic",
+        };
+        row.add_prefix(&gtk::Image::from_icon_name(icon_name));
+
+        add_info_row(
+            &row,
+            &gettext("Device"),
+            [
+                session.device_name.to_owned(),
+                session.application_version.to_owned(),
+                session.remote_end_point.to_owned(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · "),
+        );
+
+        if let Some(item) = &session.now_playing_item {
+            let name = match &item.series_name {
+                Some(series_name) => format!("{series_name} - {}", item.name),
+                None => item.name.to_owned(),
+            };
+            row.set_subtitle(&format!(
+                "{} · {}",
+                session.device_name.to_owned().unwrap_or_default(),
+                name
+            ));
+
+            let position = play_state.position_ticks.unwrap_or_default() / TICKS_PER_SECOND;
+            let runtime = item.run_time_ticks.unwrap_or_default() as i64 / TICKS_PER_SECOND;
+            if runtime > 0 {
+                let progress_bar = gtk::ProgressBar::builder()
+                    .valign(gtk::Align::Center)
+                    .width_request(80)
+                    .fraction(position as f64 / runtime as f64)
+                    .build();
+                row.add_suffix(&progress_bar);
+            }
+
+            add_info_row(
+                &row,
+                &gettext("Now Playing"),
+                format!(
+                    "{name} · {}/{}",
+                    format_duration(position),
+                    format_duration(runtime)
+                ),
+            );
+            add_info_row(
+                &row,
+                &gettext("Play Method"),
+                play_method_label(play_state.play_method.as_deref()),
+            );
+        }
+
+        if let Some(transcoding_info) = &session.transcoding_info {
+            add_transcoding_rows(&row, transcoding_info);
+        }
+
+        self.add_action_rows(&row, &session);
+
+        row
+    }
+
+    fn add_action_rows(&self, row: &adw::ExpanderRow, session: &SessionInfo) {
+        if session.supports_remote_control {
+            let message_row = adw::ButtonRow::builder()
+                .title(gettext("Send Message"))
+                .start_icon_name("mail-send-symbolic")
+                .build();
+            let id = session.id.to_owned();
+            message_row.connect_activated(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.on_send_message(id.to_owned());
+                }
+            ));
+            row.add_row(&message_row);
+        }
+
+        if session.supports_remote_control && session.now_playing_item.is_some() {
+            let stop_row = adw::ButtonRow::builder()
+                .title(gettext("Stop Playback"))
+                .start_icon_name("media-playback-stop-symbolic")
+                .build();
+            let id = session.id.to_owned();
+            stop_row.connect_activated(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    let id = id.to_owned();
+                    let client = obj.client();
+                    obj.run_and_refresh(
+                        async move { client.stop_session_playback(&id).await },
+                        gettext("Playback stopped"),
+                    );
+                }
+            ));
+            row.add_row(&stop_row);
+        }
+
+        if let (Some(_), Some(device_id), Some(play_session_id)) = (
+            &session.transcoding_info,
+            &session.device_id,
+            &session.play_state.play_session_id,
+        ) {
+            let kill_row = adw::ButtonRow::builder()
+                .title(gettext("Stop Transcoding"))
+                .start_icon_name("process-stop-symbolic")
+                .css_classes(["destructive-action"])
+                .build();
+            let device_id = device_id.to_owned();
+            let play_session_id = play_session_id.to_owned();
+            kill_row.connect_activated(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    let device_id = device_id.to_owned();
+                    let play_session_id = play_session_id.to_owned();
+                    let client = obj.client();
+                    obj.run_and_refresh(
+                        async move { client.kill_transcode(&device_id, &play_session_id).await },
+                        gettext("Transcoding stopped"),
+                    );
+                }
+            ));
+            row.add_row(&kill_row);
+        }
+    }
+
+    fn run_and_refresh<F>(&self, future: F, message: String)
+    where
+        F: std::future::Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                match spawn_tokio(future).await {
+                    Ok(_) => {
+                        obj.toast(message);
+                        obj.refresh();
+                    }
+                    Err(e) => obj.toast(e.to_user_facing()),
+                }
+            }
+        ));
+    }
+
+    fn on_send_message(&self, id: String) {
+        let header_entry = adw::EntryRow::builder()
+            .title(gettext("Header"))
+            .text("Tsukimi")
+            .build();
+        let text_entry = adw::EntryRow::builder().title(gettext("Message")).build();
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&header_entry);
+        list.append(&text_entry);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Send Message"))
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("send", &gettext("Send"))]);
+        dialog.set_response_appearance("send", adw::ResponseAppearance::Suggested);
+        dialog.set_response_enabled("send", false);
+        dialog.set_close_response("cancel");
+
+        text_entry.connect_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                dialog.set_response_enabled("send", !entry.text().trim().is_empty());
+            }
+        ));
+
+        dialog.connect_response(
+            Some("send"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                header_entry,
+                #[weak]
+                text_entry,
+                move |_, _| {
+                    let id = id.to_owned();
+                    let header = header_entry.text().to_string();
+                    let text = text_entry.text().to_string();
+                    let client = obj.client();
+                    obj.run_and_refresh(
+                        async move { client.send_session_message(&id, &header, &text).await },
+                        gettext("Message sent"),
+                    );
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+}
+
+fn add_info_row(row: &adw::ExpanderRow, title: &str, subtitle: String) {
+    let info_row = adw::ActionRow::builder()
+        .use_markup(false)
+        .title(title)
+        .subtitle(subtitle)
+        .subtitle_selectable(true)
+        .css_classes(["property"])
+        .build();
+    row.add_row(&info_row);
+}
+
+fn add_transcoding_rows(row: &adw::ExpanderRow, info: &TranscodingInfo) {
+    let direct_label = |direct: bool| {
+        if direct {
+            gettext("Direct")
+        } else {
+            gettext("Transcode")
+        }
+    };
+
+    if !info.transcode_reasons.is_empty() {
+        add_info_row(
+            row,
+            &gettext("Transcode Reasons"),
+            info.transcode_reasons
+                .iter()
+                .map(|reason| split_words(reason))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+
+    let mut video = vec![info.video_codec.to_owned().unwrap_or_default()];
+    if let (Some(width), Some(height)) = (info.width, info.height) {
+        video.push(format!("{width}x{height}"));
+    }
+    if let Some(framerate) = info.framerate {
+        video.push(format!("{framerate:.3} fps"));
+    }
+    video.push(direct_label(info.is_video_direct));
+    add_info_row(row, &gettext("Video"), video.join(" · "));
+
+    add_info_row(
+        row,
+        &gettext("Audio"),
+        format!(
+            "{} · {}",
+            info.audio_codec.to_owned().unwrap_or_default(),
+            direct_label(info.is_audio_direct)
+        ),
+    );
+
+    let mut stream = Vec::new();
+    if let Some(bitrate) = info.bitrate {
+        stream.push(format!("{:.1} Mbps", bitrate as f64 / 1_000_000.0));
+    }
+    if let Some(container) = &info.container {
+        stream.push(container.to_owned());
+    }
+    if let Some(completion) = info.completion_percentage {
+        stream.push(format!("{completion:.0}%"));
+    }
+    add_info_row(row, &gettext("Stream"), stream.join(" · "));
+
+    add_info_row(
+        row,
+        &gettext("Hardware Acceleration"),
+        info.hardware_acceleration_type
+            .to_owned()
+            .filter(|hwaccel| !hwaccel.is_empty() && hwaccel != "none")
+            .unwrap_or(gettext("None")),
+    );
+}
+
+fn play_method_label(play_method: Option<&str>) -> String {
+    match play_method {
+        Some("DirectPlay") => gettext("Direct Play"),
+        Some("DirectStream") => gettext("Direct Stream"),
+        Some("Transcode") => gettext("Transcode"),
+        Some(other) => other.to_string(),
+        None => gettext("Unknown"),
+    }
+}
+
+// TranscodeReasons are PascalCase identifiers like `VideoCodecNotSupported`
+fn split_words(s: &str) -> String {
+    let mut words = String::new();
+    for (i, c) in s.char_indices() {
+        if i > 0 && c.is_uppercase() {
+            words.push(' ');
+        }
+        words.push(c);
+    }
+    words
+}
@@ -0,0 +1,454 @@
+use std::{
+    cell::{
+        OnceCell,
+        RefCell,
+    },
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use anyhow::{
+    Result,
+    anyhow,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    gio,
+    glib,
+    template_callbacks,
+};
+
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        picture_source::PictureSource,
+        structs::User,
+    },
+    ui::widgets::utils::GlobalToast,
+    utils::{
+        resolve_picture_file,
+        spawn,
+        spawn_tokio,
+    },
+};
+
+const BITS_PER_MEGABIT: f64 = 1_000_000.0;
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_user_dialog.ui")]
+    pub struct ServerUserDialog {
+        #[template_child]
+        pub avatar: TemplateChild<adw::Avatar>,
+        #[template_child]
+        pub admin_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub disabled_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub remote_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub folders_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub all_folders_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub bitrate_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub parental_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub parental_list: TemplateChild<gtk::StringList>,
+        #[template_child]
+        pub delete_row: TemplateChild<adw::ButtonRow>,
+
+        pub user: RefCell<Option<User>>,
+        pub folder_rows: RefCell<Vec<(String, adw::SwitchRow)>>,
+        // Rating values in the same order as `parental_list`, `None` for no limit
+        pub parental_values: RefCell<Vec<Option<i32>>>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerUserDialog {
+        const NAME: &'static str = "ServerUserDialog";
+        type Type = super::ServerUserDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerUserDialog {}
+    impl WidgetImpl for ServerUserDialog {}
+    impl AdwDialogImpl for ServerUserDialog {}
+}
+
+glib::wrapper! {
+    pub struct ServerUserDialog(ObjectSubclass<imp::ServerUserDialog>)
+        @extends gtk::Widget, adw::Dialog, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl ServerUserDialog {
+    pub fn new(user: User, client: Arc<JellyfinClient>) -> Self {
+        let obj: Self = glib::Object::new();
+        let _ = obj.imp().client.set(client);
+        obj.set_user(user);
+        obj
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerUserDialog is created with a client")
+            .clone()
+    }
+
+    fn user(&self) -> Option<User> {
+        self.imp().user.borrow().to_owned()
+    }
+
+    fn set_user(&self, user: User) {
+        let imp = self.imp();
+        let policy = &user.policy;
+
+        self.set_title(&user.name);
+        imp.avatar.set_text(Some(&user.name));
+
+        imp.admin_row.set_active(policy.is_administrator);
+        imp.disabled_row.set_active(policy.is_disabled);
+        imp.remote_row.set_active(policy.enable_remote_access);
+        imp.all_folders_row.set_active(policy.enable_all_folders);
+        imp.bitrate_row
+            .set_value(policy.remote_client_bitrate_limit as f64 / BITS_PER_MEGABIT);
+
+        // Don't let admins lock themselves out
+        let is_self = user.id == self.client().session().account.user_id;
+        imp.delete_row.set_sensitive(!is_self);
+        imp.admin_row.set_sensitive(!is_self);
+        imp.disabled_row.set_sensitive(!is_self);
+
+        if let Some(tag) = user.primary_image_tag.to_owned() {
+            let source = PictureSource::User {
+                id: user.id.to_owned(),
+                tag,
+            };
+            spawn(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                async move {
+                    if let Ok(file) = resolve_picture_file(source).await {
+                        obj.set_avatar_file(&file);
+                    }
+                }
+            ));
+        }
+
+        imp.user.replace(Some(user));
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                obj.set_folders().await;
+                obj.set_parental_ratings().await;
+            }
+        ));
+    }
+
+    fn set_avatar_file(&self, file: &gio::File) {
+        if let Ok(texture) = gtk::gdk::Texture::from_file(file) {
+            self.imp().avatar.set_custom_image(Some(&texture));
+        }
+    }
+
+    async fn set_folders(&self) {
+        let client = self.client();
+        let folders = match spawn_tokio(async move { client.get_media_folders().await }).await {
+            Ok(folders) => folders,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        let Some(user) = self.user() else {
+            return;
+        };
+
+        let imp = self.imp();
+        let mut folder_rows = Vec::new();
+
+        for folder in folders.items {
+            let row = adw::SwitchRow::builder()
+                .title(&folder.name)
+                .active(user.policy.enabled_folders.contains(&folder.id))
+                .build();
+            imp.all_folders_row
+                .bind_property("active", &row, "sensitive")
+                .invert_boolean()
+                .sync_create()
+                .build();
+            imp.folders_group.add(&row);
+            folder_rows.push((folder.id, row));
+        }
+
+        imp.folder_rows.replace(folder_rows);
+    }
+
+    async fn set_parental_ratings(&self) {
+        let client = self.client();
+        let ratings = match spawn_tokio(async move { client.get_parental_ratings().await }).await {
+            Ok(ratings) => ratings,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        let Some(user) = self.user() else {
+            return;
+        };
+
+        let imp = self.imp();
+        let mut names: Vec<String> = vec![gettext("No Limit")];
+        let mut values = vec![None];
+
+        // Several rating systems share the same value, show them as one entry
+        for rating in ratings {
+            let Some(value) = rating.value else {
+                continue;
+            };
+            match values.iter().position(|v| *v == Some(value)) {
+                Some(index) => names[index].push_str(&format!(", {}", rating.name)),
+                None => {
+                    names.push(rating.name);
+                    values.push(Some(value));
+                }
+            }
+        }
+
+        imp.parental_list
+            .splice(0, 0, &names.iter().map(String::as_str).collect::<Vec<_>>());
+        let selected = values
+            .iter()
+            .position(|v| *v == user.policy.max_parental_rating)
+            .unwrap_or(0);
+        imp.parental_row.set_selected(selected as u32);
+        imp.parental_values.replace(values);
+    }
+
+    #[template_callback]
+    async fn on_save(&self) {
+        let Some(user) = self.user() else {
+            return;
+        };
+
+        let imp = self.imp();
+        let mut policy = user.policy.to_owned();
+
+        policy.is_administrator = imp.admin_row.is_active();
+        policy.is_disabled = imp.disabled_row.is_active();
+        policy.enable_remote_access = imp.remote_row.is_active();
+        policy.enable_all_folders = imp.all_folders_row.is_active();
+        policy.enabled_folders = imp
+            .folder_rows
+            .borrow()
+            .iter()
+            .filter(|(_, row)| row.is_active())
+            .map(|(id, _)| id.to_owned())
+            .collect();
+        policy.remote_client_bitrate_limit =
+            (imp.bitrate_row.value() * BITS_PER_MEGABIT).round() as i64;
+        if let Some(value) = imp
+            .parental_values
+            .borrow()
+            .get(imp.parental_row.selected() as usize)
+        {
+            policy.max_parental_rating = *value;
+        }
+
+        let id = user.id.to_owned();
+        let new_policy = policy.to_owned();
+        let client = self.client();
+        match spawn_tokio(async move { client.update_user_policy(&id, &new_policy).await }).await {
+            Ok(_) => {
+                let mut user = user;
+                user.policy = policy;
+                imp.user.replace(Some(user));
+                self.toast(gettext("User saved"));
+            }
+            Err(e) => self.toast(e.to_user_facing()),
+        }
+    }
+
+    #[template_callback]
+    fn on_reset_password(&self) {
+        let Some(user) = self.user() else {
+            return;
+        };
+
+        let password_entry = adw::PasswordEntryRow::builder()
+            .title(gettext("New Password"))
+            .build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&password_entry);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Reset Password"))
+            .body(gettext("Leave empty to remove the password"))
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("Cancel")), ("reset", &gettext("Reset"))]);
+        dialog.set_response_appearance("reset", adw::ResponseAppearance::Destructive);
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("reset"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                password_entry,
+                move |_, _| {
+                    let id = user.id.to_owned();
+                    let password = password_entry.text().to_string();
+                    let client = obj.client();
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            match spawn_tokio(async move {
+                                client.reset_user_password(&id, &password).await
+                            })
+                            .await
+                            {
+                                Ok(_) => obj.toast(gettext("Password reset")),
+                                Err(e) => obj.toast(e.to_user_facing()),
+                            }
+                        }
+                    ));
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    #[template_callback]
+    fn on_delete_user(&self) {
+        let Some(user) = self.user() else {
+            return;
+        };
+
+        let dialog = adw::AlertDialog::new(
+            Some(&gettext("Delete User")),
+            Some(&gettext("Are you sure you want to delete {name}?").replace("{name}", &user.name)),
+        );
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("delete", &gettext("Delete")),
+        ]);
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        dialog.connect_response(
+            Some("delete"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_, _| {
+                    let id = user.id.to_owned();
+                    let client = obj.client();
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            match spawn_tokio(async move { client.delete_user(&id).await }).await {
+                                Ok(_) => {
+                                    obj.close();
+                                }
+                                Err(e) => obj.toast(e.to_user_facing()),
+                            }
+                        }
+                    ));
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    #[template_callback]
+    async fn on_change_avatar(&self) {
+        let images_filter = gtk::FileFilter::new();
+        images_filter.set_name(Some(&gettext("Image")));
+        images_filter.add_pixbuf_formats();
+        let model = gio::ListStore::new::<gtk::FileFilter>();
+        model.append(&images_filter);
+
+        let filedialog = gtk::FileDialog::builder()
+            .modal(true)
+            .title(gettext("Select a picture"))
+            .filters(&model)
+            .build();
+
+        let root = self.root().and_downcast::<gtk::Window>();
+        let Ok(file) = filedialog.open_future(root.as_ref()).await else {
+            return;
+        };
+
+        match self.post_avatar(&file).await {
+            Ok(_) => {
+                self.set_avatar_file(&file);
+                self.toast(gettext("Avatar updated"));
+            }
+            Err(e) => self.toast(e.to_user_facing()),
+        }
+    }
+
+    async fn post_avatar(&self, file: &gio::File) -> Result<()> {
+        let id = self.user().map(|user| user.id).ok_or(anyhow!("No user"))?;
+
+        let (bytes, _) = file.load_bytes_future().await?;
+
+        let content_type = file
+            .query_info_future(
+                "standard::content-type",
+                gio::FileQueryInfoFlags::NONE,
+                glib::Priority::LOW,
+            )
+            .await
+            .ok()
+            .and_then(|info| info.content_type())
+            .map(|mime| mime.to_string())
+            .unwrap_or("image/jpeg".to_string());
+
+        use base64::{
+            Engine as _,
+            engine::general_purpose::STANDARD,
+        };
+        let bytes = STANDARD.encode(bytes);
+
+        let client = self.client();
+        spawn_tokio(async move { client.post_user_image(&id, bytes, &content_type).await }).await
+    }
+}
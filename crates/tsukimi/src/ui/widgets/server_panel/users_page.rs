@@ -0,0 +1,279 @@
+use std::{
+    cell::OnceCell,
+    sync::Arc,
+};
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+    template_callbacks,
+};
+
+use super::{
+    ServerUserDialog,
+    utc_to_localstring,
+};
+use crate::{
+    client::{
+        error::UserFacingError,
+        jellyfin_client::JellyfinClient,
+        picture_source::PictureSource,
+        structs::User,
+    },
+    fraction,
+    fraction_reset,
+    ui::{
+        provider::IS_ADMIN,
+        widgets::utils::GlobalToast,
+    },
+    utils::{
+        resolve_picture_file,
+        spawn,
+        spawn_tokio,
+    },
+};
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_users_page.ui")]
+    pub struct ServerUsersPage {
+        #[template_child]
+        pub users_list: TemplateChild<gtk::ListBox>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerUsersPage {
+        const NAME: &'static str = "ServerUsersPage";
+        type Type = super::ServerUsersPage;
+        type ParentType = adw::NavigationPage;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerUsersPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().refresh();
+        }
+    }
+
+    impl WidgetImpl for ServerUsersPage {}
+    impl NavigationPageImpl for ServerUsersPage {}
+}
+
+glib::wrapper! {
+    pub struct ServerUsersPage(ObjectSubclass<imp::ServerUsersPage>)
+        @extends gtk::Widget, adw::NavigationPage, @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[template_callbacks]
+impl ServerUsersPage {
+    pub fn new(client: Arc<JellyfinClient>) -> Self {
+        let page: Self = glib::Object::new();
+        let _ = page.imp().client.set(client);
+        page
+    }
+
+    fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("ServerUsersPage is created with a client")
+            .clone()
+    }
+
+    pub fn refresh(&self) {
+        if !IS_ADMIN.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        spawn(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                fraction_reset!(obj);
+                obj.set_users().await;
+                fraction!(obj);
+            }
+        ));
+    }
+
+    async fn set_users(&self) {
+        let client = self.client();
+        let mut users = match spawn_tokio(async move { client.get_users().await }).await {
+            Ok(users) => users,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
+
+        users.sort_by(|a, b| b.last_activity_date.cmp(&a.last_activity_date));
+
+        let users_list = &self.imp().users_list;
+        users_list.remove_all();
+
+        for user in users {
+            users_list.append(&self.user_row(user));
+        }
+    }
+
+    fn user_row(&self, user: User) -> adw::ActionRow {
+        let mut subtitle = match user.last_activity_date {
+            Some(date) => {
+                gettext("Last active: {date}").replace("{date}", &utc_to_localstring(&date))
+            }
+            None => gettext("Never active"),
+        };
+        if user.policy.is_administrator {
+            subtitle.push_str(&format!(" · {}", gettext("Administrator")));
+        }
+        if user.policy.is_disabled {
+            subtitle.push_str(&format!(" · {}", gettext("Disabled")));
+        }
+
+        let row = adw::ActionRow::builder()
+            .title(&user.name)
+            .subtitle(subtitle)
+            .activatable(true)
+            .build();
+
+        let avatar = adw::Avatar::new(32, Some(&user.name), true);
+        row.add_prefix(&avatar);
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+        if let Some(tag) = user.primary_image_tag.to_owned() {
+            let source = PictureSource::User {
+                id: user.id.to_owned(),
+                tag,
+            };
+            spawn(glib::clone!(
+                #[weak]
+                avatar,
+                async move {
+                    if let Ok(file) = resolve_picture_file(source).await
+                        && let Ok(texture) = gtk::gdk::Texture::from_file(&file)
+                    {
+                        avatar.set_custom_image(Some(&texture));
+                    }
+                }
+            ));
+        }
+
+        row.connect_activated(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                obj.present_user_dialog(user.to_owned());
+            }
+        ));
+
+        row
+    }
+
+    fn present_user_dialog(&self, user: User) {
+        let dialog = ServerUserDialog::new(user, self.client());
+        dialog.connect_closed(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                obj.refresh();
+            }
+        ));
+        dialog.present(Some(self));
+    }
+
+    #[template_callback]
+    fn on_add_user(&self) {
+        let name_entry = adw::EntryRow::builder().title(gettext("Name")).build();
+        let password_entry = adw::PasswordEntryRow::builder()
+            .title(gettext("Password"))
+            .build();
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&name_entry);
+        list.append(&password_entry);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Add User"))
+            .extra_child(&list)
+            .build();
+        dialog.add_responses(&[
+            ("cancel", &gettext("Cancel")),
+            ("create", &gettext("Create")),
+        ]);
+        dialog.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+        dialog.set_response_enabled("create", false);
+        dialog.set_default_response(Some("create"));
+        dialog.set_close_response("cancel");
+
+        name_entry.connect_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                dialog.set_response_enabled("create", !entry.text().trim().is_empty());
+            }
+        ));
+
+        dialog.connect_response(
+            Some("create"),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                name_entry,
+                #[weak]
+                password_entry,
+                move |_, _| {
+                    let name = name_entry.text().trim().to_string();
+                    let password = password_entry.text().to_string();
+                    spawn(glib::clone!(
+                        #[weak]
+                        obj,
+                        async move {
+                            obj.create_user(name, password).await;
+                        }
+                    ));
+                }
+            ),
+        );
+
+        dialog.present(Some(self));
+    }
+
+    async fn create_user(&self, name: String, password: String) {
+        let client = self.client();
+        let user =
+            match spawn_tokio(async move { client.create_user(&name, &password).await }).await {
+                Ok(user) => user,
+                Err(e) => {
+                    self.toast(e.to_user_facing());
+                    return;
+                }
+            };
+
+        self.toast(gettext("User created"));
+        self.refresh();
+        self.present_user_dialog(user);
+    }
+}
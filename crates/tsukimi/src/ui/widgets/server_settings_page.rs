@@ -0,0 +1,272 @@
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use gettextrs::gettext;
+use gtk::{
+    CompositeTemplate,
+    glib,
+};
+
+use super::utils::GlobalToast;
+use crate::{
+    client::registry::CLIENTS,
+    ui::models::{
+        SETTINGS,
+        server_overrides::ServerOverrides,
+    },
+};
+
+/// Values of "music-repeat-mode", in the order of the repeat combo row.
+const REPEAT_MODES: [&str; 3] = ["none", "repeat", "repeat-one"];
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/server_settings_page.ui")]
+    pub struct ServerSettingsPage {
+        #[template_child]
+        pub playback_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub audio_lang_expander: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub audio_lang_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub subtitle_lang_expander: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub subtitle_lang_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub danmaku_expander: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub danmaku_switch: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub hwdec_expander: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub hwdec_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub cache_size_expander: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub cache_size_spin: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub cache_time_expander: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub cache_time_spin: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub repeat_expander: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub repeat_combo: TemplateChild<adw::ComboRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ServerSettingsPage {
+        const NAME: &'static str = "ServerSettingsPage";
+        type Type = super::ServerSettingsPage;
+        type ParentType = adw::PreferencesPage;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ServerSettingsPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for ServerSettingsPage {}
+    impl PreferencesPageImpl for ServerSettingsPage {}
+}
+
+glib::wrapper! {
+    /// Settings the signed in server overrides on top of the global ones.
+    pub struct ServerSettingsPage(ObjectSubclass<imp::ServerSettingsPage>)
+        @extends gtk::Widget, adw::PreferencesPage,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for ServerSettingsPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerSettingsPage {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn setup(&self) {
+        let imp = self.imp();
+
+        let session = CLIENTS.active().session();
+        if session.server_name_hash.is_empty() {
+            imp.playback_group
+                .set_description(Some(&gettext("Sign in to a server to override settings")));
+            self.set_sensitive(false);
+            return;
+        }
+        imp.playback_group.set_description(Some(
+            &gettext("Switch on a setting to use a different value on {server}")
+                .replace("{server}", &session.account.servername),
+        ));
+
+        self.bind_override(
+            "mpv-audio-preferred-lang",
+            &imp.audio_lang_expander,
+            &*imp.audio_lang_combo,
+            "selected",
+        );
+        self.bind_override(
+            "mpv-subtitle-preferred-lang",
+            &imp.subtitle_lang_expander,
+            &*imp.subtitle_lang_combo,
+            "selected",
+        );
+        self.bind_override(
+            "is-danmaku-enabled",
+            &imp.danmaku_expander,
+            &*imp.danmaku_switch,
+            "active",
+        );
+        self.bind_override(
+            "mpv-hwdec",
+            &imp.hwdec_expander,
+            &*imp.hwdec_combo,
+            "selected",
+        );
+        self.bind_override(
+            "mpv-cache-size",
+            &imp.cache_size_expander,
+            &*imp.cache_size_spin,
+            "value",
+        );
+        self.bind_override(
+            "mpv-cache-time",
+            &imp.cache_time_expander,
+            &*imp.cache_time_spin,
+            "value",
+        );
+        self.bind_override(
+            "music-repeat-mode",
+            &imp.repeat_expander,
+            &*imp.repeat_combo,
+            "selected",
+        );
+    }
+
+    /// The enable switch of `expander` creates or removes the override of
+    /// `key`, `property` of `editor` holds its value.
+    fn bind_override(
+        &self, key: &'static str, expander: &adw::ExpanderRow, editor: &impl IsA<glib::Object>,
+        property: &'static str,
+    ) {
+        let editor = editor.upcast_ref::<glib::Object>();
+        let client = CLIENTS.active();
+        let overridden = ServerOverrides::get(&client, key);
+        editor.set_property_from_value(
+            property,
+            &to_property(
+                key,
+                &overridden.to_owned().unwrap_or_else(|| SETTINGS.value(key)),
+            ),
+        );
+        expander.set_enable_expansion(overridden.is_some());
+        update_subtitle(expander);
+
+        expander.connect_enable_expansion_notify(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            #[weak]
+            editor,
+            #[strong]
+            client,
+            move |expander| {
+                let value = expander
+                    .enables_expansion()
+                    .then(|| to_variant(key, &editor.property_value(property)));
+                if let Err(e) = ServerOverrides::set(&client, key, value.as_ref()) {
+                    obj.toast(e.to_string());
+                }
+                if value.is_none() {
+                    // Show the global value again
+                    editor
+                        .set_property_from_value(property, &to_property(key, &SETTINGS.value(key)));
+                }
+                update_subtitle(expander);
+            }
+        ));
+
+        editor.connect_notify_local(
+            Some(property),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                expander,
+                #[strong]
+                client,
+                move |editor, _| {
+                    if !expander.enables_expansion() {
+                        return;
+                    }
+                    let value = to_variant(key, &editor.property_value(property));
+                    if let Err(e) = ServerOverrides::set(&client, key, Some(&value)) {
+                        obj.toast(e.to_string());
+                    }
+                }
+            ),
+        );
+    }
+}
+
+fn update_subtitle(expander: &adw::ExpanderRow) {
+    expander.set_subtitle(&if expander.enables_expansion() {
+        gettext("Overridden on this server")
+    } else {
+        gettext("Uses the global setting")
+    });
+}
+
+/// Converts a settings value to the editor property of `key`.
+fn to_property(key: &str, value: &glib::Variant) -> glib::Value {
+    match key {
+        "is-danmaku-enabled" => value.get::<bool>().unwrap_or_default().to_value(),
+        "music-repeat-mode" => {
+            let mode = value.get::<String>().unwrap_or_default();
+            let index = REPEAT_MODES
+                .iter()
+                .position(|m| *m == mode)
+                .unwrap_or_default();
+            (index as u32).to_value()
+        }
+        "mpv-cache-size" | "mpv-cache-time" => {
+            (value.get::<i32>().unwrap_or_default() as f64).to_value()
+        }
+        _ => (value.get::<i32>().unwrap_or_default().max(0) as u32).to_value(),
+    }
+}
+
+/// Converts the editor property of `key` back to a settings value.
+fn to_variant(key: &str, value: &glib::Value) -> glib::Variant {
+    match key {
+        "is-danmaku-enabled" => value.get::<bool>().unwrap_or_default().to_variant(),
+        "music-repeat-mode" => REPEAT_MODES
+            .get(value.get::<u32>().unwrap_or_default() as usize)
+            .copied()
+            .unwrap_or(REPEAT_MODES[0])
+            .to_variant(),
+        "mpv-cache-size" | "mpv-cache-time" => {
+            (value.get::<f64>().unwrap_or_default() as i32).to_variant()
+        }
+        _ => (value.get::<u32>().unwrap_or_default() as i32).to_variant(),
+    }
+}
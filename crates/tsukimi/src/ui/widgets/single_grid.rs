@@ -1,6 +1,9 @@
 use std::{
     future::Future,
-    sync::atomic::Ordering,
+    sync::{
+        Arc,
+        atomic::Ordering,
+    },
 };
 
 use adw::prelude::*;
@@ -27,6 +30,7 @@ use super::{
 use crate::{
     client::{
         error::UserFacingError,
+        jellyfin_client::JellyfinClient,
         structs::{
             List,
             SimpleListItem,
@@ -65,15 +69,19 @@ pub mod imp {
     };
     use std::cell::OnceCell;
 
-    use crate::ui::{
-        models::SETTINGS,
-        widgets::{
-            filter_panel::FilterPanelDialog,
-            tu_item::{
-                CardOptions,
-                CardShape,
+    use crate::{
+        client::jellyfin_client::JellyfinClient,
+        ui::{
+            models::SETTINGS,
+            widgets::{
+                filter_panel::FilterPanelDialog,
+                selection_bar::SelectionBar,
+                tu_item::{
+                    CardOptions,
+                    CardShape,
+                },
+                tuview_scrolled::TuViewScrolled,
             },
-            tuview_scrolled::TuViewScrolled,
         },
     };
 
@@ -240,6 +248,10 @@ pub mod imp {
         pub stack: TemplateChild<gtk::Stack>,
         #[template_child]
         pub scrolled: TemplateChild<TuViewScrolled>,
+        #[template_child]
+        pub select_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub selection_bar: TemplateChild<SelectionBar>,
 
         #[property(get, set = Self::set_list_type, builder(ListType::default()))]
         pub list_type: Cell<ListType>,
@@ -255,6 +267,7 @@ pub mod imp {
         pub lock: Arc<AtomicBool>,
 
         pub filter_panel: OnceCell<FilterPanelDialog>,
+        pub client: OnceCell<Arc<JellyfinClient>>,
     }
 
     #[glib::object_subclass]
@@ -321,6 +334,15 @@ pub mod imp {
                 })
                 .build();
 
+            self.selection_bar
+                .set_model(Some(&self.scrolled.multi_selection()));
+            let select_button = self.select_button.get();
+            self.selection_bar.connect_done(glib::clone!(
+                #[weak]
+                select_button,
+                move |_| select_button.set_active(false)
+            ));
+
             self.parent_constructed();
         }
 
@@ -380,16 +402,21 @@ glib::wrapper! {
                     gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
 }
 
-impl Default for SingleGrid {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[gtk::template_callbacks]
 impl SingleGrid {
-    pub fn new() -> Self {
-        Object::new()
+    /// Grid of items listed by `client`.
+    pub fn new(client: Arc<JellyfinClient>) -> Self {
+        let obj: Self = Object::new();
+        let _ = obj.imp().client.set(client);
+        obj
+    }
+
+    pub fn client(&self) -> Arc<JellyfinClient> {
+        self.imp()
+            .client
+            .get()
+            .expect("SingleGrid is created with a client")
+            .clone()
     }
 
     #[template_callback]
@@ -402,10 +429,18 @@ impl SingleGrid {
         self.set_view_type(view_type);
     }
 
+    #[template_callback]
+    fn on_select_toggled(&self, button: &gtk::ToggleButton) {
+        let imp = self.imp();
+        let active = button.is_active();
+        imp.scrolled.set_selection_mode(active);
+        imp.selection_bar.reveal(active);
+    }
+
     #[template_callback]
     fn filter_panel_cb(&self, _btn: &gtk::Button) {
         let panel = self.imp().filter_panel.get_or_init(|| {
-            let dialog = FilterPanelDialog::new();
+            let dialog = FilterPanelDialog::new(self.client());
             dialog.connect_applied(glib::clone!(
                 #[weak(rename_to = obj)]
                 self,
@@ -471,7 +506,7 @@ impl SingleGrid {
     pub fn add_items<const C: bool>(&self, items: Vec<SimpleListItem>) {
         let imp = self.imp();
         let scrolled = imp.scrolled.get();
-        scrolled.set_store::<C>(items);
+        scrolled.set_store::<C>(items, &self.client());
         if scrolled.n_items() == 0 {
             imp.stack.set_visible_child_name("fallback");
         } else {
@@ -516,7 +551,7 @@ impl SingleGrid {
 
     pub fn connect_sort_changed_tokio<F, Fut>(&self, f: F)
     where
-        F: Fn(String, String, FiltersList) -> Fut + Send + Sync + 'static,
+        F: Fn(Arc<JellyfinClient>, String, String, FiltersList) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Result<List>> + Send + 'static,
     {
         self.connect_sort_changed(move |obj| {
@@ -533,7 +568,12 @@ impl SingleGrid {
             } else {
                 obj.imp().filter.remove_css_class("accent");
             }
-            let future = f(sort_by.to_owned(), sort_order.to_owned(), filters_list);
+            let future = f(
+                obj.client(),
+                sort_by.to_owned(),
+                sort_order.to_owned(),
+                filters_list,
+            );
             spawn(glib::clone!(
                 #[weak(rename_to = obj)]
                 obj,
@@ -563,7 +603,7 @@ impl SingleGrid {
 
     pub fn connect_end_edge_overshot_tokio<F, Fut>(&self, f: F)
     where
-        F: Fn(String, String, u32, FiltersList) -> Fut + Send + Sync + 'static,
+        F: Fn(Arc<JellyfinClient>, String, String, u32, FiltersList) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Result<List>> + Send + 'static,
     {
         self.imp().scrolled.connect_end_edge_reached(glib::clone!(
@@ -584,6 +624,7 @@ impl SingleGrid {
                     .map(|f| f.filters_list())
                     .unwrap_or_default();
                 let future = f(
+                    obj.client(),
                     sort_by.to_owned(),
                     sort_order.to_owned(),
                     n_items,
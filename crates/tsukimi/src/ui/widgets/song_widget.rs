@@ -22,6 +22,7 @@ use crate::{
                 CardOptions,
                 select_picture_source,
             },
+            window::Window,
         },
     },
     utils::spawn,
@@ -97,12 +98,26 @@ pub(crate) mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+            klass.install_action_async(
+                "song.play-next",
+                None,
+                |song_widget, _action, _parameter| async move {
+                    song_widget.queue(true).await;
+                },
+            );
+            klass.install_action_async(
+                "song.add-to-queue",
+                None,
+                |song_widget, _action, _parameter| async move {
+                    song_widget.queue(false).await;
+                },
+            );
             klass.install_action_async(
                 "song.editm",
                 None,
                 |window, _action, _parameter| async move {
-                    let id = window.item().id();
-                    let dialog = MetadataDialog::new(&id);
+                    let item = window.item();
+                    let dialog = MetadataDialog::new(&item.id(), item.client());
                     insert_editm_dialog!(window, dialog);
                 },
             );
@@ -111,7 +126,7 @@ pub(crate) mod imp {
                 None,
                 |window, _action, _parameter| async move {
                     let item = window.item();
-                    let dialog = ImageDialog::new(&item.id(), &item.item_type());
+                    let dialog = ImageDialog::new(&item.id(), &item.item_type(), item.client());
                     insert_editm_dialog!(window, dialog);
                 },
             );
@@ -137,6 +152,7 @@ pub(crate) mod imp {
             core_song.set_artist(item.albumartist_name());
             core_song.set_name(item.name());
             core_song.set_id(item.id());
+            core_song.set_client(item.client());
             core_song.set_image_source(select_picture_source(&item, CardOptions::default()));
             core_song.set_duration(item.run_time_ticks() / 10000000);
             obj.set_up();
@@ -219,6 +235,13 @@ impl SongWidget {
         ));
     }
 
+    async fn queue(&self, play_next: bool) {
+        let Some(window) = self.root().and_downcast::<Window>() else {
+            return;
+        };
+        window.queue_songs(vec![self.coresong()], play_next).await;
+    }
+
     fn bind(&self, core_song: &CoreSong) {
         self.bind_property("state", core_song, "state")
             .sync_create()
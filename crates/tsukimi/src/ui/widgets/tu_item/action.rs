@@ -1,8 +1,10 @@
+use std::sync::Arc;
+
 use crate::{
     alert_dialog,
     client::{
         error::UserFacingError,
-        jellyfin_client::JELLYFIN_CLIENT,
+        jellyfin_client::JellyfinClient,
     },
     ui::{
         provider::IS_ADMIN,
@@ -38,6 +40,7 @@ use gtk::{
 use super::TuItemBasic;
 use anyhow::Result;
 
+#[derive(Clone, Copy)]
 pub enum Action {
     Like,
     Unlike,
@@ -46,9 +49,21 @@ pub enum Action {
     Remove,
 }
 
+pub async fn perform_item_action(
+    client: Arc<JellyfinClient>, id: String, series_id: Option<String>, action: &Action,
+) -> Result<()> {
+    match action {
+        Action::Like => client.like(&id).await,
+        Action::Unlike => client.unlike(&id).await,
+        Action::Played => client.set_as_played(&id, series_id).await,
+        Action::Unplayed => client.set_as_unplayed(&id, series_id).await,
+        Action::Remove => client.hide_from_resume(&id, series_id).await,
+    }
+}
+
 pub trait TuItemAction {
     async fn perform_action_inner(
-        id: String, series_id: Option<String>, action: &Action,
+        client: Arc<JellyfinClient>, id: String, series_id: Option<String>, action: &Action,
     ) -> Result<()>;
 
     async fn perform_action(&self, action: Action);
@@ -76,23 +91,20 @@ where
     <T as glib::clone::Downgrade>::Weak: glib::clone::Upgrade<Strong = T>,
 {
     async fn perform_action_inner(
-        id: String, series_id: Option<String>, action: &Action,
+        client: Arc<JellyfinClient>, id: String, series_id: Option<String>, action: &Action,
     ) -> Result<()> {
-        match action {
-            Action::Like => JELLYFIN_CLIENT.like(&id).await,
-            Action::Unlike => JELLYFIN_CLIENT.unlike(&id).await,
-            Action::Played => JELLYFIN_CLIENT.set_as_played(&id, series_id).await,
-            Action::Unplayed => JELLYFIN_CLIENT.set_as_unplayed(&id, series_id).await,
-            Action::Remove => JELLYFIN_CLIENT.hide_from_resume(&id, series_id).await,
-        }
+        perform_item_action(client, id, series_id, action).await
     }
 
     async fn perform_action(&self, action: Action) {
+        let client = self.item().client();
         let id = self.item().id();
         let series_id = self.item().series_id();
         self.update_state(&action);
         let result =
-            spawn_tokio(async move { Self::perform_action_inner(id, series_id, &action).await });
+            spawn_tokio(
+                async move { Self::perform_action_inner(client, id, series_id, &action).await },
+            );
 
         match result.await {
             Ok(_) => self.toast(gettext("Success")),
@@ -205,9 +217,9 @@ where
             "Movie" | "Series" | "Episode" | "MusicVideo" | "AdultVideo" | "Audio" => {
                 self.set_item_action(true, true, true)
             }
-            "MusicAlbum" | "BoxSet" | "Tag" | "Genre" | "Views" | "Person" | "Actor"
-            | "Director" | "Writer" | "Producer" | "GuestStar" | "TvChannel" | "Folder"
-            | "Season" => self.set_item_action(false, true, true),
+            "MusicAlbum" | "MusicArtist" | "BoxSet" | "Tag" | "Genre" | "Views" | "Person"
+            | "Actor" | "Director" | "Writer" | "Producer" | "GuestStar" | "TvChannel"
+            | "Folder" | "Season" => self.set_item_action(false, true, true),
             "CollectionFolder" | "UserView" => self.set_item_action(false, false, false),
             _ => None,
         }
@@ -230,7 +242,10 @@ where
                             async move {
                                 let id = obj.item().id();
                                 let dialog =
-                                    crate::ui::widgets::metadata_dialog::MetadataDialog::new(&id);
+                                    crate::ui::widgets::metadata_dialog::MetadataDialog::new(
+                                        &id,
+                                        obj.item().client(),
+                                    );
                                 crate::insert_editm_dialog!(obj, dialog);
                             }
                         ))
@@ -251,6 +266,7 @@ where
                                 let dialog = crate::ui::widgets::image_dialog::ImageDialog::new(
                                     &item.id(),
                                     &item.item_type(),
+                                    item.client(),
                                 );
                                 crate::insert_editm_dialog!(obj, dialog);
                             }
@@ -270,10 +286,9 @@ where
                             #[weak]
                             obj,
                             async move {
+                                let client = obj.item().client();
                                 let id = obj.item().id();
-                                match spawn_tokio(async move { JELLYFIN_CLIENT.scan(&id).await })
-                                    .await
-                                {
+                                match spawn_tokio(async move { client.scan(&id).await }).await {
                                     Ok(_) => {
                                         obj.toast(gettext("Scanning..."));
                                     }
@@ -300,7 +315,9 @@ where
                                     let id = obj.item().id();
                                     let type_ = obj.item().item_type();
                                     let dialog = crate::ui::widgets::identify::IdentifyDialog::new(
-                                        &id, &type_,
+                                        &id,
+                                        &type_,
+                                        obj.item().client(),
                                     );
                                     crate::insert_editm_dialog!(obj, dialog);
                                 }
@@ -320,7 +337,10 @@ where
                                 async move {
                                     let id = obj.item().id();
                                     let dialog =
-                                        crate::ui::widgets::refresh_dialog::RefreshDialog::new(&id);
+                                        crate::ui::widgets::refresh_dialog::RefreshDialog::new(
+                                            &id,
+                                            obj.item().client(),
+                                        );
                                     crate::insert_editm_dialog!(obj, dialog);
                                 }
                             ))
@@ -452,6 +472,44 @@ where
             }
         }
 
+        if matches!(
+            self.item().item_type().as_str(),
+            "Audio" | "MusicAlbum" | "MusicArtist"
+        ) {
+            action_group.add_action_entries([
+                gio::ActionEntry::builder("play-next")
+                    .activate(glib::clone!(
+                        #[weak(rename_to = obj)]
+                        self,
+                        move |_, _, _| {
+                            spawn(glib::clone!(
+                                #[weak]
+                                obj,
+                                async move {
+                                    obj.item().queue_songs(&obj, true).await;
+                                }
+                            ))
+                        }
+                    ))
+                    .build(),
+                gio::ActionEntry::builder("add-to-queue")
+                    .activate(glib::clone!(
+                        #[weak(rename_to = obj)]
+                        self,
+                        move |_, _, _| {
+                            spawn(glib::clone!(
+                                #[weak]
+                                obj,
+                                async move {
+                                    obj.item().queue_songs(&obj, false).await;
+                                }
+                            ))
+                        }
+                    ))
+                    .build(),
+            ]);
+        }
+
         if self.item().is_resume() {
             action_group.add_action_entries([gio::ActionEntry::builder("remove")
                 .activate(glib::clone!(
@@ -473,17 +531,18 @@ where
     }
 
     async fn delete_item(&self) {
+        let client = self.item().client();
+        let delete_client = client.clone();
         let id = self.item().id();
         let id_clone = id.to_owned();
 
-        let delete_info =
-            match spawn_tokio(async move { JELLYFIN_CLIENT.delete_info(&id).await }).await {
-                Ok(info) => info,
-                Err(e) => {
-                    self.toast(e.to_user_facing());
-                    return;
-                }
-            };
+        let delete_info = match spawn_tokio(async move { client.delete_info(&id).await }).await {
+            Ok(info) => info,
+            Err(e) => {
+                self.toast(e.to_user_facing());
+                return;
+            }
+        };
 
         let alert_dialog = adw::AlertDialog::builder()
             .heading(gettext("Delete Item"))
@@ -507,16 +566,15 @@ where
                 self,
                 move |_, _| {
                     let id_clone = id_clone.to_owned();
+                    let client = delete_client.clone();
 
                     spawn(glib::clone!(
                         #[weak]
                         obj,
                         async move {
-                            match spawn_tokio(async move {
-                                JELLYFIN_CLIENT.delete_item(&id_clone).await
-                            })
-                            .await
-                            .and_then(|r| r.error_for_status().map_err(|e| e.into()))
+                            match spawn_tokio(async move { client.delete_item(&id_clone).await })
+                                .await
+                                .and_then(|r| r.error_for_status().map_err(|e| e.into()))
                             {
                                 Ok(_) => {
                                     obj.toast(gettext("Item deleted"));
@@ -544,11 +602,12 @@ where
 
         use adw::prelude::*;
 
-        let dialog = MissingEpisodesDialog::new(&id);
+        let dialog = MissingEpisodesDialog::new(&id, self.item().client());
         dialog.present(Some(window));
     }
 
     async fn remove_identification(&self) {
+        let client = self.item().client();
         let id = self.item().id();
         let alert_dialog = adw::AlertDialog::builder()
             .heading(gettext("Remove Identification"))
@@ -567,16 +626,15 @@ where
                 self,
                 move |_, _| {
                     let id = id.to_owned();
+                    let client = client.clone();
 
                     spawn(glib::clone!(
                         #[weak]
                         obj,
                         async move {
-                            match spawn_tokio(
-                                async move { JELLYFIN_CLIENT.reset_metadata(&id).await },
-                            )
-                            .await
-                            .and_then(|r| r.error_for_status().map_err(|e| e.into()))
+                            match spawn_tokio(async move { client.reset_metadata(&id).await })
+                                .await
+                                .and_then(|r| r.error_for_status().map_err(|e| e.into()))
                             {
                                 Ok(_) => {
                                     obj.toast(gettext("Item deleted"));
@@ -3,7 +3,11 @@ mod overlay;
 mod prelude;
 mod progressbar_animation;
 
-pub use action::TuItemAction;
+pub use action::{
+    Action,
+    TuItemAction,
+    perform_item_action,
+};
 pub use overlay::{
     CardOptions,
     CardShape,
@@ -122,15 +122,17 @@ fn tagged_source(
         tag,
         image_type,
         image_index,
+        account: None,
     })
 }
 
 pub fn select_backdrop_picture_source(item: &TuItem) -> Option<PictureSource> {
-    if item.item_type() == EPISODE {
+    let source = if item.item_type() == EPISODE {
         parent_backdrop_source(item).or_else(|| current_source(item, BACKDROP, Some(0)))
     } else {
         current_source(item, BACKDROP, Some(0))
-    }
+    };
+    source.map(|source| source.for_account(item.account_key()))
 }
 
 pub fn select_logo_picture_source(item: &SimpleListItem) -> Option<PictureSource> {
@@ -218,6 +220,10 @@ fn album_primary_source(item: &TuItem) -> Option<PictureSource> {
 }
 
 pub fn select_picture_source(item: &TuItem, options: CardOptions) -> Option<PictureSource> {
+    card_picture_source(item, options).map(|source| source.for_account(item.account_key()))
+}
+
+fn card_picture_source(item: &TuItem, options: CardOptions) -> Option<PictureSource> {
     if let Some(url) = item.image_url().filter(|url| !url.trim().is_empty()) {
         return Some(PictureSource::Url { url });
     }
@@ -114,6 +114,8 @@ pub mod imp {
         #[template_child]
         pub folder_mark: TemplateChild<gtk::Button>,
         #[template_child]
+        pub server_badge: TemplateChild<gtk::Label>,
+        #[template_child]
         pub direct_play_button: TemplateChild<gtk::Button>,
 
         #[template_child]
@@ -481,6 +483,13 @@ impl TuListItem {
 
         imp.folder_mark.set_visible(item.has_folder_mark());
 
+        if let Some(server_name) = item.server_name() {
+            imp.server_badge.set_label(&server_name);
+            imp.server_badge.set_visible(true);
+        } else {
+            imp.server_badge.set_visible(false);
+        }
+
         imp.direct_play_button
             .set_visible(item.has_direct_play_mark());
 
@@ -31,7 +31,10 @@ use super::{
     utils::TuItemBuildExt,
 };
 use crate::{
-    client::structs::SimpleListItem,
+    client::{
+        jellyfin_client::JellyfinClient,
+        structs::SimpleListItem,
+    },
     ui::provider::{
         tu_item::TuItem,
         tu_object::TuObject,
@@ -45,7 +48,10 @@ pub(crate) mod imp {
         atomic::AtomicBool,
     };
 
-    use std::cell::Cell;
+    use std::cell::{
+        Cell,
+        OnceCell,
+    };
 
     use glib::subclass::InitializingObject;
     use gtk::glib::Properties;
@@ -84,6 +90,7 @@ pub(crate) mod imp {
         pub spinner_revealer: TemplateChild<gtk::Revealer>,
 
         pub selection: NoSelectionWrap,
+        pub multi_selection: OnceCell<gtk::MultiSelection>,
         pub lock: Arc<AtomicBool>,
 
         #[property(get, set, builder(CardShape::default()))]
@@ -94,6 +101,8 @@ pub(crate) mod imp {
         pub prefer_parent_poster: Cell<bool>,
         #[property(get, set, default = false)]
         pub is_resume: Cell<bool>,
+        #[property(get)]
+        pub selection_mode: Cell<bool>,
         pub resolved_card_shape: Cell<CardShape>,
     }
 
@@ -117,6 +126,9 @@ pub(crate) mod imp {
     impl ObjectImpl for TuViewScrolled {
         fn constructed(&self) {
             self.parent_constructed();
+            let _ = self
+                .multi_selection
+                .set(gtk::MultiSelection::new(self.selection.model()));
             self.obj().set_view_type(ViewType::GridView);
         }
     }
@@ -142,7 +154,21 @@ impl TuViewScrolled {
         glib::Object::new()
     }
 
-    pub fn set_store<const C: bool>(&self, items: Vec<SimpleListItem>) {
+    /// Shows `items`, listed by `client`.
+    pub fn set_store<const C: bool>(
+        &self, items: Vec<SimpleListItem>, client: &Arc<JellyfinClient>,
+    ) {
+        self.set_store_with::<C>(items, |item| TuItem::from_simple(item, client));
+    }
+
+    /// Shows items that don't come from a server, like danmaku search results.
+    pub fn set_local_store<const C: bool>(&self, items: Vec<SimpleListItem>) {
+        self.set_store_with::<C>(items, TuItem::from);
+    }
+
+    fn set_store_with<const C: bool>(
+        &self, items: Vec<SimpleListItem>, to_item: impl Fn(SimpleListItem) -> TuItem,
+    ) {
         let imp = self.imp();
         let Some(store) = imp.selection.model().and_downcast::<gio::ListStore>() else {
             return;
@@ -158,7 +184,7 @@ impl TuViewScrolled {
         let items = items
             .into_iter()
             .map(|item| {
-                let tu_item = TuItem::from_simple(item);
+                let tu_item = to_item(item);
                 tu_item.set_is_resume(is_resume);
                 TuObject::new(tu_item)
             })
@@ -179,14 +205,59 @@ impl TuViewScrolled {
                 imp.scrolled_window.set_child(Some(&imp.grid.get()));
                 imp.grid
                     .set_factory(Some(factory.tu_item(self.card_options())));
-                imp.grid.set_model(Some(&imp.selection.0));
             }
             ViewType::ListView => {
                 imp.scrolled_window.set_child(Some(&imp.list.get()));
                 imp.list.set_factory(Some(
                     factory.tu_overview_item(ViewGroup::ListView, self.card_options()),
                 ));
-                imp.list.set_model(Some(&imp.selection.0));
+            }
+        }
+        self.apply_selection_model();
+    }
+
+    /// Lets the user pick items instead of opening them. Click, ctrl-click and
+    /// shift-click behave like in any other GTK list while this is on.
+    pub fn set_selection_mode(&self, selection_mode: bool) {
+        let imp = self.imp();
+        if imp.selection_mode.replace(selection_mode) == selection_mode {
+            return;
+        }
+        if !selection_mode {
+            self.multi_selection().unselect_all();
+        }
+        self.apply_selection_model();
+        self.notify_selection_mode();
+    }
+
+    pub fn multi_selection(&self) -> gtk::MultiSelection {
+        self.imp()
+            .multi_selection
+            .get()
+            .expect("TuViewScrolled is not constructed")
+            .clone()
+    }
+
+    fn apply_selection_model(&self) {
+        let imp = self.imp();
+        let selection_mode = imp.selection_mode.get();
+        let model = if selection_mode {
+            self.multi_selection().upcast::<gtk::SelectionModel>()
+        } else {
+            imp.selection.0.clone().upcast()
+        };
+
+        imp.grid.set_model(Some(&model));
+        imp.grid.set_single_click_activate(!selection_mode);
+        imp.grid.set_enable_rubberband(selection_mode);
+        imp.list.set_model(Some(&model));
+        imp.list.set_single_click_activate(!selection_mode);
+        imp.list.set_enable_rubberband(selection_mode);
+        for widget in [imp.grid.upcast_ref::<gtk::Widget>(), imp.list.upcast_ref()] {
+            if selection_mode {
+                widget.add_css_class("selection-mode");
+            } else {
+                widget.remove_css_class("selection-mode");
             }
         }
     }
@@ -222,6 +293,9 @@ impl TuViewScrolled {
 
     #[template_callback]
     fn on_gridview_item_activated(&self, position: u32, view: &gtk::GridView) {
+        if self.selection_mode() {
+            return;
+        }
         let Some(model) = view.model() else {
             return;
         };
@@ -233,6 +307,9 @@ impl TuViewScrolled {
 
     #[template_callback]
     fn on_listview_item_activated(&self, position: u32, view: &gtk::ListView) {
+        if self.selection_mode() {
+            return;
+        }
         let Some(model) = view.model() else {
             return;
         };
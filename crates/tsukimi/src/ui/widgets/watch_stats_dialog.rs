@@ -0,0 +1,265 @@
+use std::cell::RefCell;
+
+use adw::{
+    prelude::*,
+    subclass::prelude::*,
+};
+use chrono::{
+    Local,
+    NaiveDate,
+};
+use gettextrs::{
+    gettext,
+    ngettext,
+};
+use gtk::{
+    CompositeTemplate,
+    gio,
+    glib,
+};
+
+use crate::{
+    client::registry::CLIENTS,
+    ui::models::watch_history::{
+        WatchHistory,
+        WatchSession,
+    },
+    utils::spawn,
+};
+
+pub(crate) mod imp {
+    use glib::subclass::InitializingObject;
+
+    use super::*;
+
+    #[derive(CompositeTemplate, Default)]
+    #[template(resource = "/moe/tsuna/tsukimi/ui/watch_stats_dialog.ui")]
+    pub struct WatchStatsDialog {
+        #[template_child]
+        pub toast: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub export_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub today_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub week_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub month_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub current_streak_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub longest_streak_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub daily_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub weekly_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub monthly_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub series_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub genres_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub seasons_group: TemplateChild<adw::PreferencesGroup>,
+
+        pub sessions: RefCell<Vec<WatchSession>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for WatchStatsDialog {
+        const NAME: &'static str = "WatchStatsDialog";
+        type Type = super::WatchStatsDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.install_action_async(
+                "stats.export",
+                Some(glib::VariantTy::STRING),
+                |dialog, _action, target| async move {
+                    if let Some(format) = target.and_then(|t| t.get::<String>()) {
+                        dialog.export(&format).await;
+                    }
+                },
+            );
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for WatchStatsDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            spawn(glib::clone!(
+                #[weak]
+                obj,
+                async move {
+                    obj.load().await;
+                }
+            ));
+        }
+    }
+
+    impl WidgetImpl for WatchStatsDialog {}
+    impl AdwDialogImpl for WatchStatsDialog {}
+}
+
+glib::wrapper! {
+    /// Viewing statistics from the watch history recorded on this device.
+    pub struct WatchStatsDialog(ObjectSubclass<imp::WatchStatsDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for WatchStatsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WatchStatsDialog {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    async fn load(&self) {
+        let imp = self.imp();
+        let account = CLIENTS.active().session().account_key();
+        let sessions = match WatchHistory::load(account.to_owned()).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                self.add_toast(&e.to_string());
+                Vec::new()
+            }
+        };
+        if sessions.is_empty() {
+            imp.stack.set_visible_child_name("empty");
+            return;
+        }
+
+        let stats = match WatchHistory::stats(account, Local::now().date_naive()).await {
+            Ok(stats) => stats,
+            Err(e) => {
+                self.add_toast(&e.to_string());
+                imp.stack.set_visible_child_name("empty");
+                return;
+            }
+        };
+        imp.sessions.replace(sessions);
+        imp.export_button.set_sensitive(true);
+        imp.stack.set_visible_child_name("stats");
+
+        imp.today_row.set_subtitle(&format_watch_time(stats.today));
+        imp.week_row
+            .set_subtitle(&format_watch_time(stats.this_week));
+        imp.month_row
+            .set_subtitle(&format_watch_time(stats.this_month));
+        imp.current_streak_row
+            .set_subtitle(&format_days(stats.current_streak));
+        imp.longest_streak_row
+            .set_subtitle(&format_days(stats.longest_streak));
+
+        let date_rows =
+            |group: &adw::PreferencesGroup, entries: &[(NaiveDate, f64)], format: &str| {
+                for (date, watched) in entries.iter().rev() {
+                    group.add(&stat_row(
+                        &date.format(format).to_string(),
+                        &format_watch_time(*watched),
+                    ));
+                }
+            };
+        date_rows(&imp.daily_group, &stats.daily, "%Y-%m-%d");
+        date_rows(&imp.weekly_group, &stats.weekly, "%G-W%V");
+        date_rows(&imp.monthly_group, &stats.monthly, "%Y-%m");
+
+        let top_rows = |group: &adw::PreferencesGroup, entries: &[(String, f64)]| {
+            group.set_visible(!entries.is_empty());
+            for (name, watched) in entries {
+                group.add(&stat_row(name, &format_watch_time(*watched)));
+            }
+        };
+        top_rows(&imp.series_group, &stats.top_series);
+        top_rows(&imp.genres_group, &stats.top_genres);
+
+        imp.seasons_group
+            .set_visible(!stats.completed_seasons.is_empty());
+        for season in stats.completed_seasons {
+            imp.seasons_group.add(&stat_row(
+                &season.series_name,
+                &gettext("Season {number}").replace("{number}", &season.season.to_string()),
+            ));
+        }
+    }
+
+    async fn export(&self, format: &str) {
+        let (name, content) = {
+            let sessions = self.imp().sessions.borrow();
+            match format {
+                "json" => match WatchHistory::to_json(&sessions) {
+                    Ok(json) => ("watch-history.json", json),
+                    Err(e) => {
+                        self.add_toast(&e.to_string());
+                        return;
+                    }
+                },
+                _ => ("watch-history.csv", WatchHistory::to_csv(&sessions)),
+            }
+        };
+
+        let filedialog = gtk::FileDialog::builder()
+            .modal(true)
+            .title(gettext("Export Watch History"))
+            .initial_name(name)
+            .build();
+
+        let root = self.root().and_downcast::<gtk::Window>();
+        let Ok(file) = filedialog.save_future(root.as_ref()).await else {
+            return;
+        };
+
+        match file
+            .replace_contents_future(
+                content.into_bytes(),
+                None,
+                false,
+                gio::FileCreateFlags::REPLACE_DESTINATION,
+            )
+            .await
+        {
+            Ok(_) => self.add_toast(&gettext("Watch history exported")),
+            Err((_, e)) => self.add_toast(&e.to_string()),
+        }
+    }
+
+    fn add_toast(&self, message: &str) {
+        self.imp().toast.add_toast(adw::Toast::new(message));
+    }
+}
+
+fn stat_row(title: &str, value: &str) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(title)
+        .subtitle(value)
+        .build();
+    row.add_css_class("property");
+    row
+}
+
+fn format_watch_time(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    if minutes < 60 {
+        return gettext("{minutes} min").replace("{minutes}", &minutes.to_string());
+    }
+    gettext("{hours} h {minutes} min")
+        .replace("{hours}", &(minutes / 60).to_string())
+        .replace("{minutes}", &(minutes % 60).to_string())
+}
+
+fn format_days(days: u32) -> String {
+    ngettext("{days} day", "{days} days", days).replace("{days}", &days.to_string())
+}
@@ -150,6 +150,15 @@ mod imp {
             klass.install_action("win.search", None, |obj, _, _| {
                 obj.searchpage();
             });
+            klass.install_action("win.remote-control", None, |obj, _, _| {
+                obj.remote_control();
+            });
+            klass.install_action("win.customize-home", None, |obj, _, _| {
+                obj.customize_home();
+            });
+            klass.install_action("win.watch-stats", None, |obj, _, _| {
+                obj.watch_stats();
+            });
             klass.install_action("win.add-server", None, |obj, _, _| {
                 obj.new_account();
             });
@@ -253,22 +262,26 @@ mod imp {
 
 use super::{
     home::HomePage,
+    home_layout_dialog::HomeLayoutDialog,
     item::{
         ItemPage,
         SelectedVideoSubInfo,
     },
     liked::LikedPage,
+    remote_control_dialog::RemoteControlDialog,
     search::SearchPage,
     server_action_row,
     server_panel::ServerPanel,
     tu_item::PROGRESSBAR_ANIMATION_DURATION,
     utils::GlobalToast,
+    watch_stats_dialog::WatchStatsDialog,
 };
 use crate::{
     APP_ID,
     client::{
         Account,
-        jellyfin_client::JELLYFIN_CLIENT,
+        error::UserFacingError,
+        registry::CLIENTS,
     },
     ui::{
         models::SETTINGS,
@@ -369,6 +382,28 @@ impl Window {
         imp.last_content_list_selection.replace(Some(2));
     }
 
+    pub fn remote_control(&self) {
+        RemoteControlDialog::new(CLIENTS.active(), None).present(Some(self));
+    }
+
+    pub fn watch_stats(&self) {
+        WatchStatsDialog::new().present(Some(self));
+    }
+
+    pub fn customize_home(&self) {
+        let dialog = HomeLayoutDialog::new();
+        dialog.connect_closed(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |_| {
+                if let Some(homepage) = obj.imp().homepage.child().and_downcast::<HomePage>() {
+                    homepage.update(true);
+                }
+            }
+        ));
+        dialog.present(Some(self));
+    }
+
     #[template_callback]
     pub fn on_pop(&self) {
         let imp = self.imp();
@@ -403,10 +438,9 @@ impl Window {
         for account in &accounts {
             if SETTINGS.auto_select_server()
                 && account.servername == SETTINGS.preferred_server()
-                && JELLYFIN_CLIENT.session().account.user_id.is_empty()
+                && CLIENTS.active().session().account.user_id.is_empty()
             {
-                let _ = JELLYFIN_CLIENT.init(account).await;
-                self.reset();
+                self.switch_account(account);
             }
         }
         if accounts.is_empty() {
@@ -505,6 +539,17 @@ impl Window {
         }
     }
 
+    /// Makes `account` the active server and reloads the main view.
+    pub fn switch_account(&self, account: &Account) -> bool {
+        if let Err(e) = CLIENTS.activate(account) {
+            self.toast(e.to_user_facing());
+            return false;
+        }
+        let _ = SETTINGS.set_preferred_server(&account.servername);
+        self.reset();
+        true
+    }
+
     pub fn reset(&self) {
         self.mainpage();
         self.imp().selectlist.set_selected(0);
@@ -518,7 +563,7 @@ impl Window {
                 obj.homepage();
 
                 let source =
-                    spawn_tokio(async move { JELLYFIN_CLIENT.get_user_avatar_source().await })
+                    spawn_tokio(async move { CLIENTS.active().get_user_avatar_source().await })
                         .await;
                 let source = match source {
                     Ok(source) => source,
@@ -549,7 +594,7 @@ impl Window {
 
     pub async fn account_setup(&self) {
         let imp = self.imp();
-        let s = JELLYFIN_CLIENT.session();
+        let s = CLIENTS.active().session();
         imp.namerow.set_title(&s.account.username);
         imp.namerow.set_subtitle(&s.account.servername);
     }
@@ -791,6 +836,13 @@ impl Window {
             .await;
     }
 
+    pub async fn queue_songs(&self, songs: Vec<CoreSong>, play_next: bool) {
+        self.imp()
+            .player_toolbar_box
+            .queue_songs(songs, play_next)
+            .await;
+    }
+
     pub fn play_media(
         &self, selected: Option<SelectedVideoSubInfo>, item: TuItem, episode_list: Vec<TuItem>,
         matcher: Option<String>, start_seconds: f64,
@@ -898,16 +950,8 @@ impl Window {
         if section == *imp.servers_section {
             let section_idx = item.section_index() as usize;
             let accounts = SETTINGS.accounts();
-            if let Some(account) = accounts.get(section_idx).cloned() {
-                spawn(glib::clone!(
-                    #[weak(rename_to = obj)]
-                    self,
-                    async move {
-                        SETTINGS.set_preferred_server(&account.servername).unwrap();
-                        let _ = JELLYFIN_CLIENT.init(&account).await;
-                        obj.reset();
-                    }
-                ));
+            if let Some(account) = accounts.get(section_idx) {
+                self.switch_account(account);
             }
             return;
         }
@@ -10,8 +10,8 @@ use xxhash_rust::xxh3::xxh3_64;
 
 use crate::{
     client::{
-        jellyfin_client::JELLYFIN_CLIENT,
         picture_source::PictureSource,
+        registry::CLIENTS,
         runtime::runtime,
     },
     ui::jellyfin_cache_path,
@@ -248,7 +248,11 @@ pub async fn resolve_picture_file(source: PictureSource) -> Result<gio::File> {
     match source {
         PictureSource::Url { url, .. } => Ok(gio::File::for_uri(&url)),
         source => {
-            let path = spawn_tokio(async move { JELLYFIN_CLIENT.get_image(source).await }).await?;
+            let client = source
+                .account()
+                .and_then(|key| CLIENTS.get_by_key(key))
+                .unwrap_or_else(|| CLIENTS.active());
+            let path = spawn_tokio(async move { client.get_image(source).await }).await?;
             Ok(gio::File::for_path(path))
         }
     }